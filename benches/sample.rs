@@ -13,47 +13,158 @@ fn gen_formulae(c: &mut Criterion) {
     c.bench_function(
         &format!("generate formulae (size {SIZE}, vars {VARS})"),
         |b| {
-            b.iter(|| {
-                learn_ltl::gen_formulae::<VARS>(
-                    SIZE,
-                    (0..VARS as Idx).collect_vec().as_slice(),
-                )
-            });
+            b.iter(|| learn_ltl::gen_formulae(SIZE, (0..VARS as Idx).collect_vec().as_slice()));
         },
     );
 }
 
 fn solve_sample(c: &mut Criterion) {
-    let file = File::open("_sample_0077.ron").expect("open file");
+    let file = File::open("SATcomparison/abscence/0000.ron").expect("open file");
     let mut buf_reader = BufReader::new(file);
     let mut contents = Vec::new();
     buf_reader.read_to_end(&mut contents).expect("read");
-    let sample = ron::de::from_bytes::<Sample<3>>(&contents).expect("sample");
+    let sample = ron::de::from_bytes::<Sample<2>>(&contents).expect("sample");
+
+    c.bench_function("solve sample abscence 0000", |b| {
+        b.iter(|| solve(black_box(&sample), &SolveOptions::default()))
+    });
 
-    c.bench_function("solve sample 0077", |b| {
-        b.iter(|| par_brute_solve(black_box(&sample), false))
+    c.bench_function("solve bottom-up sample abscence 0000", |b| {
+        b.iter(|| solve_bottom_up(black_box(&sample), &SolveOptions::default()))
     });
 
-    let file = File::open("_sample_0197.ron").expect("open file");
+    c.bench_function("solve sat sample abscence 0000", |b| {
+        b.iter(|| solve_sat(black_box(&sample), &SolveOptions::default()))
+    });
+
+    let file = File::open("SATcomparison/abscence/0003.ron").expect("open file");
     let mut buf_reader = BufReader::new(file);
     let mut contents = Vec::new();
     buf_reader.read_to_end(&mut contents).expect("read");
-    let sample = ron::de::from_bytes::<Sample<3>>(&contents).expect("sample");
+    let sample = ron::de::from_bytes::<Sample<2>>(&contents).expect("sample");
+
+    c.bench_function("solve sample abscence 0003", |b| {
+        b.iter(|| solve(black_box(&sample), &SolveOptions::default()))
+    });
 
-    c.bench_function("solve sample 0197", |b| {
-        b.iter(|| par_brute_solve(black_box(&sample), false))
+    c.bench_function("solve bottom-up sample abscence 0003", |b| {
+        b.iter(|| solve_bottom_up(black_box(&sample), &SolveOptions::default()))
     });
 
-    let file = File::open("_sample_tbth0000.ron").expect("open file");
+    c.bench_function("solve sat sample abscence 0003", |b| {
+        b.iter(|| solve_sat(black_box(&sample), &SolveOptions::default()))
+    });
+
+    let file = File::open("SATcomparison/universality/0000.ron").expect("open file");
     let mut buf_reader = BufReader::new(file);
     let mut contents = Vec::new();
     buf_reader.read_to_end(&mut contents).expect("read");
-    let sample = ron::de::from_bytes::<Sample<3>>(&contents).expect("sample");
+    let sample = ron::de::from_bytes::<Sample<2>>(&contents).expect("sample");
+
+    c.bench_function("solve sample universality 0000", |b| {
+        b.iter(|| solve(black_box(&sample), &SolveOptions::default()))
+    });
 
-    c.bench_function("solve sample tbth 0000", |b| {
-        b.iter(|| par_brute_solve(black_box(&sample), false))
+    c.bench_function("solve bottom-up sample universality 0000", |b| {
+        b.iter(|| solve_bottom_up(black_box(&sample), &SolveOptions::default()))
     });
+
+    c.bench_function("solve sat sample universality 0000", |b| {
+        b.iter(|| solve_sat(black_box(&sample), &SolveOptions::default()))
+    });
+}
+
+fn eval_sample(c: &mut Criterion) {
+    const SAMPLES: [&str; 3] = [
+        "bank_transaction_1250",
+        "chinese_wall_policy_1250",
+        "glba_6802_1250",
+    ];
+    // Formulae with nested temporal operators are where the recursive evaluation is most expensive.
+    const FORMULAE: [&str; 3] = [
+        "G((x1)→(F(x0)))",
+        "G(F((x0)U(x1)))",
+        "(¬(x0))U(G((x1)U(x0)))",
+    ];
+    // Length of the long trace obtained by concatenating the traces of the sample.
+    const LONG_TRACE: usize = 2000;
+
+    for name in SAMPLES {
+        let file = File::open(format!("SySLite-comparison/{name}.ron")).expect("open file");
+        let mut buf_reader = BufReader::new(file);
+        let mut contents = Vec::new();
+        buf_reader.read_to_end(&mut contents).expect("read");
+        let sample = ron::de::from_bytes::<DynSample>(&contents).expect("sample");
+        let traces = sample
+            .positive_traces
+            .iter()
+            .chain(&sample.negative_traces)
+            .collect_vec();
+        let long_trace = traces
+            .iter()
+            .flat_map(|trace| trace.iter().cloned())
+            .take(LONG_TRACE)
+            .collect_vec();
+
+        for formula in FORMULAE {
+            let formula = formula.parse::<SyntaxTree>().expect("formula");
+            let mut group = c.benchmark_group(format!("eval {formula} on {name}"));
+
+            group.bench_function("recursive", |b| {
+                b.iter(|| {
+                    traces
+                        .iter()
+                        .filter(|trace| black_box(&formula).eval(trace))
+                        .count()
+                })
+            });
+
+            group.bench_function("dynamic programming", |b| {
+                b.iter(|| {
+                    traces
+                        .iter()
+                        .filter(|trace| black_box(&formula).eval_all_times(trace)[0])
+                        .count()
+                })
+            });
+
+            group.bench_function("recursive is_consistent", |b| {
+                b.iter(|| {
+                    sample
+                        .positive_traces
+                        .iter()
+                        .all(|trace| black_box(&formula).eval(trace))
+                        && sample
+                            .negative_traces
+                            .iter()
+                            .all(|trace| !black_box(&formula).eval(trace))
+                })
+            });
+
+            group.bench_function("dynamic programming is_consistent", |b| {
+                b.iter(|| sample.is_consistent(black_box(&formula)))
+            });
+
+            group.bench_function(
+                format!("recursive at all times (length {LONG_TRACE})"),
+                |b| {
+                    b.iter(|| {
+                        (0..long_trace.len())
+                            .filter(|time| black_box(&formula).eval_at_time(&long_trace, *time))
+                            .count()
+                    })
+                },
+            );
+
+            group.bench_function(
+                format!("dynamic programming at all times (length {LONG_TRACE})"),
+                |b| b.iter(|| black_box(&formula).eval_all_times(&long_trace)),
+            );
+
+            group.finish();
+        }
+    }
 }
 
-criterion_group!(benches, gen_formulae, solve_sample);
+criterion_group!(benches, gen_formulae, solve_sample, eval_sample);
 criterion_main!(benches);
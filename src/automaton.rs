@@ -0,0 +1,912 @@
+use crate::syntax::*;
+use crate::trace::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{hash_map::Entry, BTreeSet, HashMap, VecDeque};
+use std::fmt::{self, Write};
+use std::sync::Arc;
+
+/// A deterministic finite automaton accepting the (non-empty) traces satisfying a formula.
+///
+/// Its alphabet is made of the values of the propositional variables it reads (those appearing in the formula),
+/// and each letter is represented by the bitmask of the true ones (the `n`-th variable of `vars` being the `n`-th bit).
+/// Other variables are ignored when running the automaton on a trace.
+///
+/// The automaton is a plain table, so that it can be serialized and run without this crate.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Dfa {
+    /// The indexes of the propositional variables read by the automaton, in increasing order.
+    pub vars: Vec<Idx>,
+    /// The initial state.
+    pub initial: usize,
+    /// Whether each state is accepting.
+    pub accepting: Vec<bool>,
+    /// `transitions[state][letter]` is the state reached from `state` by reading `letter`.
+    pub transitions: Vec<Vec<usize>>,
+}
+
+/// The error returned when an automaton would read more than [`Dfa::MAX_VARS`] variables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TooManyVars {
+    /// The number of variables the automaton would read.
+    pub vars: usize,
+}
+
+impl fmt::Display for TooManyVars {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "automata read at most {} variables, but {} are needed",
+            Dfa::MAX_VARS,
+            self.vars
+        )
+    }
+}
+
+impl std::error::Error for TooManyVars {}
+
+impl SyntaxTree {
+    /// Compiles the formula into the minimal deterministic finite automaton accepting the traces satisfying it,
+    /// according to the LTLf semantics of [`SyntaxTree::eval`].
+    /// The automaton reads only the variables appearing in the formula, which can be at most [`Dfa::MAX_VARS`].
+    ///
+    /// Minimal automata are numbered canonically (by breadth-first search from the initial state),
+    /// so that two formulae using the same variables are equivalent if and only if their automata are equal.
+    /// The translation can take time and space exponential in the size of the formula
+    /// (and in the ends of the intervals of bounded operators).
+    pub fn to_dfa(&self) -> Result<Dfa, TooManyVars> {
+        let vars = self.used_vars();
+        Dfa::check_vars(vars.len())?;
+        Ok(Tableau::new(&self.without_bounded_operators(), &vars)
+            .determinize(vars)
+            .minimize())
+    }
+
+    /// Rewrites bounded operators into nested (weak) next operators, e.g., `F[1,2] φ ≡ X (φ ∨ X φ)`.
+    fn without_bounded_operators(&self) -> SyntaxTree {
+        let rewrite = |branch: &Arc<SyntaxTree>| Arc::new(branch.without_bounded_operators());
+        match self {
+            SyntaxTree::Atom(_) | SyntaxTree::True | SyntaxTree::False => self.clone(),
+            SyntaxTree::Not(branch) => SyntaxTree::Not(rewrite(branch)),
+            SyntaxTree::Next(branch) => SyntaxTree::Next(rewrite(branch)),
+            SyntaxTree::WeakNext(branch) => SyntaxTree::WeakNext(rewrite(branch)),
+            SyntaxTree::Globally(branch) => SyntaxTree::Globally(rewrite(branch)),
+            SyntaxTree::Finally(branch) => SyntaxTree::Finally(rewrite(branch)),
+            SyntaxTree::Yesterday(branch) => SyntaxTree::Yesterday(rewrite(branch)),
+            SyntaxTree::Once(branch) => SyntaxTree::Once(rewrite(branch)),
+            SyntaxTree::Historically(branch) => SyntaxTree::Historically(rewrite(branch)),
+            // G[a,b] φ ≡ N^a (φ ∧ N (φ ∧ ...)), with b-a nested N in the parenthesis
+            SyntaxTree::BoundedGlobally(interval, branch) => {
+                let branch = rewrite(branch);
+                let window = (interval.start..interval.end).fold(branch.clone(), |acc, _| {
+                    Arc::new(SyntaxTree::And(
+                        branch.clone(),
+                        Arc::new(SyntaxTree::WeakNext(acc)),
+                    ))
+                });
+                (0..interval.start)
+                    .fold(window, |acc, _| Arc::new(SyntaxTree::WeakNext(acc)))
+                    .as_ref()
+                    .clone()
+            }
+            // F[a,b] φ ≡ X^a (φ ∨ X (φ ∨ ...)), with b-a nested X in the parenthesis
+            SyntaxTree::BoundedFinally(interval, branch) => {
+                let branch = rewrite(branch);
+                let window = (interval.start..interval.end).fold(branch.clone(), |acc, _| {
+                    Arc::new(SyntaxTree::Or(
+                        branch.clone(),
+                        Arc::new(SyntaxTree::Next(acc)),
+                    ))
+                });
+                (0..interval.start)
+                    .fold(window, |acc, _| Arc::new(SyntaxTree::Next(acc)))
+                    .as_ref()
+                    .clone()
+            }
+            SyntaxTree::And(left_branch, right_branch) => {
+                SyntaxTree::And(rewrite(left_branch), rewrite(right_branch))
+            }
+            SyntaxTree::Or(left_branch, right_branch) => {
+                SyntaxTree::Or(rewrite(left_branch), rewrite(right_branch))
+            }
+            SyntaxTree::Implies(left_branch, right_branch) => {
+                SyntaxTree::Implies(rewrite(left_branch), rewrite(right_branch))
+            }
+            SyntaxTree::Until(left_branch, right_branch) => {
+                SyntaxTree::Until(rewrite(left_branch), rewrite(right_branch))
+            }
+            SyntaxTree::Release(left_branch, right_branch) => {
+                SyntaxTree::Release(rewrite(left_branch), rewrite(right_branch))
+            }
+            SyntaxTree::WeakUntil(left_branch, right_branch) => {
+                SyntaxTree::WeakUntil(rewrite(left_branch), rewrite(right_branch))
+            }
+            SyntaxTree::Since(left_branch, right_branch) => {
+                SyntaxTree::Since(rewrite(left_branch), rewrite(right_branch))
+            }
+            // φ U[0,0] ψ ≡ ψ, φ U[0,b] ψ ≡ ψ ∨ (φ ∧ X(φ U[0,b-1] ψ)) and φ U[a,b] ψ ≡ φ ∧ X(φ U[a-1,b-1] ψ)
+            SyntaxTree::BoundedUntil(interval, left_branch, right_branch) => {
+                let (left_branch, right_branch) = (rewrite(left_branch), rewrite(right_branch));
+                let step = |acc| {
+                    Arc::new(SyntaxTree::And(
+                        left_branch.clone(),
+                        Arc::new(SyntaxTree::Next(acc)),
+                    ))
+                };
+                let window = (interval.start..interval.end).fold(right_branch.clone(), |acc, _| {
+                    Arc::new(SyntaxTree::Or(right_branch.clone(), step(acc)))
+                });
+                (0..interval.start)
+                    .fold(window, |acc, _| step(acc))
+                    .as_ref()
+                    .clone()
+            }
+        }
+    }
+}
+
+/// A subformula of a `Tableau`, with its branches given by their indexes.
+#[derive(Debug, Clone, Copy)]
+enum Node {
+    /// The position of the variable in the alphabet.
+    Atom(usize),
+    True,
+    False,
+    Not(usize),
+    And(usize, usize),
+    Or(usize, usize),
+    Implies(usize, usize),
+    /// Past operators, whose value depends on that of the given subformula at the previous time instant.
+    Past(PastOp, usize, usize),
+    /// Future operators, whose value depends on that of the given subformula at the next time instant,
+    /// which is guessed, and then checked once the next state of the trace is read.
+    Future(FutureOp, usize, usize),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum PastOp {
+    Yesterday,
+    Once,
+    Historically,
+    Since,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FutureOp {
+    Next,
+    WeakNext,
+    Globally,
+    Finally,
+    Until,
+    Release,
+    WeakUntil,
+}
+
+impl FutureOp {
+    /// Weak operators hold at the end of the trace whenever possible, strong ones do not.
+    fn is_weak(self) -> bool {
+        matches!(
+            self,
+            FutureOp::WeakNext | FutureOp::Globally | FutureOp::Release | FutureOp::WeakUntil
+        )
+    }
+}
+
+/// A state of the non-deterministic automaton of the tableau:
+/// the values of the subformulae of past operators at the last time instant read,
+/// followed by the guessed values of the subformulae of future operators at the next one.
+/// The initial state, before reading anything, is `None`.
+type TableauState = Option<Vec<bool>>;
+
+/// The subformulae of a formula (without bounded operators), ordered so that branches come before their parents,
+/// from which a non-deterministic automaton is built, guessing the value of future operators one step ahead.
+struct Tableau {
+    nodes: Vec<Node>,
+    root: usize,
+    /// Indexes of the nodes of past and future operators.
+    past: Vec<usize>,
+    future: Vec<usize>,
+}
+
+impl Tableau {
+    /// Builds the tableau of the formula, reading the given variables.
+    fn new(formula: &SyntaxTree, vars: &[Idx]) -> Self {
+        let mut tableau = Tableau {
+            nodes: Vec::new(),
+            root: 0,
+            past: Vec::new(),
+            future: Vec::new(),
+        };
+        let mut indexes = HashMap::new();
+        tableau.root = tableau.add(formula, vars, &mut indexes);
+        tableau
+    }
+
+    fn add<'a>(
+        &mut self,
+        formula: &'a SyntaxTree,
+        vars: &[Idx],
+        indexes: &mut HashMap<&'a SyntaxTree, usize>,
+    ) -> usize {
+        if let Some(idx) = indexes.get(formula) {
+            return *idx;
+        }
+        let node = match formula {
+            SyntaxTree::Atom(var) => {
+                Node::Atom(vars.binary_search(var).expect("variable of the alphabet"))
+            }
+            SyntaxTree::True => Node::True,
+            SyntaxTree::False => Node::False,
+            SyntaxTree::Not(branch) => Node::Not(self.add(branch, vars, indexes)),
+            SyntaxTree::Next(branch) => self.unary_future(FutureOp::Next, branch, vars, indexes),
+            SyntaxTree::WeakNext(branch) => {
+                self.unary_future(FutureOp::WeakNext, branch, vars, indexes)
+            }
+            SyntaxTree::Globally(branch) => {
+                self.unary_future(FutureOp::Globally, branch, vars, indexes)
+            }
+            SyntaxTree::Finally(branch) => {
+                self.unary_future(FutureOp::Finally, branch, vars, indexes)
+            }
+            SyntaxTree::Yesterday(branch) => {
+                let branch = self.add(branch, vars, indexes);
+                Node::Past(PastOp::Yesterday, branch, branch)
+            }
+            SyntaxTree::Once(branch) => {
+                let branch = self.add(branch, vars, indexes);
+                Node::Past(PastOp::Once, branch, branch)
+            }
+            SyntaxTree::Historically(branch) => {
+                let branch = self.add(branch, vars, indexes);
+                Node::Past(PastOp::Historically, branch, branch)
+            }
+            SyntaxTree::And(left_branch, right_branch) => Node::And(
+                self.add(left_branch, vars, indexes),
+                self.add(right_branch, vars, indexes),
+            ),
+            SyntaxTree::Or(left_branch, right_branch) => Node::Or(
+                self.add(left_branch, vars, indexes),
+                self.add(right_branch, vars, indexes),
+            ),
+            SyntaxTree::Implies(left_branch, right_branch) => Node::Implies(
+                self.add(left_branch, vars, indexes),
+                self.add(right_branch, vars, indexes),
+            ),
+            SyntaxTree::Until(left_branch, right_branch) => Node::Future(
+                FutureOp::Until,
+                self.add(left_branch, vars, indexes),
+                self.add(right_branch, vars, indexes),
+            ),
+            SyntaxTree::Release(left_branch, right_branch) => Node::Future(
+                FutureOp::Release,
+                self.add(left_branch, vars, indexes),
+                self.add(right_branch, vars, indexes),
+            ),
+            SyntaxTree::WeakUntil(left_branch, right_branch) => Node::Future(
+                FutureOp::WeakUntil,
+                self.add(left_branch, vars, indexes),
+                self.add(right_branch, vars, indexes),
+            ),
+            SyntaxTree::Since(left_branch, right_branch) => Node::Past(
+                PastOp::Since,
+                self.add(left_branch, vars, indexes),
+                self.add(right_branch, vars, indexes),
+            ),
+            SyntaxTree::BoundedGlobally(..)
+            | SyntaxTree::BoundedFinally(..)
+            | SyntaxTree::BoundedUntil(..) => {
+                unreachable!("bounded operators are rewritten before building the tableau")
+            }
+        };
+        let idx = self.nodes.len();
+        match node {
+            Node::Past(..) => self.past.push(idx),
+            Node::Future(..) => self.future.push(idx),
+            _ => {}
+        }
+        self.nodes.push(node);
+        indexes.insert(formula, idx);
+        idx
+    }
+
+    fn unary_future<'a>(
+        &mut self,
+        op: FutureOp,
+        branch: &'a SyntaxTree,
+        vars: &[Idx],
+        indexes: &mut HashMap<&'a SyntaxTree, usize>,
+    ) -> Node {
+        let branch = self.add(branch, vars, indexes);
+        Node::Future(op, branch, branch)
+    }
+
+    /// The states reached from `state` by reading `letter`, one for each consistent guess of the future.
+    fn successors(&self, state: &TableauState, letter: usize) -> Vec<Vec<bool>> {
+        let mut successors = Vec::new();
+        let mut values = vec![false; self.nodes.len()];
+        for guess in 0..1usize << self.future.len() {
+            let guessed = |idx: usize| {
+                let pos = self
+                    .future
+                    .iter()
+                    .position(|node| *node == idx)
+                    .expect("future node");
+                guess & (1 << pos) != 0
+            };
+            // Values of past operators at the previous time instant, if any.
+            let previous = |idx: usize| {
+                state.as_ref().map(|state| {
+                    let pos = self
+                        .past
+                        .iter()
+                        .position(|node| *node == idx)
+                        .expect("past node");
+                    state[pos]
+                })
+            };
+            for (idx, node) in self.nodes.iter().enumerate() {
+                values[idx] = match *node {
+                    Node::Atom(var) => letter & (1 << var) != 0,
+                    Node::True => true,
+                    Node::False => false,
+                    Node::Not(branch) => !values[branch],
+                    Node::And(left, right) => values[left] && values[right],
+                    Node::Or(left, right) => values[left] || values[right],
+                    Node::Implies(left, right) => !values[left] || values[right],
+                    Node::Past(op, left, right) => match op {
+                        PastOp::Yesterday => previous(idx).unwrap_or(false),
+                        PastOp::Once => values[left] || previous(idx).unwrap_or(false),
+                        PastOp::Historically => values[left] && previous(idx).unwrap_or(true),
+                        PastOp::Since => {
+                            values[right] || (values[left] && previous(idx).unwrap_or(false))
+                        }
+                    },
+                    Node::Future(op, left, right) => {
+                        let next = guessed(idx);
+                        match op {
+                            FutureOp::Next | FutureOp::WeakNext => next,
+                            FutureOp::Globally => values[left] && next,
+                            FutureOp::Finally => values[left] || next,
+                            FutureOp::Until | FutureOp::WeakUntil => {
+                                values[right] || (values[left] && next)
+                            }
+                            FutureOp::Release => values[right] && (values[left] || next),
+                        }
+                    }
+                };
+            }
+            // The guesses made at the previous time instant have to be right,
+            // and the formula has to hold at the start of the trace.
+            let consistent = match state {
+                None => values[self.root],
+                Some(state) => self.future.iter().enumerate().all(|(pos, idx)| {
+                    let value = match self.nodes[*idx] {
+                        Node::Future(FutureOp::Next | FutureOp::WeakNext, branch, _) => {
+                            values[branch]
+                        }
+                        _ => values[*idx],
+                    };
+                    state[self.past.len() + pos] == value
+                }),
+            };
+            if consistent {
+                // Yesterday remembers the value of its argument, the other past operators their own.
+                let past = self.past.iter().map(|idx| match self.nodes[*idx] {
+                    Node::Past(PastOp::Yesterday, branch, _) => values[branch],
+                    _ => values[*idx],
+                });
+                let future = (0..self.future.len()).map(|pos| guess & (1 << pos) != 0);
+                successors.push(past.chain(future).collect());
+            }
+        }
+        successors
+    }
+
+    /// A trace can end in a state if the guesses about the next time instant agree with there being none:
+    /// weak operators have to be guessed to hold, and strong ones not to.
+    fn is_final(&self, state: &TableauState) -> bool {
+        state.as_ref().is_some_and(|state| {
+            self.future
+                .iter()
+                .enumerate()
+                .all(|(pos, idx)| match self.nodes[*idx] {
+                    Node::Future(op, _, _) => state[self.past.len() + pos] == op.is_weak(),
+                    _ => unreachable!("future node"),
+                })
+        })
+    }
+
+    /// Subset construction, over the letters of the given variables.
+    fn determinize(&self, vars: Vec<Idx>) -> Dfa {
+        let initial: BTreeSet<TableauState> = BTreeSet::from([None]);
+        let mut indexes = HashMap::from([(initial.clone(), 0)]);
+        let mut queue = VecDeque::from([initial]);
+        let mut dfa = Dfa {
+            vars,
+            initial: 0,
+            accepting: Vec::new(),
+            transitions: Vec::new(),
+        };
+        while let Some(states) = queue.pop_front() {
+            dfa.accepting
+                .push(states.iter().any(|state| self.is_final(state)));
+            let transitions = (0..dfa.letters())
+                .map(|letter| {
+                    let successors: BTreeSet<TableauState> = states
+                        .iter()
+                        .flat_map(|state| self.successors(state, letter))
+                        .map(Some)
+                        .collect();
+                    let next = indexes.len();
+                    *indexes.entry(successors.clone()).or_insert_with(|| {
+                        queue.push_back(successors);
+                        next
+                    })
+                })
+                .collect();
+            dfa.transitions.push(transitions);
+        }
+        dfa
+    }
+}
+
+impl Dfa {
+    /// The largest number of variables an automaton can read:
+    /// as its table has a column for each of the `2^vars` letters, larger alphabets would hardly fit in memory.
+    pub const MAX_VARS: usize = 20;
+
+    fn check_vars(vars: usize) -> Result<(), TooManyVars> {
+        if vars > Self::MAX_VARS {
+            Err(TooManyVars { vars })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// The number of states of the automaton.
+    pub fn states(&self) -> usize {
+        self.accepting.len()
+    }
+
+    /// The number of letters of the alphabet.
+    fn letters(&self) -> usize {
+        1 << self.vars.len()
+    }
+
+    /// The letter corresponding to a state of a trace.
+    fn letter(&self, state: &[bool]) -> usize {
+        self.vars
+            .iter()
+            .enumerate()
+            .filter(|(_, var)| state[**var as usize])
+            .map(|(bit, _)| 1 << bit)
+            .sum()
+    }
+
+    /// The state of a trace corresponding to a letter, where the variables not read by the automaton are false.
+    fn state(&self, letter: usize) -> Vec<bool> {
+        let mut state = vec![false; self.vars.last().map_or(0, |var| *var as usize + 1)];
+        for (bit, var) in self.vars.iter().enumerate() {
+            state[*var as usize] = letter & (1 << bit) != 0;
+        }
+        state
+    }
+
+    /// The state reached from `state` by reading a state of a trace.
+    pub fn next(&self, state: usize, tuple: &[bool]) -> usize {
+        self.transitions[state][self.letter(tuple)]
+    }
+
+    /// Runs the automaton on a trace, returning whether it is accepted.
+    pub fn accepts<S: AsRef<[bool]>>(&self, trace: &[S]) -> bool {
+        let state = trace.iter().fold(self.initial, |state, tuple| {
+            self.next(state, tuple.as_ref())
+        });
+        self.accepting[state]
+    }
+
+    /// Returns the minimal automaton accepting the same traces,
+    /// with its (reachable) states numbered by breadth-first search from the initial one.
+    pub fn minimize(&self) -> Dfa {
+        // Moore's algorithm: states are split by acceptance, then by the classes their letters lead to, until stable.
+        let mut classes: Vec<usize> = self
+            .accepting
+            .iter()
+            .map(|accepting| *accepting as usize)
+            .collect();
+        let mut count = 0;
+        loop {
+            let mut signatures = HashMap::new();
+            let refined: Vec<usize> = (0..self.states())
+                .map(|state| {
+                    let signature = (
+                        classes[state],
+                        self.transitions[state]
+                            .iter()
+                            .map(|next| classes[*next])
+                            .collect::<Vec<_>>(),
+                    );
+                    let next = signatures.len();
+                    *signatures.entry(signature).or_insert(next)
+                })
+                .collect();
+            classes = refined;
+            if signatures.len() == count {
+                break;
+            }
+            count = signatures.len();
+        }
+
+        // Canonical numbering of the classes, by breadth-first search.
+        let mut numbers = HashMap::from([(classes[self.initial], 0)]);
+        let mut representatives = vec![self.initial];
+        let mut idx = 0;
+        while idx < representatives.len() {
+            let state = representatives[idx];
+            for next in &self.transitions[state] {
+                if let Entry::Vacant(entry) = numbers.entry(classes[*next]) {
+                    entry.insert(representatives.len());
+                    representatives.push(*next);
+                }
+            }
+            idx += 1;
+        }
+        Dfa {
+            vars: self.vars.clone(),
+            initial: 0,
+            accepting: representatives
+                .iter()
+                .map(|state| self.accepting[*state])
+                .collect(),
+            transitions: representatives
+                .iter()
+                .map(|state| {
+                    self.transitions[*state]
+                        .iter()
+                        .map(|next| numbers[&classes[*next]])
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+
+    /// Extends the alphabet of the automaton with the given variables, whose values are ignored.
+    pub fn with_vars(&self, vars: &[Idx]) -> Result<Dfa, TooManyVars> {
+        let mut extended = self.vars.clone();
+        extended.extend(vars);
+        extended.sort_unstable();
+        extended.dedup();
+        Self::check_vars(extended.len())?;
+        // The bit of each variable of the automaton in the extended letters.
+        let bits = self
+            .vars
+            .iter()
+            .map(|var| extended.binary_search(var).expect("extended variable"))
+            .collect::<Vec<_>>();
+        let letter = |extended_letter: usize| -> usize {
+            bits.iter()
+                .enumerate()
+                .filter(|(_, bit)| extended_letter & (1 << **bit) != 0)
+                .map(|(old_bit, _)| 1 << old_bit)
+                .sum()
+        };
+        let letters = 1 << extended.len();
+        Ok(Dfa {
+            vars: extended,
+            initial: self.initial,
+            accepting: self.accepting.clone(),
+            transitions: self
+                .transitions
+                .iter()
+                .map(|transitions| {
+                    (0..letters)
+                        .map(|extended_letter| transitions[letter(extended_letter)])
+                        .collect()
+                })
+                .collect(),
+        })
+    }
+
+    /// A shortest trace accepted by the automaton, if any.
+    pub fn accepted_trace(&self) -> Option<DynTrace> {
+        self.shortest_trace(|state| self.accepting[state])
+    }
+
+    /// A shortest (non-empty) trace rejected by the automaton, if any.
+    pub fn rejected_trace(&self) -> Option<DynTrace> {
+        self.shortest_trace(|state| !self.accepting[state])
+    }
+
+    /// Whether the automaton accepts no trace, i.e., its formula is unsatisfiable.
+    pub fn is_empty(&self) -> bool {
+        self.accepted_trace().is_none()
+    }
+
+    /// Whether the automaton accepts every (non-empty) trace, i.e., its formula is valid.
+    pub fn is_universal(&self) -> bool {
+        self.rejected_trace().is_none()
+    }
+
+    /// A shortest trace accepted by the automaton but not by the other one, if any.
+    /// Both automata are run over the variables read by either, which can be at most [`Dfa::MAX_VARS`].
+    pub fn difference_trace(&self, other: &Dfa) -> Result<Option<DynTrace>, TooManyVars> {
+        let product = self.with_vars(&other.vars)?.product(
+            &other.with_vars(&self.vars)?,
+            |accepting, other_accepting| accepting && !other_accepting,
+        );
+        Ok(product.accepted_trace())
+    }
+
+    /// The automaton running both automata (over the same alphabet) in parallel,
+    /// accepting according to `accepting` applied to their acceptance.
+    fn product(&self, other: &Dfa, accepting: impl Fn(bool, bool) -> bool) -> Dfa {
+        assert_eq!(self.vars, other.vars, "automata over different alphabets");
+        let initial = (self.initial, other.initial);
+        let mut indexes = HashMap::from([(initial, 0)]);
+        let mut pairs = vec![initial];
+        let mut product = Dfa {
+            vars: self.vars.clone(),
+            initial: 0,
+            accepting: Vec::new(),
+            transitions: Vec::new(),
+        };
+        let mut idx = 0;
+        while idx < pairs.len() {
+            let (state, other_state) = pairs[idx];
+            product.accepting.push(accepting(
+                self.accepting[state],
+                other.accepting[other_state],
+            ));
+            let transitions = (0..self.letters())
+                .map(|letter| {
+                    let next = (
+                        self.transitions[state][letter],
+                        other.transitions[other_state][letter],
+                    );
+                    let len = pairs.len();
+                    *indexes.entry(next).or_insert_with(|| {
+                        pairs.push(next);
+                        len
+                    })
+                })
+                .collect();
+            product.transitions.push(transitions);
+            idx += 1;
+        }
+        product
+    }
+
+    /// A shortest non-empty trace leading to a state satisfying `target`, found by breadth-first search.
+    fn shortest_trace(&self, target: impl Fn(usize) -> bool) -> Option<DynTrace> {
+        // For each state reached by a non-empty trace, the letter read last on a shortest one,
+        // and the state it was read from (`None` standing for the start of the trace).
+        let mut parents: Vec<Option<(Option<usize>, usize)>> = vec![None; self.states()];
+        let mut queue = VecDeque::new();
+        for letter in 0..self.letters() {
+            let next = self.transitions[self.initial][letter];
+            if parents[next].is_none() {
+                parents[next] = Some((None, letter));
+                queue.push_back(next);
+            }
+        }
+        while let Some(state) = queue.pop_front() {
+            if target(state) {
+                let mut trace = Vec::new();
+                let mut current = Some(state);
+                while let Some(state) = current {
+                    let (parent, letter) = parents[state].expect("reached state");
+                    trace.push(self.state(letter));
+                    current = parent;
+                }
+                trace.reverse();
+                return Some(trace);
+            }
+            for letter in 0..self.letters() {
+                let next = self.transitions[state][letter];
+                if parents[next].is_none() {
+                    parents[next] = Some((Some(state), letter));
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+
+    /// Exports the automaton in the DOT format of Graphviz, with variables named `x0`, `x1`, ...
+    pub fn to_dot(&self) -> String {
+        let vars = (0..self.vars.last().map_or(0, |var| *var + 1))
+            .map(|var| format!("x{var}"))
+            .collect::<Vec<_>>();
+        self.to_dot_w_named_vars(&vars)
+    }
+
+    /// Exports the automaton in the DOT format of Graphviz, with the given variable names (indexed as in a sample).
+    /// Each edge is labeled by the letters leading through it, each written as a conjunction of literals.
+    pub fn to_dot_w_named_vars(&self, vars: &[String]) -> String {
+        let mut dot = String::from("digraph {\n    rankdir=LR;\n    node [shape=circle];\n");
+        writeln!(
+            dot,
+            "    init [shape=point];\n    init -> {};",
+            self.initial
+        )
+        .expect("write");
+        for (state, accepting) in self.accepting.iter().enumerate() {
+            if *accepting {
+                writeln!(dot, "    {state} [shape=doublecircle];").expect("write");
+            }
+        }
+        for (state, transitions) in self.transitions.iter().enumerate() {
+            let mut edges: Vec<(usize, Vec<usize>)> = Vec::new();
+            for (letter, next) in transitions.iter().enumerate() {
+                match edges.iter_mut().find(|(target, _)| target == next) {
+                    Some((_, letters)) => letters.push(letter),
+                    None => edges.push((*next, vec![letter])),
+                }
+            }
+            for (next, letters) in edges {
+                let label = if letters.len() == transitions.len() {
+                    "⊤".to_string()
+                } else {
+                    letters
+                        .iter()
+                        .map(|letter| {
+                            self.vars
+                                .iter()
+                                .enumerate()
+                                .map(|(bit, var)| {
+                                    let name = &vars[*var as usize];
+                                    if letter & (1 << bit) != 0 {
+                                        name.clone()
+                                    } else {
+                                        format!("¬{name}")
+                                    }
+                                })
+                                .collect::<Vec<_>>()
+                                .join("∧")
+                        })
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                };
+                writeln!(dot, "    {state} -> {next} [label=\"{label}\"];").expect("write");
+            }
+        }
+        dot.push('}');
+        dot
+    }
+}
+
+#[cfg(test)]
+mod translation {
+    use super::*;
+    use crate::Operators;
+
+    // The automata accept exactly the traces satisfying their formula, for every operator.
+    #[test]
+    fn semantics() {
+        let vars = [0, 1];
+        let traces = all_traces(&vars, 4).collect::<Vec<_>>();
+        let operators = Operators::all().with_horizon(1);
+        for size in 1..=4 {
+            for formula in operators.gen_formulae(size, &vars) {
+                let dfa = formula.to_dfa().unwrap();
+                for trace in &traces {
+                    assert_eq!(
+                        dfa.accepts(trace),
+                        formula.eval(trace),
+                        "{formula} on {trace:?}"
+                    );
+                }
+                assert!(
+                    !dfa.accepts::<Vec<bool>>(&[]),
+                    "{formula} on the empty trace"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn minimal() {
+        let dfa = parse_formula("F x0").to_dfa().unwrap();
+        assert_eq!(dfa.states(), 2);
+        assert_eq!(dfa, parse_formula("x0 | X F x0").to_dfa().unwrap());
+        assert_eq!(dfa, dfa.minimize());
+        assert_eq!(
+            dfa,
+            Dfa {
+                vars: vec![0],
+                initial: 0,
+                accepting: vec![false, true],
+                transitions: vec![vec![0, 1], vec![1, 1]],
+            }
+        );
+        assert_ne!(dfa, parse_formula("F[0,2] x0").to_dfa().unwrap());
+    }
+
+    #[test]
+    fn emptiness() {
+        for formula in ["x0 & !x0", "G X x0", "X x0 & H Y x1"] {
+            let dfa = parse_formula(formula).to_dfa().unwrap();
+            assert!(dfa.is_empty(), "{formula}");
+            assert_eq!(dfa.accepted_trace(), None);
+        }
+        for formula in ["x0 | !x0", "F N x0", "G x0 | F !x0"] {
+            let dfa = parse_formula(formula).to_dfa().unwrap();
+            assert!(dfa.is_universal(), "{formula}");
+            assert_eq!(dfa.rejected_trace(), None);
+        }
+        let dfa = parse_formula("X X x0").to_dfa().unwrap();
+        assert!(!dfa.is_empty() && !dfa.is_universal());
+        assert_eq!(
+            dfa.accepted_trace(),
+            Some(vec![vec![false], vec![false], vec![true]])
+        );
+        assert_eq!(dfa.rejected_trace(), Some(vec![vec![false]]));
+    }
+
+    #[test]
+    fn alphabet() {
+        let dfa = parse_formula("F x0")
+            .to_dfa()
+            .unwrap()
+            .with_vars(&[1])
+            .unwrap();
+        assert!(dfa.accepts(&[[false, true], [true, false]]));
+        assert!(!dfa.accepts(&[[false, true], [false, true]]));
+        assert_eq!(dfa.minimize(), dfa);
+        // Extra variables in the trace are ignored.
+        assert!(parse_formula("x0")
+            .to_dfa()
+            .unwrap()
+            .accepts(&[[true, false, false]]));
+    }
+
+    // Only the variables appearing in the formula make up the alphabet, however large their indexes.
+    #[test]
+    fn sparse_vars() {
+        let dfa = parse_formula("F x16").to_dfa().unwrap();
+        assert_eq!(dfa.vars, vec![16]);
+        assert!(dfa.transitions.iter().all(|row| row.len() == 2));
+        let dfa = parse_formula("x100 U x70").to_dfa().unwrap();
+        assert_eq!(dfa.vars, vec![70, 100]);
+        let mut state = vec![false; 101];
+        state[70] = true;
+        assert!(dfa.accepts(&[&state]));
+        assert_eq!(dfa.accepted_trace(), Some(vec![state]));
+        assert_eq!(
+            dfa.difference_trace(&parse_formula("x3").to_dfa().unwrap())
+                .unwrap()
+                .map(|trace| trace[0].len()),
+            Some(101)
+        );
+        let formula = (0..=Dfa::MAX_VARS as Idx)
+            .map(|var| format!("F x{var}"))
+            .collect::<Vec<_>>()
+            .join(" & ");
+        assert_eq!(
+            parse_formula(&formula).to_dfa(),
+            Err(TooManyVars {
+                vars: Dfa::MAX_VARS + 1
+            })
+        );
+    }
+
+    #[test]
+    fn dot() {
+        let dot = parse_formula("F x0").to_dfa().unwrap().to_dot();
+        assert_eq!(
+            dot,
+            "digraph {\n    rankdir=LR;\n    node [shape=circle];\n    init [shape=point];\n    init -> 0;\n    1 [shape=doublecircle];\n    0 -> 0 [label=\"¬x0\"];\n    0 -> 1 [label=\"x0\"];\n    1 -> 1 [label=\"⊤\"];\n}"
+        );
+    }
+
+    #[test]
+    fn serialization() {
+        let dfa = parse_formula("x0 U (x1 & O x0)").to_dfa().unwrap();
+        let serialized = ron::to_string(&dfa).expect("serialize");
+        assert_eq!(ron::from_str::<Dfa>(&serialized).expect("deserialize"), dfa);
+        let serialized = serde_json::to_string(&dfa).expect("serialize");
+        assert_eq!(
+            serde_json::from_str::<Dfa>(&serialized).expect("deserialize"),
+            dfa
+        );
+    }
+}
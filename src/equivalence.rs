@@ -0,0 +1,235 @@
+use crate::automaton::*;
+use crate::syntax::*;
+use crate::trace::*;
+
+/// How two formulae are related, as far as the traces up to a bounded length can tell
+/// (or on all traces, for [`SyntaxTree::compare_exact`]).
+/// The traces telling the formulae apart are shortest ones.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Comparison {
+    /// The formulae hold on the same traces.
+    Equivalent,
+    /// The first formula implies the second one, but not vice versa:
+    /// the trace satisfies the second formula only.
+    Stronger(DynTrace),
+    /// The second formula implies the first one, but not vice versa:
+    /// the trace satisfies the first formula only.
+    Weaker(DynTrace),
+    /// Neither formula implies the other one.
+    Incomparable {
+        /// A trace satisfying the first formula only.
+        only_first: DynTrace,
+        /// A trace satisfying the second formula only.
+        only_second: DynTrace,
+    },
+}
+
+impl SyntaxTree {
+    /// Compares the formula with another one under LTLf semantics,
+    /// by evaluating both on every trace up to `max_len` long (over the variables of either formula).
+    ///
+    /// The search is exhaustive, so the result is exact for traces up to the given length,
+    /// but formulae telling apart only longer traces (e.g., `X X X x0` and `⊥` with `max_len = 3`) are deemed equivalent.
+    /// As the traces are `2^(vars * len)`, the bound has to be kept small.
+    pub fn compare(&self, other: &SyntaxTree, max_len: usize) -> Comparison {
+        match self.distinguishing_traces(other, max_len, true) {
+            (None, None) => Comparison::Equivalent,
+            (None, Some(only_second)) => Comparison::Stronger(only_second),
+            (Some(only_first), None) => Comparison::Weaker(only_first),
+            (Some(only_first), Some(only_second)) => Comparison::Incomparable {
+                only_first,
+                only_second,
+            },
+        }
+    }
+
+    /// Compares the formula with another one under LTLf semantics on all traces, whatever their length,
+    /// by translating both into automata (see [`SyntaxTree::to_dfa`]).
+    ///
+    /// Unlike [`SyntaxTree::compare`], the result is exact, but the translation is exponential in the size of the formulae,
+    /// and fails if they use more than [`Dfa::MAX_VARS`] variables altogether.
+    pub fn compare_exact(&self, other: &SyntaxTree) -> Result<Comparison, TooManyVars> {
+        let (dfa, other_dfa) = (self.to_dfa()?, other.to_dfa()?);
+        Ok(
+            match (
+                dfa.difference_trace(&other_dfa)?,
+                other_dfa.difference_trace(&dfa)?,
+            ) {
+                (None, None) => Comparison::Equivalent,
+                (None, Some(only_second)) => Comparison::Stronger(only_second),
+                (Some(only_first), None) => Comparison::Weaker(only_first),
+                (Some(only_first), Some(only_second)) => Comparison::Incomparable {
+                    only_first,
+                    only_second,
+                },
+            },
+        )
+    }
+
+    /// Checks whether the formula implies another one on every trace up to `max_len` long (see [`SyntaxTree::compare`]),
+    /// returning a shortest trace satisfying the formula but not the other one otherwise.
+    pub fn implies(&self, other: &SyntaxTree, max_len: usize) -> Result<(), DynTrace> {
+        match self.distinguishing_traces(other, max_len, false) {
+            (Some(trace), _) => Err(trace),
+            (None, _) => Ok(()),
+        }
+    }
+
+    /// Checks whether the formula is equivalent to another one on every trace up to `max_len` long (see [`SyntaxTree::compare`]),
+    /// returning a shortest trace satisfying only one of them otherwise.
+    pub fn is_equivalent(&self, other: &SyntaxTree, max_len: usize) -> Result<(), DynTrace> {
+        match self.compare(other, max_len) {
+            Comparison::Equivalent => Ok(()),
+            Comparison::Stronger(trace) | Comparison::Weaker(trace) => Err(trace),
+            Comparison::Incomparable {
+                only_first,
+                only_second,
+            } => Err(if only_second.len() < only_first.len() {
+                only_second
+            } else {
+                only_first
+            }),
+        }
+    }
+
+    /// Shortest traces satisfying only the formula and only the other one, respectively.
+    /// The search stops at the first trace satisfying only the formula, unless `both` are needed.
+    fn distinguishing_traces(
+        &self,
+        other: &SyntaxTree,
+        max_len: usize,
+        both: bool,
+    ) -> (Option<DynTrace>, Option<DynTrace>) {
+        // Variables appearing in neither formula would only multiply the traces to check.
+        let mut vars = self.used_vars();
+        vars.extend(other.used_vars());
+        vars.sort_unstable();
+        vars.dedup();
+        let (mut only_first, mut only_second) = (None, None);
+        for trace in all_traces(&vars, max_len) {
+            match (self.eval(&trace), other.eval(&trace)) {
+                (true, false) if only_first.is_none() => only_first = Some(trace),
+                (false, true) if only_second.is_none() => only_second = Some(trace),
+                _ => continue,
+            }
+            if only_first.is_some() && (!both || only_second.is_some()) {
+                break;
+            }
+        }
+        (only_first, only_second)
+    }
+}
+
+#[cfg(test)]
+mod compare {
+    use super::*;
+
+    #[test]
+    fn equivalent() {
+        for (first, second) in [
+            ("F x0", "x0 | X F x0"),
+            ("x0 U x1", "x1 | (x0 & X(x0 U x1))"),
+            ("G x0", "x0 & N G x0"),
+            ("!(x0 U x1)", "!x0 R !x1"),
+            ("x0 W x1", "(x0 U x1) | G x0"),
+            ("G X x0", "false"),
+        ] {
+            assert_eq!(
+                parse_formula(first).compare(&parse_formula(second), 5),
+                Comparison::Equivalent,
+                "{first}"
+            );
+            assert_eq!(
+                parse_formula(first).is_equivalent(&parse_formula(second), 5),
+                Ok(())
+            );
+        }
+    }
+
+    #[test]
+    fn stronger() {
+        let (first, second) = (parse_formula("X x0"), parse_formula("N x0"));
+        // At the end of the trace, weak next holds but strong next does not.
+        assert_eq!(
+            first.compare(&second, 4),
+            Comparison::Stronger(vec![vec![false]])
+        );
+        assert_eq!(
+            second.compare(&first, 4),
+            Comparison::Weaker(vec![vec![false]])
+        );
+        assert_eq!(first.implies(&second, 4), Ok(()));
+        assert_eq!(second.implies(&first, 4), Err(vec![vec![false]]));
+
+        let (first, second) = (parse_formula("G x0"), parse_formula("F x0"));
+        assert_eq!(first.implies(&second, 4), Ok(()));
+        assert_eq!(
+            second.implies(&first, 4),
+            Err(vec![vec![false], vec![true]])
+        );
+    }
+
+    #[test]
+    fn incomparable() {
+        let (first, second) = (parse_formula("x0"), parse_formula("X x1"));
+        assert_eq!(
+            first.compare(&second, 4),
+            Comparison::Incomparable {
+                only_first: vec![vec![true, false]],
+                only_second: vec![vec![false, false], vec![false, true]],
+            }
+        );
+        assert_eq!(
+            first.is_equivalent(&second, 4),
+            Err(vec![vec![true, false]])
+        );
+    }
+
+    // Differences showing up only on longer traces are missed.
+    #[test]
+    fn bound() {
+        let (first, second) = (parse_formula("X X X x0"), parse_formula("false"));
+        assert_eq!(first.compare(&second, 3), Comparison::Equivalent);
+        let trace = vec![vec![false], vec![false], vec![false], vec![true]];
+        assert_eq!(first.compare(&second, 4), Comparison::Weaker(trace.clone()));
+        assert_eq!(first.compare_exact(&second), Ok(Comparison::Weaker(trace)));
+    }
+
+    // Only the variables appearing in the formulae are enumerated, however large their indexes.
+    #[test]
+    fn sparse_vars() {
+        let (first, second) = (parse_formula("F x10"), parse_formula("G x10 | x3"));
+        assert_eq!(second.used_vars(), vec![3, 10]);
+        let (mut x3, mut x10) = (vec![false; 11], vec![false; 11]);
+        x3[3] = true;
+        x10[10] = true;
+        assert_eq!(
+            first.compare(&second, 12),
+            Comparison::Incomparable {
+                only_first: vec![vec![false; 11], x10],
+                only_second: vec![x3],
+            }
+        );
+    }
+
+    // The exact comparison agrees with the bounded one, for a bound large enough.
+    #[test]
+    fn exact() {
+        for (first, second) in [
+            ("F x0", "x0 | X F x0"),
+            ("G X x0", "false"),
+            ("X x0", "N x0"),
+            ("G x0", "F x0"),
+            ("x0", "X x1"),
+            ("F[1,2] x0", "X F x0"),
+            ("O x0 S x1", "H Y x1"),
+        ] {
+            let (first, second) = (parse_formula(first), parse_formula(second));
+            assert_eq!(
+                first.compare_exact(&second),
+                Ok(first.compare(&second, 4)),
+                "{first} vs {second}"
+            );
+        }
+    }
+}
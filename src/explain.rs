@@ -0,0 +1,391 @@
+use crate::syntax::*;
+use std::collections::HashMap;
+use std::fmt;
+
+/// The evaluation of a (sub)formula on a trace at a time instant,
+/// annotated with the evaluations of its subformulae justifying it.
+///
+/// The justification is minimal: only the time instants needed to settle the value are included,
+/// e.g., the first time instant at which the argument of `G` fails,
+/// or the time instant at which the right-hand-side argument of `U` becomes true (together with those before it).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explanation {
+    pub formula: SyntaxTree,
+    pub time: Time,
+    pub value: bool,
+    /// The evaluations of the subformulae justifying the value, ordered by time
+    /// (empty for atoms and constants, and for temporal operators settled by the trace ending or starting).
+    pub reasons: Vec<Explanation>,
+}
+
+impl Explanation {
+    /// The time instants at which the subformulae are evaluated to justify the value, in increasing order.
+    pub fn witnesses(&self) -> Vec<Time> {
+        let mut times = self
+            .reasons
+            .iter()
+            .map(|reason| reason.time)
+            .collect::<Vec<_>>();
+        times.sort_unstable();
+        times.dedup();
+        times
+    }
+
+    /// The number of evaluations making up the explanation, including the root one.
+    pub fn size(&self) -> usize {
+        1 + self.reasons.iter().map(Explanation::size).sum::<usize>()
+    }
+
+    /// Prints the explanation as an indented tree, one evaluation per line, using the given variable names.
+    pub fn print_w_named_vars(&self, vars: &[String]) -> String {
+        let mut lines = String::new();
+        self.print_in(&mut lines, 0, &|formula| formula.print_w_named_vars(vars));
+        lines
+    }
+
+    fn print_in(&self, lines: &mut String, depth: usize, print: &dyn Fn(&SyntaxTree) -> String) {
+        lines.push_str(&format!(
+            "{:indent$}{} is {} at time {}\n",
+            "",
+            print(&self.formula),
+            self.value,
+            self.time,
+            indent = 2 * depth
+        ));
+        for reason in &self.reasons {
+            reason.print_in(lines, depth + 1, print);
+        }
+    }
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut lines = String::new();
+        self.print_in(&mut lines, 0, &|formula| formula.to_string());
+        write!(f, "{}", lines.trim_end())
+    }
+}
+
+impl SyntaxTree {
+    /// Evaluates the formula on a trace, explaining why it holds or not (see [`Explanation`]).
+    pub fn explain<S: AsRef<[bool]>>(&self, trace: &[S]) -> Explanation {
+        self.explain_at_time(trace, 0)
+    }
+
+    /// Evaluates the formula on a trace at the given time instant, explaining why it holds or not.
+    pub fn explain_at_time<S: AsRef<[bool]>>(&self, trace: &[S], time: Time) -> Explanation {
+        assert!(time < trace.len());
+
+        Explainer {
+            trace,
+            values: HashMap::new(),
+        }
+        .explain(self, time)
+    }
+}
+
+/// Builds explanations, caching the values of each subformula explained at all time instants.
+/// The values are computed by [`SyntaxTree::eval_all_times`] on each such subformula,
+/// which evaluates its own subformulae anew, so deep formulae are evaluated more than once.
+struct Explainer<'a, S> {
+    trace: &'a [S],
+    /// The values of the subformulae explained so far, indexed by their address in the formula being explained.
+    values: HashMap<*const SyntaxTree, Vec<bool>>,
+}
+
+impl<S: AsRef<[bool]>> Explainer<'_, S> {
+    fn value(&mut self, formula: &SyntaxTree, time: Time) -> bool {
+        let trace = self.trace;
+        self.values
+            .entry(formula as *const SyntaxTree)
+            .or_insert_with(|| formula.eval_all_times(trace))[time]
+    }
+
+    fn explain(&mut self, formula: &SyntaxTree, time: Time) -> Explanation {
+        let len = self.trace.len();
+        let value = self.value(formula, time);
+        // The subformulae (and the time instants at which they are evaluated) justifying the value.
+        let reasons: Vec<(&SyntaxTree, Time)> = match formula {
+            SyntaxTree::Atom(_) | SyntaxTree::True | SyntaxTree::False => Vec::new(),
+            SyntaxTree::Not(branch) => vec![(branch, time)],
+            SyntaxTree::Next(branch) | SyntaxTree::WeakNext(branch) => {
+                if time + 1 < len {
+                    vec![(branch, time + 1)]
+                } else {
+                    Vec::new()
+                }
+            }
+            SyntaxTree::Yesterday(branch) => {
+                if time > 0 {
+                    vec![(branch, time - 1)]
+                } else {
+                    Vec::new()
+                }
+            }
+            // A single time instant where the argument takes the decisive value suffices,
+            // otherwise it takes every time instant in range.
+            SyntaxTree::Globally(branch) | SyntaxTree::Finally(branch) => self.decisive(
+                branch,
+                time..len,
+                !matches!(formula, SyntaxTree::Globally(_)),
+            ),
+            SyntaxTree::BoundedGlobally(interval, branch)
+            | SyntaxTree::BoundedFinally(interval, branch) => self.decisive(
+                branch,
+                interval.window(time, len),
+                matches!(formula, SyntaxTree::BoundedFinally(..)),
+            ),
+            // Past operators look for the closest decisive time instant.
+            SyntaxTree::Once(branch) | SyntaxTree::Historically(branch) => self.decisive(
+                branch,
+                (0..=time).rev(),
+                matches!(formula, SyntaxTree::Once(_)),
+            ),
+            SyntaxTree::And(left_branch, right_branch)
+            | SyntaxTree::Or(left_branch, right_branch)
+            | SyntaxTree::Implies(left_branch, right_branch) => {
+                // The value of a branch that settles the value on its own.
+                let (left_decisive, right_decisive) = match formula {
+                    SyntaxTree::And(..) => (false, false),
+                    SyntaxTree::Or(..) => (true, true),
+                    _ => (false, true),
+                };
+                let decisive = matches!(formula, SyntaxTree::And(..)) != value;
+                if decisive && self.value(left_branch, time) == left_decisive {
+                    vec![(left_branch, time)]
+                } else if decisive && self.value(right_branch, time) == right_decisive {
+                    vec![(right_branch, time)]
+                } else {
+                    vec![(left_branch, time), (right_branch, time)]
+                }
+            }
+            SyntaxTree::Until(left_branch, right_branch)
+            | SyntaxTree::WeakUntil(left_branch, right_branch) => self.until(
+                (left_branch, right_branch),
+                time..len,
+                time..=len - 1,
+                matches!(formula, SyntaxTree::WeakUntil(..)),
+            ),
+            SyntaxTree::BoundedUntil(interval, left_branch, right_branch) => {
+                let window = interval.window(time, len);
+                self.until(
+                    (left_branch, right_branch),
+                    time..*window.end() + 1,
+                    window,
+                    false,
+                )
+            }
+            SyntaxTree::Release(left_branch, right_branch) => {
+                let mut reasons = Vec::new();
+                for t in time..len {
+                    if !self.value(right_branch, t) {
+                        // The right-hand-side argument holding before is irrelevant.
+                        reasons = (time..t).map(|t| (left_branch.as_ref(), t)).collect();
+                        reasons.push((right_branch, t));
+                        break;
+                    }
+                    reasons.push((right_branch, t));
+                    if self.value(left_branch, t) {
+                        reasons.push((left_branch, t));
+                        break;
+                    }
+                }
+                reasons
+            }
+            SyntaxTree::Since(left_branch, right_branch) => {
+                let mut reasons = Vec::new();
+                for t in (0..=time).rev() {
+                    if self.value(right_branch, t) {
+                        // The right-hand-side argument not holding later on is irrelevant.
+                        reasons = (t + 1..=time).map(|t| (left_branch.as_ref(), t)).collect();
+                        reasons.push((right_branch, t));
+                        break;
+                    }
+                    reasons.push((right_branch, t));
+                    if !self.value(left_branch, t) {
+                        reasons.push((left_branch, t));
+                        break;
+                    }
+                }
+                reasons
+            }
+        };
+
+        let mut reasons = reasons
+            .into_iter()
+            .map(|(branch, t)| self.explain(branch, t))
+            .collect::<Vec<_>>();
+        reasons.sort_by_key(|reason| reason.time);
+        Explanation {
+            formula: formula.clone(),
+            time,
+            value,
+            reasons,
+        }
+    }
+
+    /// The first time instant in `times` where the branch takes the `decisive` value,
+    /// or all of them if there is none.
+    fn decisive<'f>(
+        &mut self,
+        branch: &'f SyntaxTree,
+        times: impl Iterator<Item = Time>,
+        decisive: bool,
+    ) -> Vec<(&'f SyntaxTree, Time)> {
+        let mut reasons = Vec::new();
+        for t in times {
+            if self.value(branch, t) == decisive {
+                return vec![(branch, t)];
+            }
+            reasons.push((branch, t));
+        }
+        reasons
+    }
+
+    /// The time instants justifying an until, with its left-hand-side argument evaluated over `range`
+    /// and its right-hand-side one looked for within `window`.
+    fn until<'f>(
+        &mut self,
+        (left_branch, right_branch): (&'f SyntaxTree, &'f SyntaxTree),
+        range: std::ops::Range<Time>,
+        window: std::ops::RangeInclusive<Time>,
+        weak: bool,
+    ) -> Vec<(&'f SyntaxTree, Time)> {
+        let mut reasons = Vec::new();
+        for t in range.clone() {
+            if window.contains(&t) {
+                if self.value(right_branch, t) {
+                    let mut reasons = (range.start..t)
+                        .map(|t| (left_branch, t))
+                        .collect::<Vec<_>>();
+                    reasons.push((right_branch, t));
+                    return reasons;
+                }
+                reasons.push((right_branch, t));
+            }
+            if !self.value(left_branch, t) {
+                reasons.push((left_branch, t));
+                return reasons;
+            }
+        }
+        // The left-hand-side argument holds throughout, which justifies a weak until,
+        // whereas a strong one fails as the right-hand-side argument never holds.
+        if weak {
+            range.map(|t| (left_branch, t)).collect()
+        } else {
+            reasons
+        }
+    }
+}
+
+#[cfg(test)]
+mod explanation {
+    use super::*;
+    use crate::trace::*;
+    use crate::Operators;
+
+    /// Checks the values in the explanation, collecting those of the atoms it relies on.
+    fn check<S: AsRef<[bool]>>(
+        explanation: &Explanation,
+        trace: &[S],
+        atoms: &mut Vec<(Idx, Time, bool)>,
+    ) {
+        let formula = &explanation.formula;
+        assert_eq!(
+            explanation.value,
+            formula.eval_at_time(trace, explanation.time),
+            "{formula} at time {}",
+            explanation.time
+        );
+        if let SyntaxTree::Atom(var) = formula {
+            atoms.push((*var, explanation.time, explanation.value));
+        }
+        for reason in &explanation.reasons {
+            check(reason, trace, atoms);
+        }
+    }
+
+    // The values are correct, and justified: every trace as long as the explained one
+    // and agreeing with it on the atoms the explanation relies on gets the same value.
+    #[test]
+    fn justified() {
+        let vars = [0, 1];
+        let traces = all_traces(&vars, 3).collect::<Vec<_>>();
+        for formula in Operators::all().with_horizon(1).gen_formulae(4, &vars) {
+            for trace in &traces {
+                let explanation = formula.explain(trace);
+                let mut atoms = Vec::new();
+                check(&explanation, trace, &mut atoms);
+                for other in traces.iter().filter(|other| other.len() == trace.len()) {
+                    if atoms
+                        .iter()
+                        .all(|(var, time, value)| other[*time][*var as usize] == *value)
+                    {
+                        assert_eq!(
+                            formula.eval(other),
+                            explanation.value,
+                            "{formula} on {trace:?} and {other:?}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn witnesses() {
+        let trace = [[true, false], [true, false], [false, false], [true, true]];
+        // The first time instant where G fails.
+        let explanation = parse_formula("G x0").explain(&trace);
+        assert!(!explanation.value);
+        assert_eq!(explanation.witnesses(), vec![2]);
+        // Every time instant where G holds.
+        assert_eq!(
+            parse_formula("G !x1").explain(&trace[..3]).witnesses(),
+            vec![0, 1, 2]
+        );
+        // The time instant where the right-hand-side argument of U becomes true, and those before it.
+        let explanation = parse_formula("F x1").explain(&trace);
+        assert_eq!(explanation.witnesses(), vec![3]);
+        let explanation = parse_formula("x0 U x1").explain(&trace);
+        assert!(!explanation.value);
+        assert_eq!(explanation.witnesses(), vec![0, 1, 2]);
+        assert_eq!(explanation.reasons.len(), 4);
+        let explanation = parse_formula("x0 U x1").explain(&trace[3..]);
+        assert!(explanation.value);
+        assert_eq!(explanation.size(), 2);
+        // The trace ending is enough for X to fail.
+        let explanation = parse_formula("X x0").explain_at_time(&trace, 3);
+        assert!(!explanation.value && explanation.reasons.is_empty());
+        // Only the false conjunct.
+        let explanation = parse_formula("x1 & G x0").explain(&trace);
+        assert_eq!(explanation.reasons.len(), 1);
+        assert_eq!(explanation.reasons[0].formula, parse_formula("x1"));
+    }
+
+    #[test]
+    fn print() {
+        let trace = [[true, false], [false, true]];
+        let explanation = parse_formula("G(x0 | x1) & F !x0").explain(&trace);
+        assert_eq!(
+            explanation.to_string(),
+            "\
+(G((x0)∨(x1)))∧(F(¬(x0))) is true at time 0
+  G((x0)∨(x1)) is true at time 0
+    (x0)∨(x1) is true at time 0
+      x0 is true at time 0
+    (x0)∨(x1) is true at time 1
+      x1 is true at time 1
+  F(¬(x0)) is true at time 0
+    ¬(x0) is true at time 1
+      x0 is false at time 1"
+        );
+        let vars = vec!["req".to_string(), "ack".to_string()];
+        assert_eq!(
+            parse_formula("X x0")
+                .explain(&trace)
+                .print_w_named_vars(&vars),
+            "X(req) is false at time 0\n  req is false at time 1\n"
+        );
+    }
+}
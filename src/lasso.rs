@@ -0,0 +1,750 @@
+use crate::syntax::*;
+use crate::trace::*;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+/// An infinite trace, made of a finite prefix followed by a (non-empty) cycle repeated forever.
+///
+/// It is (de)serialized with its states as tuples, as the traces of [`DynSample`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(into = "LassoRepr", try_from = "LassoRepr")]
+pub struct Lasso {
+    prefix: DynTrace,
+    cycle: DynTrace,
+}
+
+impl Lasso {
+    /// Creates the lasso repeating `cycle` forever after `prefix`.
+    ///
+    /// Panics if the cycle is empty, or if the states do not all have the same number of variables.
+    pub fn new(prefix: DynTrace, cycle: DynTrace) -> Self {
+        assert!(!cycle.is_empty(), "the cycle of a lasso cannot be empty");
+        let vars = cycle[0].len();
+        assert!(
+            prefix.iter().chain(&cycle).all(|state| state.len() == vars),
+            "states with different numbers of variables"
+        );
+        Lasso { prefix, cycle }
+    }
+
+    pub fn prefix(&self) -> &[Vec<bool>] {
+        &self.prefix
+    }
+
+    pub fn cycle(&self) -> &[Vec<bool>] {
+        &self.cycle
+    }
+
+    /// The number of propositional variables of the states of the lasso.
+    pub fn num_vars(&self) -> usize {
+        self.cycle[0].len()
+    }
+
+    /// The time instant at which the cycle starts (for the first time).
+    pub fn loop_start(&self) -> Time {
+        self.prefix.len()
+    }
+
+    /// The state at the given time instant, which can be arbitrarily far in the future.
+    pub fn state(&self, time: Time) -> &[bool] {
+        let pos = self.position(time);
+        if pos < self.prefix.len() {
+            &self.prefix[pos]
+        } else {
+            &self.cycle[pos - self.prefix.len()]
+        }
+    }
+
+    /// The index of the state at the given time instant, in the prefix followed by the cycle.
+    fn position(&self, time: Time) -> usize {
+        if time < self.prefix.len() {
+            time
+        } else {
+            self.loop_start() + (time - self.loop_start()) % self.cycle.len()
+        }
+    }
+
+    /// The position of the state `offset` time instants after the one at position `pos`,
+    /// for offsets as large as `Time::MAX`: past a whole lasso, offsets are reduced modulo the cycle.
+    fn position_after(&self, pos: usize, offset: Time) -> usize {
+        let len = self.prefix.len() + self.cycle.len();
+        let offset = if offset < len {
+            offset
+        } else {
+            len + (offset - len) % self.cycle.len()
+        };
+        self.position(pos + offset)
+    }
+
+    /// The same infinite trace, with the cycle unrolled into the prefix the given number of times.
+    pub fn unrolled(&self, times: usize) -> Lasso {
+        let mut prefix = self.prefix.clone();
+        for _ in 0..times {
+            prefix.extend_from_slice(&self.cycle);
+        }
+        Lasso {
+            prefix,
+            cycle: self.cycle.clone(),
+        }
+    }
+
+    /// The shortest lasso representing the same infinite trace,
+    /// so that two lassos represent the same trace iff their normal forms are equal.
+    pub fn normalized(&self) -> Lasso {
+        // The shortest cycle repeating into the given one, i.e., the smallest rotation leaving it unchanged.
+        let period = (1..=self.cycle.len())
+            .find(|&period| {
+                self.cycle[period..]
+                    .iter()
+                    .chain(&self.cycle[..period])
+                    .eq(&self.cycle)
+            })
+            .expect("the cycle is its own period");
+        let mut prefix = self.prefix.clone();
+        let mut cycle = self.cycle[..period].to_vec();
+        // The prefix is shortened as long as it ends as the cycle does.
+        while prefix
+            .last()
+            .is_some_and(|state| state == &cycle[period - 1])
+        {
+            prefix.pop();
+            cycle.rotate_right(1);
+        }
+        Lasso { prefix, cycle }
+    }
+
+    /// The lasso restricted to the given variables, in that order.
+    fn projected(&self, vars: &[Idx]) -> Lasso {
+        let project = |trace: &DynTrace| {
+            trace
+                .iter()
+                .map(|state| vars.iter().map(|var| state[*var as usize]).collect())
+                .collect()
+        };
+        Lasso {
+            prefix: project(&self.prefix),
+            cycle: project(&self.cycle),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Lasso")]
+struct LassoRepr {
+    prefix: Vec<Tuple<bool>>,
+    cycle: Vec<Tuple<bool>>,
+}
+
+impl From<Lasso> for LassoRepr {
+    fn from(lasso: Lasso) -> Self {
+        LassoRepr {
+            prefix: lasso.prefix.into_iter().map(Tuple).collect(),
+            cycle: lasso.cycle.into_iter().map(Tuple).collect(),
+        }
+    }
+}
+
+impl TryFrom<LassoRepr> for Lasso {
+    type Error = String;
+
+    fn try_from(repr: LassoRepr) -> Result<Self, Self::Error> {
+        let prefix: DynTrace = repr.prefix.into_iter().map(|state| state.0).collect();
+        let cycle: DynTrace = repr.cycle.into_iter().map(|state| state.0).collect();
+        let vars = cycle
+            .first()
+            .ok_or("the cycle of a lasso cannot be empty")?
+            .len();
+        if prefix.iter().chain(&cycle).any(|state| state.len() != vars) {
+            return Err("states with different numbers of variables".to_string());
+        }
+        Ok(Lasso { prefix, cycle })
+    }
+}
+
+/// All the lassos over the given variables with prefix and cycle up to `max_len` long in total,
+/// with states as given by `all_traces`.
+/// The same infinite trace is represented by many of them.
+pub(crate) fn all_lassos(vars: &[Idx], max_len: usize) -> impl Iterator<Item = Lasso> {
+    all_traces(vars, max_len).flat_map(|trace| {
+        (0..trace.len()).map(move |loop_start| Lasso {
+            prefix: trace[..loop_start].to_vec(),
+            cycle: trace[loop_start..].to_vec(),
+        })
+    })
+}
+
+impl SyntaxTree {
+    /// Evaluate a formula on an infinite trace, given as a lasso,
+    /// according to the standard semantics of LTL (with past operators).
+    ///
+    /// On infinite traces there is always a next time instant, so weak next is the same as next,
+    /// and bounded operators look at their whole interval.
+    pub fn eval_lasso(&self, lasso: &Lasso) -> bool {
+        self.eval_lasso_at_time(lasso, 0)
+    }
+
+    /// Evaluate a formula on an infinite trace, given as a lasso, at the given time instant.
+    pub fn eval_lasso_at_time(&self, lasso: &Lasso, time: Time) -> bool {
+        let (unrolled, values) = self.lasso_values(lasso);
+        values[unrolled.position(time)]
+    }
+
+    /// The truth values of the formula on a lasso at the time instants up to `len`, excluded.
+    pub(crate) fn eval_lasso_all_times(&self, lasso: &Lasso, len: usize) -> Vec<bool> {
+        let (unrolled, values) = self.lasso_values(lasso);
+        (0..len)
+            .map(|time| values[unrolled.position(time)])
+            .collect()
+    }
+
+    /// The lasso unrolled as needed by the formula, and the truth values of the formula at each of its states.
+    ///
+    /// Past operators can take different values on different repetitions of the cycle,
+    /// but each of them makes the truth values periodic (with the period of the cycle) at most one repetition later.
+    /// Once the cycle is unrolled as many times as past operators can be nested in the formula,
+    /// every subformula is periodic from the start of the cycle on, so that it can be evaluated on the lasso.
+    fn lasso_values(&self, lasso: &Lasso) -> (Lasso, Vec<bool>) {
+        let unrolled = lasso.unrolled(self.past_depth());
+        let values = self.eval_unrolled(&unrolled);
+        (unrolled, values)
+    }
+
+    /// The largest number of nested past operators in the formula.
+    fn past_depth(&self) -> usize {
+        match self {
+            SyntaxTree::Atom(_) | SyntaxTree::True | SyntaxTree::False => 0,
+            SyntaxTree::Yesterday(branch)
+            | SyntaxTree::Once(branch)
+            | SyntaxTree::Historically(branch) => 1 + branch.past_depth(),
+            SyntaxTree::Since(left_branch, right_branch) => {
+                1 + left_branch.past_depth().max(right_branch.past_depth())
+            }
+            SyntaxTree::Not(branch)
+            | SyntaxTree::Next(branch)
+            | SyntaxTree::WeakNext(branch)
+            | SyntaxTree::Globally(branch)
+            | SyntaxTree::Finally(branch)
+            | SyntaxTree::BoundedGlobally(_, branch)
+            | SyntaxTree::BoundedFinally(_, branch) => branch.past_depth(),
+            SyntaxTree::And(left_branch, right_branch)
+            | SyntaxTree::Or(left_branch, right_branch)
+            | SyntaxTree::Implies(left_branch, right_branch)
+            | SyntaxTree::Until(left_branch, right_branch)
+            | SyntaxTree::Release(left_branch, right_branch)
+            | SyntaxTree::WeakUntil(left_branch, right_branch)
+            | SyntaxTree::BoundedUntil(_, left_branch, right_branch) => {
+                left_branch.past_depth().max(right_branch.past_depth())
+            }
+        }
+    }
+
+    /// The truth values of the formula at each state of a lasso unrolled enough for its past operators.
+    ///
+    /// Past operators are evaluated forward, as on finite traces,
+    /// while future operators are evaluated as fixpoints over the lasso, whose last state is followed by the start of the cycle.
+    fn eval_unrolled(&self, lasso: &Lasso) -> Vec<bool> {
+        let len = lasso.prefix.len() + lasso.cycle.len();
+        let next = |pos: usize| lasso.position(pos + 1);
+        // Values repeat with the cycle, so offsets in an interval past a whole lasso from its start add nothing.
+        let offsets = |interval: Interval| {
+            interval.start..=interval.end.min(interval.start.saturating_add(len))
+        };
+        // Fixpoint of `value(pos) = step(pos, value(next(pos)))`, the least one from `false` and the greatest one from `true`.
+        let fixpoint = |init: bool, step: &dyn Fn(usize, bool) -> bool| {
+            let mut values = vec![init; len];
+            let mut changed = true;
+            while changed {
+                changed = false;
+                for pos in (0..len).rev() {
+                    let value = step(pos, values[next(pos)]);
+                    if value != values[pos] {
+                        values[pos] = value;
+                        changed = true;
+                    }
+                }
+            }
+            values
+        };
+        // Past operators, forward from the start of the trace: `value(pos) = step(pos, value(pos - 1))`.
+        let forward = |init: bool, step: &dyn Fn(usize, bool) -> bool| {
+            let mut values = Vec::with_capacity(len);
+            for pos in 0..len {
+                let previous = if pos == 0 { init } else { values[pos - 1] };
+                values.push(step(pos, previous));
+            }
+            values
+        };
+        match self {
+            SyntaxTree::Atom(var) => (0..len)
+                .map(|pos| lasso.state(pos)[*var as usize])
+                .collect(),
+            SyntaxTree::True => vec![true; len],
+            SyntaxTree::False => vec![false; len],
+            SyntaxTree::Not(branch) => branch
+                .eval_unrolled(lasso)
+                .into_iter()
+                .map(|value| !value)
+                .collect(),
+            SyntaxTree::Next(branch) | SyntaxTree::WeakNext(branch) => {
+                let values = branch.eval_unrolled(lasso);
+                (0..len).map(|pos| values[next(pos)]).collect()
+            }
+            SyntaxTree::Globally(branch) => {
+                let values = branch.eval_unrolled(lasso);
+                fixpoint(true, &|pos, next| values[pos] && next)
+            }
+            SyntaxTree::Finally(branch) => {
+                let values = branch.eval_unrolled(lasso);
+                fixpoint(false, &|pos, next| values[pos] || next)
+            }
+            SyntaxTree::Yesterday(branch) => {
+                let values = branch.eval_unrolled(lasso);
+                (0..len).map(|pos| pos > 0 && values[pos - 1]).collect()
+            }
+            SyntaxTree::Once(branch) => {
+                let values = branch.eval_unrolled(lasso);
+                forward(false, &|pos, previous| values[pos] || previous)
+            }
+            SyntaxTree::Historically(branch) => {
+                let values = branch.eval_unrolled(lasso);
+                forward(true, &|pos, previous| values[pos] && previous)
+            }
+            SyntaxTree::BoundedGlobally(interval, branch) => {
+                let values = branch.eval_unrolled(lasso);
+                (0..len)
+                    .map(|pos| offsets(*interval).all(|k| values[lasso.position_after(pos, k)]))
+                    .collect()
+            }
+            SyntaxTree::BoundedFinally(interval, branch) => {
+                let values = branch.eval_unrolled(lasso);
+                (0..len)
+                    .map(|pos| offsets(*interval).any(|k| values[lasso.position_after(pos, k)]))
+                    .collect()
+            }
+            SyntaxTree::And(left_branch, right_branch) => {
+                let (left, right) = (
+                    left_branch.eval_unrolled(lasso),
+                    right_branch.eval_unrolled(lasso),
+                );
+                (0..len).map(|pos| left[pos] && right[pos]).collect()
+            }
+            SyntaxTree::Or(left_branch, right_branch) => {
+                let (left, right) = (
+                    left_branch.eval_unrolled(lasso),
+                    right_branch.eval_unrolled(lasso),
+                );
+                (0..len).map(|pos| left[pos] || right[pos]).collect()
+            }
+            SyntaxTree::Implies(left_branch, right_branch) => {
+                let (left, right) = (
+                    left_branch.eval_unrolled(lasso),
+                    right_branch.eval_unrolled(lasso),
+                );
+                (0..len).map(|pos| !left[pos] || right[pos]).collect()
+            }
+            SyntaxTree::Until(left_branch, right_branch) => {
+                let (left, right) = (
+                    left_branch.eval_unrolled(lasso),
+                    right_branch.eval_unrolled(lasso),
+                );
+                fixpoint(false, &|pos, next| right[pos] || (left[pos] && next))
+            }
+            SyntaxTree::Release(left_branch, right_branch) => {
+                let (left, right) = (
+                    left_branch.eval_unrolled(lasso),
+                    right_branch.eval_unrolled(lasso),
+                );
+                fixpoint(true, &|pos, next| right[pos] && (left[pos] || next))
+            }
+            SyntaxTree::WeakUntil(left_branch, right_branch) => {
+                let (left, right) = (
+                    left_branch.eval_unrolled(lasso),
+                    right_branch.eval_unrolled(lasso),
+                );
+                fixpoint(true, &|pos, next| right[pos] || (left[pos] && next))
+            }
+            SyntaxTree::Since(left_branch, right_branch) => {
+                let (left, right) = (
+                    left_branch.eval_unrolled(lasso),
+                    right_branch.eval_unrolled(lasso),
+                );
+                forward(false, &|pos, previous| {
+                    right[pos] || (left[pos] && previous)
+                })
+            }
+            SyntaxTree::BoundedUntil(interval, left_branch, right_branch) => {
+                let (left, right) = (
+                    left_branch.eval_unrolled(lasso),
+                    right_branch.eval_unrolled(lasso),
+                );
+                (0..len)
+                    .map(|pos| {
+                        // Likewise, the left-hand-side argument takes all its values within a whole lasso.
+                        offsets(*interval).any(|k| {
+                            right[lasso.position_after(pos, k)]
+                                && (0..k.min(len)).all(|j| left[lasso.position(pos + j)])
+                        })
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// A sample of infinite traces, given as lassos, to learn formulae under infinite-trace semantics
+/// (see `solve_lasso`).
+///
+/// It is (de)serialized in the same format as [`DynSample`], with traces given as lassos,
+/// such as `(prefix: [(true, false)], cycle: [(false, true)])`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LassoSample {
+    pub var_names: Vec<String>,
+    pub positive_traces: Vec<Lasso>,
+    pub negative_traces: Vec<Lasso>,
+}
+
+impl LassoSample {
+    /// Creates an empty sample over the given number of variables, named `x0`, `x1`, ...
+    pub fn new(vars: usize) -> Self {
+        LassoSample {
+            var_names: Self::default_var_names(vars),
+            ..Default::default()
+        }
+    }
+
+    fn default_var_names(vars: usize) -> Vec<String> {
+        (0..vars).map(|n| format!("x{n}")).collect()
+    }
+
+    /// The number of propositional variables of the sample.
+    pub fn num_vars(&self) -> usize {
+        self.var_names.len()
+    }
+
+    /// Returns the indexes of the variables that can appear in a solution,
+    /// excluding those whose name has been marked as discarded by a leading `~` (as for `LabeledSample::vars`).
+    pub fn vars(&self) -> Vec<Idx> {
+        self.var_names
+            .iter()
+            .enumerate()
+            .filter(|(_, name)| !name.starts_with('~'))
+            .map(|(idx, _)| idx as Idx)
+            .collect()
+    }
+
+    // https://rust-lang.github.io/rust-clippy/master/index.html#result_unit_err
+    #[allow(clippy::result_unit_err)]
+    pub fn add_positive_trace(&mut self, trace: Lasso) -> Result<(), ()> {
+        assert_eq!(trace.num_vars(), self.num_vars());
+        let normalized = trace.normalized();
+        if !self
+            .negative_traces
+            .iter()
+            .any(|negative| negative.normalized() == normalized)
+        {
+            if !self
+                .positive_traces
+                .iter()
+                .any(|positive| positive.normalized() == normalized)
+            {
+                self.positive_traces.push(trace);
+            }
+            Ok(())
+        } else {
+            Err(())
+        }
+    }
+
+    // https://rust-lang.github.io/rust-clippy/master/index.html#result_unit_err
+    #[allow(clippy::result_unit_err)]
+    pub fn add_negative_trace(&mut self, trace: Lasso) -> Result<(), ()> {
+        assert_eq!(trace.num_vars(), self.num_vars());
+        let normalized = trace.normalized();
+        if !self
+            .positive_traces
+            .iter()
+            .any(|positive| positive.normalized() == normalized)
+        {
+            if !self
+                .negative_traces
+                .iter()
+                .any(|negative| negative.normalized() == normalized)
+            {
+                self.negative_traces.push(trace);
+            }
+            Ok(())
+        } else {
+            Err(())
+        }
+    }
+
+    pub fn positive_traces(&self) -> usize {
+        self.positive_traces.len()
+    }
+
+    pub fn negative_traces(&self) -> usize {
+        self.negative_traces.len()
+    }
+
+    /// Whether some formula tells apart positive and negative traces,
+    /// i.e., no positive trace is the same infinite trace as a negative one over the variables of the solution.
+    pub fn is_solvable(&self) -> bool {
+        let vars = self.vars();
+        let normalized = |trace: &Lasso| trace.projected(&vars).normalized();
+        let positives = self.positive_traces.iter().map(normalized).collect_vec();
+        self.negative_traces
+            .iter()
+            .all(|negative| !positives.contains(&normalized(negative)))
+    }
+
+    pub fn is_consistent(&self, formula: &SyntaxTree) -> bool {
+        self.positive_traces
+            .iter()
+            .all(|trace| formula.eval_lasso(trace))
+            && self
+                .negative_traces
+                .iter()
+                .all(|trace| !formula.eval_lasso(trace))
+    }
+
+    /// Returns the indexes of the positive and of the negative traces misclassified by the formula.
+    pub fn misclassified(&self, formula: &SyntaxTree) -> Misclassified {
+        Misclassified {
+            positives: self
+                .positive_traces
+                .iter()
+                .positions(|trace| !formula.eval_lasso(trace))
+                .collect(),
+            negatives: self
+                .negative_traces
+                .iter()
+                .positions(|trace| formula.eval_lasso(trace))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Sample")]
+struct LassoSampleRepr {
+    // Missing (or empty) names are replaced by the default ones.
+    #[serde(default)]
+    var_names: Tuple<String>,
+    positive_traces: Vec<Lasso>,
+    negative_traces: Vec<Lasso>,
+}
+
+impl Serialize for LassoSample {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        LassoSampleRepr {
+            var_names: Tuple(self.var_names.clone()),
+            positive_traces: self.positive_traces.clone(),
+            negative_traces: self.negative_traces.clone(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for LassoSample {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = LassoSampleRepr::deserialize(deserializer)?;
+        let mut traces = repr.positive_traces.iter().chain(&repr.negative_traces);
+        let var_names = if repr.var_names.0.is_empty() {
+            Self::default_var_names(traces.clone().next().map_or(0, Lasso::num_vars))
+        } else {
+            repr.var_names.0
+        };
+        if let Some(trace) = traces.find(|trace| trace.num_vars() != var_names.len()) {
+            return Err(serde::de::Error::invalid_length(
+                trace.num_vars(),
+                &format!("{} variables", var_names.len()).as_str(),
+            ));
+        }
+
+        Ok(LassoSample {
+            var_names,
+            positive_traces: repr.positive_traces,
+            negative_traces: repr.negative_traces,
+        })
+    }
+}
+
+#[cfg(test)]
+mod infinite {
+    use super::*;
+    use crate::Operators;
+
+    fn lasso(prefix: &[bool], cycle: &[bool]) -> Lasso {
+        let states = |states: &[bool]| states.iter().map(|value| vec![*value]).collect();
+        Lasso::new(states(prefix), states(cycle))
+    }
+
+    #[test]
+    fn eval() {
+        let alternating = lasso(&[], &[false, true]);
+        assert!(parse_formula("G F x0").eval_lasso(&alternating));
+        assert!(!parse_formula("F G x0").eval_lasso(&alternating));
+        assert!(parse_formula("X x0").eval_lasso(&alternating));
+        assert!(parse_formula("G(x0 -> X !x0)").eval_lasso(&alternating));
+        assert!(!parse_formula("F[0,0] x0").eval_lasso(&alternating));
+        assert!(parse_formula("G F[0,1] x0").eval_lasso(&alternating));
+        assert!(!parse_formula("G G[0,1] x0").eval_lasso(&alternating));
+
+        // Unlike on finite traces, there is always a next time instant.
+        let constant = lasso(&[], &[false]);
+        assert!(!parse_formula("N x0").eval_lasso(&constant));
+        assert!(!parse_formula("F N x0").eval_lasso(&constant));
+        assert!(parse_formula("G X !x0").eval_lasso(&constant));
+        assert!(!parse_formula("x0 W x0").eval_lasso(&constant));
+        assert!(parse_formula("!x0 W x0").eval_lasso(&constant));
+        assert!(!parse_formula("!x0 U x0").eval_lasso(&constant));
+        assert!(parse_formula("x0 R !x0").eval_lasso(&constant));
+
+        // Past operators see the previous repetitions of the cycle.
+        let lasso = lasso(&[true], &[true, false, false]);
+        assert!(parse_formula("G(!x0 -> O x0)").eval_lasso(&lasso));
+        assert!(parse_formula("F(x0 & Y x0)").eval_lasso(&lasso));
+        assert!(!parse_formula("X X F(x0 & Y x0)").eval_lasso(&lasso));
+        assert!(parse_formula("G F(!x0 & Y !x0)").eval_lasso(&lasso));
+        assert!(!parse_formula("X X F H x0").eval_lasso(&lasso));
+        assert!(parse_formula("G(Y Y Y x0 -> x0)").eval_lasso_at_time(&lasso, 100));
+    }
+
+    // Classic identities of LTL hold at every time instant of every lasso, but some fail on finite traces.
+    #[test]
+    fn identities() {
+        let lassos = all_lassos(&[0, 1], 3).collect_vec();
+        for (first, second) in [
+            ("N x0", "X x0"),
+            ("!X x0", "X !x0"),
+            ("G X x0", "X G x0"),
+            ("F N x0", "F X x0"),
+            ("x0 U x1", "x1 | (x0 & X(x0 U x1))"),
+            ("x0 W x1", "(x0 U x1) | G x0"),
+            ("x0 R x1", "!(!x0 U !x1)"),
+            ("F x0", "true U x0"),
+            ("G[1,2] x0", "X x0 & X X x0"),
+            ("x0 U[1,2] x1", "x0 & X(x1 | (x0 & X x1))"),
+            ("x0 S x1", "x1 | (x0 & Y(x0 S x1))"),
+            ("O x0", "!H !x0"),
+        ] {
+            let (first, second) = (parse_formula(first), parse_formula(second));
+            for lasso in &lassos {
+                assert_eq!(
+                    first.eval_lasso_all_times(lasso, 12),
+                    second.eval_lasso_all_times(lasso, 12),
+                    "{first} and {second} on {lasso:?}"
+                );
+            }
+        }
+    }
+
+    // Bounded operators with bounds up to `Time::MAX` agree with their unbounded counterparts.
+    #[test]
+    fn huge_bounds() {
+        let max = Time::MAX;
+        let lassos = all_lassos(&[0, 1], 3).collect_vec();
+        for (first, second) in [
+            (format!("G[0,{max}] x0"), "G x0"),
+            (format!("F[1,{max}] x0"), "X F x0"),
+            (format!("x0 U[1,{max}] x1"), "x0 & X(x0 U x1)"),
+            // Every time instant that far lies on the cycle.
+            (format!("G F[{max},{max}] x0"), "F G x0"),
+        ] {
+            let (first, second) = (parse_formula(&first), parse_formula(second));
+            for lasso in &lassos {
+                assert_eq!(
+                    first.eval_lasso_all_times(lasso, 6),
+                    second.eval_lasso_all_times(lasso, 6),
+                    "{first} and {second} on {lasso:?}"
+                );
+            }
+        }
+        // `Time::MAX` is odd, so it lands on the other state of a cycle of two.
+        let formula = parse_formula(&format!("F[{max},{max}] x0"));
+        let alternating = lasso(&[], &[false, true]);
+        assert!(formula.eval_lasso(&alternating));
+        assert!(!formula.eval_lasso_at_time(&alternating, 1));
+    }
+
+    // The truth values only depend on the infinite trace, not on the lasso representing it.
+    #[test]
+    fn representation() {
+        let vars = [0, 1];
+        let lassos = all_lassos(&vars, 3).collect_vec();
+        for formula in Operators::all().with_horizon(1).gen_formulae(3, &vars) {
+            for lasso in &lassos {
+                let len = lasso.loop_start() + 8 * lasso.cycle().len();
+                let values = formula.eval_lasso_all_times(lasso, len);
+                for other in [
+                    lasso.normalized(),
+                    lasso.unrolled(2),
+                    lasso.unrolled(1).normalized(),
+                ] {
+                    assert_eq!(
+                        formula.eval_lasso_all_times(&other, len),
+                        values,
+                        "{formula} on {lasso:?}"
+                    );
+                }
+                let doubled = Lasso::new(
+                    lasso.prefix.clone(),
+                    [lasso.cycle.clone(), lasso.cycle.clone()].concat(),
+                );
+                assert_eq!(formula.eval_lasso_all_times(&doubled, len), values);
+            }
+        }
+    }
+
+    #[test]
+    fn normalized() {
+        assert_eq!(
+            lasso(&[true, false], &[true, false, true, false]).normalized(),
+            lasso(&[], &[true, false])
+        );
+        assert_eq!(
+            lasso(&[false, true, true], &[false, true]).normalized(),
+            lasso(&[false, true], &[true, false])
+        );
+        assert_eq!(
+            lasso(&[true], &[false]).normalized(),
+            lasso(&[true], &[false])
+        );
+    }
+
+    #[test]
+    fn sample() {
+        let sample = ron::de::from_str::<LassoSample>(
+            "(positive_traces: [(prefix: [], cycle: [(true, false), (false, true)])], negative_traces: [(prefix: [(true, false)], cycle: [(false, false)])])",
+        )
+        .expect("deserialize sample");
+        assert_eq!(sample.var_names, vec!["x0", "x1"]);
+        assert!(sample.is_solvable());
+        assert!(sample.is_consistent(&parse_formula("G F x0")));
+        assert_eq!(sample.misclassified(&parse_formula("x1")).len(), 1);
+        let serialized = ron::ser::to_string(&sample).expect("serialize sample");
+        assert_eq!(
+            ron::de::from_str::<LassoSample>(&serialized),
+            Ok(sample.clone())
+        );
+
+        // The same infinite trace cannot be both positive and negative.
+        let mut sample = sample;
+        let same = Lasso::new(
+            vec![vec![true, false], vec![false, true]],
+            vec![vec![true, false], vec![false, true]],
+        );
+        assert_eq!(sample.add_negative_trace(same.clone()), Err(()));
+        sample.negative_traces.push(same);
+        assert!(!sample.is_solvable());
+
+        assert!(ron::de::from_str::<LassoSample>(
+            "(positive_traces: [(prefix: [(true, false)], cycle: [])], negative_traces: [])",
+        )
+        .is_err());
+        assert!(ron::de::from_str::<LassoSample>(
+            "(positive_traces: [(prefix: [(true)], cycle: [(true, false)])], negative_traces: [])",
+        )
+        .is_err());
+    }
+}
@@ -1,8 +1,19 @@
+use crate::lasso::*;
 use crate::syntax::*;
 use crate::trace::*;
 use itertools::Itertools;
+use rayon::prelude::*;
 
+use std::fmt;
+use std::str::FromStr;
+
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+mod verify;
+
+pub use verify::*;
 
 /// A tree structure with unary and binary nodes, but containing no data.
 #[derive(Debug, Clone)]
@@ -15,183 +26,1471 @@ pub enum SkeletonTree {
 impl SkeletonTree {
     /// Generates all possible `SkeletonTree`s of the given size,
     /// where the size is given by the number of leaves.
+    /// Skeletons of smaller size are generated only once, and shared as subtrees of the larger ones.
     pub fn gen(size: usize) -> Vec<SkeletonTree> {
-        match size {
-            0 => panic!("No tree of size 0"),
-            1 => vec![SkeletonTree::Leaf],
-            size => {
-                let smaller_skeletons = Self::gen(size - 1);
-                let mut skeletons: Vec<SkeletonTree> = smaller_skeletons
-                    .into_iter()
-                    .map(|branch| SkeletonTree::UnaryNode(Arc::new(branch)))
-                    .collect();
-                for left_size in 1..(size - 1) {
-                    let left_smaller_skeletons = Self::gen(left_size);
-                    let right_smaller_skeletons = Self::gen(size - 1 - left_size);
+        assert!(size > 0, "No tree of size 0");
+        // `skeletons[n]` contains the skeletons of size `n`.
+        let mut skeletons: Vec<Vec<SkeletonTree>> = vec![Vec::new(), vec![SkeletonTree::Leaf]];
+        for size in 2..=size {
+            let mut new_skeletons: Vec<SkeletonTree> = skeletons[size - 1]
+                .iter()
+                .map(|branch| SkeletonTree::UnaryNode(Arc::new(branch.clone())))
+                .collect();
+            for left_size in 1..(size - 1) {
+                new_skeletons.extend(
+                    skeletons[left_size]
+                        .iter()
+                        .cartesian_product(&skeletons[size - 1 - left_size])
+                        .map(|(left, right)| {
+                            SkeletonTree::BinaryNode(Arc::new((left.clone(), right.clone())))
+                        }),
+                );
+            }
+            skeletons.push(new_skeletons);
+        }
 
-                    skeletons.extend(
-                        left_smaller_skeletons
-                            .into_iter()
-                            .cartesian_product(right_smaller_skeletons.into_iter())
-                            .map(|branches| SkeletonTree::BinaryNode(Arc::new(branches))),
-                    );
-                }
-                skeletons.shrink_to_fit();
+        skeletons.swap_remove(size)
+    }
 
-                skeletons
+    /// The number of nodes of the `SkeletonTree`.
+    pub fn size(&self) -> usize {
+        match self {
+            SkeletonTree::Leaf => 1,
+            SkeletonTree::UnaryNode(child) => 1 + child.size(),
+            SkeletonTree::BinaryNode(children) => 1 + children.0.size() + children.1.size(),
+        }
+    }
+
+    /// The minimum and maximum size of the formulae fitting the `SkeletonTree` using the given operators,
+    /// or `None` if there is no such formula.
+    fn size_range(&self, operators: &Operators) -> Option<(usize, usize)> {
+        let costs = |arity| {
+            let costs = operators.costs(arity);
+            Some((*costs.first()?, *costs.last()?))
+        };
+        match self {
+            // Atoms have unit size, constants have their own cost.
+            SkeletonTree::Leaf => {
+                Some(costs(0).map_or((1, 1), |(min, max)| (min.min(1), max.max(1))))
+            }
+            SkeletonTree::UnaryNode(child) => {
+                let (min_cost, max_cost) = costs(1)?;
+                let (min, max) = child.size_range(operators)?;
+                Some((min + min_cost, max + max_cost))
+            }
+            SkeletonTree::BinaryNode(children) => {
+                let (min_cost, max_cost) = costs(2)?;
+                let (left_min, left_max) = children.0.size_range(operators)?;
+                let (right_min, right_max) = children.1.size_range(operators)?;
+                Some((
+                    left_min + right_min + min_cost,
+                    left_max + right_max + max_cost,
+                ))
             }
         }
     }
 
-    /// Generates all possible LTL formulae whose structure fits that of the `SkeletonTree`,
-    /// in the sense that leaves of the `SkeletonTree` correspond to propositional variables,
+    /// Generates all possible LTL formulae of the given size whose structure fits that of the `SkeletonTree`,
+    /// in the sense that leaves of the `SkeletonTree` correspond to constants and propositional variables,
     /// unary nodes of the `SkeletonTree` correspond to unary operators of LTL,
     /// and binary nodes of the `SkeletonTree` correspond to binary operators of LTL.
+    /// Only the given operators are used, and the size of a formula is computed from their costs
+    /// (with unit costs, formulae have the same size as the `SkeletonTree`).
     /// After being generated, a formula is checked under filtering criteria,
     /// and discarded if found to be equivalent to other formulae that have been or will included anyway.
-    /// The const generic N represents the set of propositional variables which might appear in the generated formulae.
-    pub fn gen_formulae<const N: usize>(&self, vars: &[Idx]) -> Vec<SyntaxTree> {
+    /// The slice `vars` contains the propositional variables which might appear in the generated formulae.
+    pub fn gen_formulae(&self, vars: &[Idx], operators: Operators, size: usize) -> Vec<SyntaxTree> {
+        self.formulae(vars, operators, size).collect()
+    }
+
+    /// Lazily generates the same formulae as `gen_formulae`, in the same order.
+    ///
+    /// Only the formulae fitting the right branch of each binary node are kept in memory
+    /// (as they are combined with every formula fitting the left branch),
+    /// rather than all the formulae fitting the `SkeletonTree`.
+    pub fn formulae<'a>(
+        &self,
+        vars: &'a [Idx],
+        operators: Operators,
+        size: usize,
+    ) -> Box<dyn Iterator<Item = SyntaxTree> + Send + 'a> {
+        if !self
+            .size_range(&operators)
+            .is_some_and(|(min, max)| (min..=max).contains(&size))
+        {
+            return Box::new(std::iter::empty());
+        }
+        // Operators are grouped by cost, and within each group they are applied in the order of `Operator::ALL`.
+        let has_cost = move |op, cost| operators.cost(op) == Some(cost);
         match self {
-            // Leaves of the `SkeletonTree` correspond to propositional variables
-            SkeletonTree::Leaf => vars
+            // Leaves of the `SkeletonTree` correspond to constants and propositional variables
+            SkeletonTree::Leaf => Box::new(
+                [
+                    has_cost(Operator::True, size).then_some(SyntaxTree::True),
+                    has_cost(Operator::False, size).then_some(SyntaxTree::False),
+                ]
                 .into_iter()
-                .map(|n| SyntaxTree::Atom(*n))
-                .collect::<Vec<SyntaxTree>>(),
+                .flatten()
+                .chain(
+                    vars.iter()
+                        .filter(move |_| size == 1)
+                        .map(|n| SyntaxTree::Atom(*n)),
+                ),
+            ),
             // Unary nodes of the `SkeletonTree` correspond to unary operators of LTL
             SkeletonTree::UnaryNode(child) => {
-                let children = child.gen_formulae::<N>(vars);
-                // Use known bounds to allocate just as much memory as needed and avoid reallocations.
-                let mut trees = Vec::with_capacity(4 * children.len());
+                let child = child.clone();
+                Box::new(operators.costs(1).into_iter().flat_map(move |cost| {
+                    child
+                        .formulae(vars, operators, size - cost)
+                        .flat_map(move |child| {
+                            let child = Arc::new(child);
+                            [
+                                (has_cost(Operator::Not, cost) && check_not(&child, &operators))
+                                    .then(|| SyntaxTree::Not(child.clone())),
+                                (has_cost(Operator::Next, cost) && check_next(&child))
+                                    .then(|| SyntaxTree::Next(child.clone())),
+                                (has_cost(Operator::WeakNext, cost)
+                                    && check_weak_next(&child, &operators))
+                                .then(|| SyntaxTree::WeakNext(child.clone())),
+                                (has_cost(Operator::Globally, cost)
+                                    && check_globally(&child, &operators))
+                                .then(|| SyntaxTree::Globally(child.clone())),
+                                (has_cost(Operator::Finally, cost)
+                                    && check_finally(&child, &operators))
+                                .then(|| SyntaxTree::Finally(child.clone())),
+                                (has_cost(Operator::Yesterday, cost) && check_yesterday(&child))
+                                    .then(|| SyntaxTree::Yesterday(child.clone())),
+                                (has_cost(Operator::Once, cost) && check_once(&child))
+                                    .then(|| SyntaxTree::Once(child.clone())),
+                                (has_cost(Operator::Historically, cost)
+                                    && check_historically(&child, &operators))
+                                .then(|| SyntaxTree::Historically(child.clone())),
+                            ]
+                            .into_iter()
+                            .flatten()
+                            // Bounded operators are applied with every interval up to the horizon.
+                            .chain(
+                                operators
+                                    .intervals()
+                                    .filter(move |_| has_cost(Operator::BoundedGlobally, cost))
+                                    .filter_map({
+                                        let child = child.clone();
+                                        move |interval| {
+                                            check_bounded_globally(interval, &child, &operators)
+                                                .then_some(SyntaxTree::BoundedGlobally(
+                                                    interval,
+                                                    child.clone(),
+                                                ))
+                                        }
+                                    }),
+                            )
+                            .chain(
+                                operators
+                                    .intervals()
+                                    .filter(move |_| has_cost(Operator::BoundedFinally, cost))
+                                    .filter_map(move |interval| {
+                                        check_bounded_finally(interval, &child, &operators)
+                                            .then_some(SyntaxTree::BoundedFinally(
+                                                interval,
+                                                child.clone(),
+                                            ))
+                                    }),
+                            )
+                        })
+                }))
+            }
+            // Binary nodes of the `SkeletonTree` correspond to binary operators of LTL
+            SkeletonTree::BinaryNode(children) => {
+                let children = children.clone();
+                // Both children fit some formulae, as the `SkeletonTree` does.
+                let (left_min, left_max) = children.0.size_range(&operators).unwrap_or_default();
+                let (right_min, right_max) = children.1.size_range(&operators).unwrap_or_default();
+                Box::new(operators.costs(2).into_iter().flat_map(move |cost| {
+                    let children = children.clone();
+                    let children_size = size.saturating_sub(cost);
+                    let left_sizes = left_min.max(children_size.saturating_sub(right_max))
+                        ..=left_max.min(children_size.saturating_sub(right_min));
+                    left_sizes.flat_map(move |left_size| {
+                        let right_children: Arc<[Arc<SyntaxTree>]> = children
+                            .1
+                            .formulae(vars, operators, children_size - left_size)
+                            .map(Arc::new)
+                            .collect();
+                        let left_children = if right_children.is_empty() {
+                            Box::new(std::iter::empty())
+                        } else {
+                            children.0.formulae(vars, operators, left_size)
+                        };
+                        left_children.flat_map(move |left_child| {
+                            let left_child = Arc::new(left_child);
+                            let right_children = right_children.clone();
+                            (0..right_children.len()).flat_map(move |idx| {
+                                let left_child = left_child.clone();
+                                let right_child = right_children[idx].clone();
+                                [
+                                    (has_cost(Operator::And, cost)
+                                        && check_and(&left_child, &right_child, &operators))
+                                    .then(|| {
+                                        SyntaxTree::And(left_child.clone(), right_child.clone())
+                                    }),
+                                    (has_cost(Operator::Or, cost)
+                                        && check_or(&left_child, &right_child, &operators))
+                                    .then(|| {
+                                        SyntaxTree::Or(left_child.clone(), right_child.clone())
+                                    }),
+                                    (has_cost(Operator::Implies, cost)
+                                        && check_implies(&left_child, &right_child, &operators))
+                                    .then(|| {
+                                        SyntaxTree::Implies(left_child.clone(), right_child.clone())
+                                    }),
+                                    (has_cost(Operator::Until, cost)
+                                        && check_until(&left_child, &right_child, &operators))
+                                    .then(|| {
+                                        SyntaxTree::Until(left_child.clone(), right_child.clone())
+                                    }),
+                                    (has_cost(Operator::Release, cost)
+                                        && check_release(&left_child, &right_child, &operators))
+                                    .then(|| {
+                                        SyntaxTree::Release(left_child.clone(), right_child.clone())
+                                    }),
+                                    (has_cost(Operator::WeakUntil, cost)
+                                        && check_weak_until(&left_child, &right_child, &operators))
+                                    .then(|| {
+                                        SyntaxTree::WeakUntil(
+                                            left_child.clone(),
+                                            right_child.clone(),
+                                        )
+                                    }),
+                                    (has_cost(Operator::Since, cost)
+                                        && check_since(&left_child, &right_child, &operators))
+                                    .then(|| {
+                                        SyntaxTree::Since(left_child.clone(), right_child.clone())
+                                    }),
+                                ]
+                                .into_iter()
+                                .flatten()
+                                .chain(
+                                    operators
+                                        .intervals()
+                                        .filter(move |_| has_cost(Operator::BoundedUntil, cost))
+                                        .filter_map(move |interval| {
+                                            check_bounded_until(
+                                                interval,
+                                                &left_child,
+                                                &right_child,
+                                                &operators,
+                                            )
+                                            .then_some(
+                                                SyntaxTree::BoundedUntil(
+                                                    interval,
+                                                    left_child.clone(),
+                                                    right_child.clone(),
+                                                ),
+                                            )
+                                        }),
+                                )
+                            })
+                        })
+                    })
+                }))
+            }
+        }
+    }
+}
 
-                for child in children {
-                    let child = Arc::new(child);
+/// Generates all formulae of the given size over the given variables,
+/// discarding those filtered out by `SkeletonTree::gen_formulae`.
+/// The default operators are used, with unit cost (see `Operators::gen_formulae` otherwise).
+pub fn gen_formulae(size: usize, vars: &[Idx]) -> Vec<SyntaxTree> {
+    Operators::default().gen_formulae(size, vars)
+}
 
-                    if check_not(child.as_ref()) {
-                        trees.push(SyntaxTree::Not(child.clone()));
-                    }
+/// Lazily generates the same formulae as `gen_formulae`, in the same order.
+pub fn formulae(size: usize, vars: &[Idx]) -> impl Iterator<Item = SyntaxTree> + '_ {
+    Operators::default().formulae(size, vars)
+}
 
-                    if check_next(child.as_ref()) {
-                        trees.push(SyntaxTree::Next(child.clone()));
-                    }
+/// Lazily generates the same formulae as `gen_formulae` in parallel,
+/// with the skeletons of the formulae split among threads.
+/// Order-aware adaptors, such as `find_first` or `collect`, follow the order of `gen_formulae`.
+pub fn par_formulae(size: usize, vars: &[Idx]) -> impl ParallelIterator<Item = SyntaxTree> + '_ {
+    Operators::default().par_formulae(size, vars)
+}
 
-                    if check_globally(child.as_ref()) {
-                        trees.push(SyntaxTree::Globally(child.clone()));
-                    }
+/// The operators of LTL that can appear in learned formulae.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Operator {
+    True,
+    False,
+    Not,
+    Next,
+    WeakNext,
+    Globally,
+    Finally,
+    Yesterday,
+    Once,
+    Historically,
+    BoundedGlobally,
+    BoundedFinally,
+    And,
+    Or,
+    Implies,
+    Until,
+    Release,
+    WeakUntil,
+    Since,
+    BoundedUntil,
+}
 
-                    if check_finally(child.as_ref()) {
-                        trees.push(SyntaxTree::Finally(child));
-                    }
-                }
+impl Operator {
+    /// All operators, constants first, then unary ones.
+    pub const ALL: [Operator; 20] = [
+        Operator::True,
+        Operator::False,
+        Operator::Not,
+        Operator::Next,
+        Operator::WeakNext,
+        Operator::Globally,
+        Operator::Finally,
+        Operator::Yesterday,
+        Operator::Once,
+        Operator::Historically,
+        Operator::BoundedGlobally,
+        Operator::BoundedFinally,
+        Operator::And,
+        Operator::Or,
+        Operator::Implies,
+        Operator::Until,
+        Operator::Release,
+        Operator::WeakUntil,
+        Operator::Since,
+        Operator::BoundedUntil,
+    ];
 
-                trees.shrink_to_fit();
+    /// The number of operands of the operator: 0 for constants, 1 for unary operators and 2 for binary ones.
+    pub fn arity(self) -> usize {
+        match self {
+            Operator::True | Operator::False => 0,
+            op if op.is_unary() => 1,
+            _ => 2,
+        }
+    }
 
-                trees
-            }
-            // Binary nodes of the `SkeletonTree` correspond to binary operators of LTL
-            SkeletonTree::BinaryNode(child) => {
-                let left_children: Vec<Arc<SyntaxTree>> = child
-                    .0
-                    .gen_formulae::<N>(vars)
-                    .into_iter()
-                    .map(Arc::new)
-                    .collect();
-                let right_children: Vec<Arc<SyntaxTree>> = child
-                    .1
-                    .gen_formulae::<N>(vars)
-                    .into_iter()
-                    .map(Arc::new)
-                    .collect();
-                // Use known bounds to allocate just as much memory as needed and avoid reallocations.
-                let mut trees = Vec::with_capacity(4 * left_children.len() * right_children.len());
-                let children = left_children
-                    .into_iter()
-                    .cartesian_product(right_children.into_iter());
-
-                for (left_child, right_child) in children {
-                    if check_and(left_child.as_ref(), right_child.as_ref()) {
-                        trees.push(SyntaxTree::And(left_child.clone(), right_child.clone()));
-                    }
+    pub fn is_unary(self) -> bool {
+        matches!(
+            self,
+            Operator::Not
+                | Operator::Next
+                | Operator::WeakNext
+                | Operator::Globally
+                | Operator::Finally
+                | Operator::Yesterday
+                | Operator::Once
+                | Operator::Historically
+                | Operator::BoundedGlobally
+                | Operator::BoundedFinally
+        )
+    }
+}
 
-                    if check_or(left_child.as_ref(), right_child.as_ref()) {
-                        trees.push(SyntaxTree::Or(left_child.clone(), right_child.clone()));
-                    }
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let symbol = match self {
+            Operator::True => "⊤",
+            Operator::False => "⊥",
+            Operator::Not => "¬",
+            Operator::Next => "X",
+            Operator::WeakNext => "N",
+            Operator::Globally => "G",
+            Operator::Finally => "F",
+            Operator::Yesterday => "Y",
+            Operator::Once => "O",
+            Operator::Historically => "H",
+            Operator::BoundedGlobally => "G[]",
+            Operator::BoundedFinally => "F[]",
+            Operator::And => "∧",
+            Operator::Or => "∨",
+            Operator::Implies => "→",
+            Operator::Until => "U",
+            Operator::Release => "R",
+            Operator::WeakUntil => "W",
+            Operator::Since => "S",
+            Operator::BoundedUntil => "U[]",
+        };
+        write!(f, "{symbol}")
+    }
+}
 
-                    if check_implies(left_child.as_ref(), right_child.as_ref()) {
-                        trees.push(SyntaxTree::Implies(left_child.clone(), right_child.clone()));
-                    }
+impl FromStr for Operator {
+    type Err = String;
 
-                    if check_until(left_child.as_ref(), right_child.as_ref()) {
-                        trees.push(SyntaxTree::Until(left_child, right_child));
-                    }
-                }
+    /// Parses the symbols used to print formulae, as well as their ASCII alternatives accepted by the formula parser.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "⊤" | "true" => Ok(Operator::True),
+            "⊥" | "false" => Ok(Operator::False),
+            "¬" | "!" => Ok(Operator::Not),
+            "X" => Ok(Operator::Next),
+            "N" => Ok(Operator::WeakNext),
+            "G" => Ok(Operator::Globally),
+            "F" => Ok(Operator::Finally),
+            "Y" => Ok(Operator::Yesterday),
+            "O" => Ok(Operator::Once),
+            "H" => Ok(Operator::Historically),
+            "G[]" => Ok(Operator::BoundedGlobally),
+            "F[]" => Ok(Operator::BoundedFinally),
+            "∧" | "&" | "&&" => Ok(Operator::And),
+            "∨" | "|" | "||" => Ok(Operator::Or),
+            "→" | "->" => Ok(Operator::Implies),
+            "U" => Ok(Operator::Until),
+            "R" => Ok(Operator::Release),
+            "W" => Ok(Operator::WeakUntil),
+            "S" => Ok(Operator::Since),
+            "U[]" => Ok(Operator::BoundedUntil),
+            _ => Err(format!("unknown operator: {s}")),
+        }
+    }
+}
+
+/// The set of operators allowed in learned formulae, each with its cost.
+///
+/// The size of a formula is the sum of the costs of its operators (constants included), plus one for each of its atoms,
+/// so that with the default unit costs it is the number of nodes of its syntax tree.
+/// Solvers search formulae by increasing size, so costlier operators appear in solutions only when they pay off.
+///
+/// Bounded operators (written `G[]`, `F[]` and `U[]`) are searched with every interval `[a,b]` with `b` up to the horizon,
+/// which does not count towards the size of formulae.
+///
+/// The filtering criteria of the search depend on the semantics of the formulae,
+/// as some equivalences only hold on finite traces and some only on infinite ones (see `Semantics`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Operators {
+    costs: [Option<usize>; Operator::ALL.len()],
+    horizon: Time,
+    semantics: Semantics,
+}
+
+/// The traces on which formulae are evaluated.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Semantics {
+    /// Finite traces, as by `SyntaxTree::eval` (LTLf).
+    #[default]
+    Finite,
+    /// Infinite traces, given as lassos, as by `SyntaxTree::eval_lasso` (LTL).
+    Infinite,
+}
 
-                trees.shrink_to_fit();
+impl Default for Operators {
+    /// The operators ¬, X, G, F, ∧, ∨, → and U, with unit cost.
+    ///
+    /// Weak next, release and weak until are left out, as they can be expressed by the other operators
+    /// (N φ ≡ ¬X¬φ, φ R ψ ≡ ¬(¬φ U ¬ψ) and φ W ψ ≡ (φ U ψ) ∨ G φ):
+    /// they make for smaller solutions, but also for many more formulae to search.
+    /// Past operators are left out too, as they are only needed to learn pure-past formulae,
+    /// and so are the constants ⊤ and ⊥, which are only needed for trivial solutions,
+    /// and the bounded operators, which multiply the formulae to search by the number of intervals.
+    fn default() -> Self {
+        Operators::none()
+            .with(Operator::Not)
+            .with(Operator::Next)
+            .with(Operator::Globally)
+            .with(Operator::Finally)
+            .with(Operator::And)
+            .with(Operator::Or)
+            .with(Operator::Implies)
+            .with(Operator::Until)
+    }
+}
+
+impl Operators {
+    /// The horizon of bounded operators, unless otherwise set.
+    pub const DEFAULT_HORIZON: Time = 5;
+
+    /// All operators, with unit cost.
+    pub fn all() -> Self {
+        Operators {
+            costs: [Some(1); Operator::ALL.len()],
+            horizon: Self::DEFAULT_HORIZON,
+            semantics: Semantics::Finite,
+        }
+    }
+
+    /// No operators at all, so that only atoms are allowed.
+    pub fn none() -> Self {
+        Operators {
+            costs: [None; Operator::ALL.len()],
+            horizon: Self::DEFAULT_HORIZON,
+            semantics: Semantics::Finite,
+        }
+    }
+
+    /// Sets the largest end of the intervals of bounded operators.
+    pub fn with_horizon(mut self, horizon: Time) -> Self {
+        self.horizon = horizon;
+        self
+    }
+
+    pub fn horizon(&self) -> Time {
+        self.horizon
+    }
+
+    /// Sets the semantics the filtering criteria of the search have to be sound for.
+    pub fn with_semantics(mut self, semantics: Semantics) -> Self {
+        self.semantics = semantics;
+        self
+    }
+
+    pub fn semantics(&self) -> Semantics {
+        self.semantics
+    }
+
+    /// The intervals of bounded operators, ordered by start and then by end.
+    pub fn intervals(&self) -> impl Iterator<Item = Interval> {
+        let horizon = self.horizon;
+        (0..=horizon)
+            .flat_map(move |start| (start..=horizon).map(move |end| Interval::new(start, end)))
+    }
+
+    /// Allows the given operator, with unit cost.
+    pub fn with(self, op: Operator) -> Self {
+        self.with_cost(op, 1)
+    }
+
+    /// Allows the given operator, with the given (positive) cost.
+    pub fn with_cost(mut self, op: Operator, cost: usize) -> Self {
+        assert!(cost > 0, "operators must have positive cost");
+        self.costs[op as usize] = Some(cost);
+        self
+    }
+
+    /// Disallows the given operator.
+    pub fn without(mut self, op: Operator) -> Self {
+        self.costs[op as usize] = None;
+        self
+    }
+
+    /// Returns the cost of the operator, or `None` if it is not allowed.
+    pub fn cost(&self, op: Operator) -> Option<usize> {
+        self.costs[op as usize]
+    }
+
+    pub fn contains(&self, op: Operator) -> bool {
+        self.cost(op).is_some()
+    }
+
+    /// Iterates over the allowed operators, in the order of `Operator::ALL`.
+    pub fn iter(&self) -> impl Iterator<Item = (Operator, usize)> + '_ {
+        Operator::ALL
+            .into_iter()
+            .filter_map(|op| self.cost(op).map(|cost| (op, cost)))
+    }
+
+    /// The distinct costs of the allowed operators of the given arity, in increasing order.
+    fn costs(&self, arity: usize) -> Vec<usize> {
+        let mut costs = self
+            .iter()
+            .filter(|(op, _)| op.arity() == arity)
+            .map(|(_, cost)| cost)
+            .collect_vec();
+        costs.sort_unstable();
+        costs.dedup();
+        costs
+    }
+
+    /// Checks whether a formula can be rewritten by replacing the operators `removed` with the operator `added`,
+    /// without exceeding the original size.
+    /// Filtering criteria relying on such a rewriting only apply if this holds,
+    /// as otherwise the rewritten formula might never be generated.
+    fn can_replace(&self, removed: &[Operator], added: Operator) -> bool {
+        self.cost(added)
+            .is_some_and(|cost| cost <= removed.iter().map(|op| self.cost(*op).unwrap_or(0)).sum())
+    }
+
+    /// Generates all formulae of the given size over the given variables, using only the allowed operators,
+    /// discarding those filtered out by `SkeletonTree::gen_formulae`.
+    pub fn gen_formulae(&self, size: usize, vars: &[Idx]) -> Vec<SyntaxTree> {
+        self.formulae(size, vars).collect_vec()
+    }
+
+    /// Lazily generates the same formulae as `gen_formulae`, in the same order.
+    pub fn formulae<'a>(
+        &self,
+        size: usize,
+        vars: &'a [Idx],
+    ) -> impl Iterator<Item = SyntaxTree> + 'a {
+        let operators = *self;
+        self.skeletons(size)
+            .flat_map(move |skeleton| skeleton.formulae(vars, operators, size))
+    }
+
+    /// Lazily generates the same formulae as `gen_formulae` in parallel,
+    /// with the skeletons of the formulae split among threads.
+    /// Order-aware adaptors, such as `find_first` or `collect`, follow the order of `gen_formulae`.
+    pub fn par_formulae<'a>(
+        &self,
+        size: usize,
+        vars: &'a [Idx],
+    ) -> impl ParallelIterator<Item = SyntaxTree> + 'a {
+        let operators = *self;
+        self.skeletons(size)
+            .collect_vec()
+            .into_par_iter()
+            .flat_map_iter(move |skeleton| skeleton.formulae(vars, operators, size))
+    }
+
+    /// The skeletons of the formulae of the given size, by increasing number of nodes.
+    fn skeletons(&self, size: usize) -> impl Iterator<Item = SkeletonTree> {
+        let max_cost = self.iter().map(|(_, cost)| cost).max().unwrap_or(1);
+        // Every node costs between 1 and `max_cost`.
+        (size.div_ceil(max_cost)..=size)
+            .filter(|&nodes| nodes > 0)
+            .flat_map(SkeletonTree::gen)
+    }
+}
+
+impl fmt::Display for Operators {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, (op, cost)) in self.iter().enumerate() {
+            if idx > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{op}")?;
+            if cost != 1 {
+                write!(f, ":{cost}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Operators {
+    type Err = String;
 
-                trees
+    /// Parses a comma-separated list of operators, such as `G,F,&,|,->`,
+    /// each optionally followed by its cost, such as `U:2`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut operators = Operators::none();
+        for item in s.split(',') {
+            let (op, cost) = match item.split_once(':') {
+                Some((op, cost)) => {
+                    let cost = cost.trim().parse().map_err(|err| format!("{err}"))?;
+                    (op, cost)
+                }
+                None => (item, 1),
+            };
+            let op: Operator = op.parse()?;
+            if cost == 0 {
+                return Err(format!("operator {op} must have positive cost"));
             }
+            operators = operators.with_cost(op, cost);
+        }
+        Ok(operators)
+    }
+}
+
+/// A flag to cancel a search from another thread.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests the searches using this token (or any of its clones) to stop.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Options of the search for a solution, shared by all solving algorithms.
+#[derive(Debug, Clone, Default)]
+pub struct SolveOptions {
+    /// Use parallel search via multithreading, where supported.
+    pub multithread: bool,
+    /// Print the progress of the search.
+    pub log: bool,
+    /// Give up after searching formulae up to this size.
+    pub max_size: Option<usize>,
+    /// The operators allowed in the solution, and their costs contributing to its size.
+    pub operators: Operators,
+    /// Give up after this much time has passed since the start of the search.
+    pub timeout: Option<Duration>,
+    /// Give up when cancelled.
+    pub cancellation: Option<CancellationToken>,
+}
+
+/// The result of the search for a solution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome<T = SyntaxTree> {
+    /// A solution was found.
+    Solved(T),
+    /// The sample has no solution at all (e.g., a trace is both positive and negative).
+    Unsolvable,
+    /// There is no solution up to the maximum size, included.
+    Exhausted { max_size: usize },
+    /// The timeout expired while searching formulae of the given size (there is no smaller solution).
+    TimedOut { size: usize },
+    /// The search was cancelled while searching formulae of the given size (there is no smaller solution).
+    Cancelled { size: usize },
+}
+
+impl<T> Outcome<T> {
+    /// Returns the solution, if one was found.
+    pub fn solution(self) -> Option<T> {
+        match self {
+            Outcome::Solved(solution) => Some(solution),
+            _ => None,
+        }
+    }
+
+    pub fn is_solved(&self) -> bool {
+        matches!(self, Outcome::Solved(_))
+    }
+
+    /// Maps the solution, if one was found.
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Outcome<U> {
+        match self {
+            Outcome::Solved(solution) => Outcome::Solved(f(solution)),
+            Outcome::Unsolvable => Outcome::Unsolvable,
+            Outcome::Exhausted { max_size } => Outcome::Exhausted { max_size },
+            Outcome::TimedOut { size } => Outcome::TimedOut { size },
+            Outcome::Cancelled { size } => Outcome::Cancelled { size },
         }
     }
 }
 
-pub fn gen_formulae<const N: usize>(size: usize, vars: &[Idx]) -> Vec<SyntaxTree> {
-    SkeletonTree::gen(size)
-        .into_iter()
-        .flat_map(|skeleton| skeleton.gen_formulae::<N>(vars))
-        .collect_vec()
+/// The limits of a running search, given by its `SolveOptions`.
+pub(crate) struct Limits<'a> {
+    pub(crate) max_size: usize,
+    deadline: Option<Instant>,
+    cancellation: Option<&'a CancellationToken>,
 }
 
-/// Find a formula consistent with the given `Sample`.
+impl<'a> Limits<'a> {
+    pub(crate) fn new(options: &'a SolveOptions) -> Self {
+        Limits {
+            max_size: options.max_size.unwrap_or(usize::MAX),
+            deadline: options.timeout.map(|timeout| Instant::now() + timeout),
+            cancellation: options.cancellation.as_ref(),
+        }
+    }
+
+    /// Checks whether the search has to stop.
+    pub(crate) fn stopped(&self) -> bool {
+        self.cancellation
+            .is_some_and(|cancellation| cancellation.is_cancelled())
+            || self
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    /// The outcome of a search stopped while searching formulae of the given size.
+    pub(crate) fn interrupted<T>(&self, size: usize) -> Outcome<T> {
+        if self
+            .cancellation
+            .is_some_and(|cancellation| cancellation.is_cancelled())
+        {
+            Outcome::Cancelled { size }
+        } else {
+            Outcome::TimedOut { size }
+        }
+    }
+}
+
+/// Find a formula consistent with the given sample (either a `Sample` or a `DynSample`).
 /// Uses a fundamentally brute-force search algorithm.
-// Parallel search is faster but less consistent then single-threaded search
-pub fn solve<const N: usize>(
-    sample: &Sample<N>,
-    multithread: bool,
-    log: bool,
-) -> Option<SyntaxTree> {
-    use rayon::prelude::*;
+/// Parallel search returns the same formula as single-threaded search,
+/// i.e., the first consistent one in enumeration order.
+pub fn solve<S: LabeledSample>(sample: &S, options: &SolveOptions) -> Outcome {
+    if !sample.is_solvable() {
+        return Outcome::Unsolvable;
+    }
+
+    // Traces are converted once and for all to speed up evaluating formulae over them.
+    let bit_sample = BitSample::new(sample);
+    search(&sample.vars(), options, |formula| {
+        bit_sample.is_consistent(&formula).then_some(formula)
+    })
+}
 
+/// Find a formula consistent with the given sample of infinite traces (given as lassos),
+/// under the standard semantics of LTL on infinite traces (see `SyntaxTree::eval_lasso`).
+/// Uses the same search as `solve`, with filtering criteria sound for infinite traces
+/// (the semantics of `options.operators` is overridden).
+pub fn solve_lasso(sample: &LassoSample, options: &SolveOptions) -> Outcome {
     if !sample.is_solvable() {
-        return None;
+        return Outcome::Unsolvable;
     }
 
-    let vars = &sample.vars();
+    let options = SolveOptions {
+        operators: options.operators.with_semantics(Semantics::Infinite),
+        ..options.clone()
+    };
+    search(&sample.vars(), &options, |formula| {
+        sample.is_consistent(&formula).then_some(formula)
+    })
+}
+
+/// Searches formulae over the given variables by increasing size, until one is accepted.
+fn search<T, F>(vars: &[Idx], options: &SolveOptions, accept: F) -> Outcome<T>
+where
+    T: Send,
+    F: Fn(SyntaxTree) -> Option<T> + Sync,
+{
+    let limits = Limits::new(options);
+    // `Some(None)` stops the search without a solution.
+    let check = |formula: SyntaxTree| {
+        if limits.stopped() {
+            Some(None)
+        } else {
+            accept(formula).map(Some)
+        }
+    };
 
-    (1..).into_iter().find_map(|size| {
-        if log {
+    for size in 1..=limits.max_size {
+        if options.log {
             println!("Searching formulae of size {}", size);
         }
-        // At small size, the overhead for parallel iterators is not worth it.
-        // At larger size, we use parallel iterators for speed.
-        if multithread {
-            SkeletonTree::gen(size)
-                .into_par_iter()
-                .flat_map(|skeleton| skeleton.gen_formulae::<N>(vars))
-                .find_any(|formula| sample.is_consistent(formula))
+        // `find_map_first` still searches in parallel,
+        // but returns the first formula accepted in enumeration order, as the sequential search does.
+        let found = if options.multithread {
+            options
+                .operators
+                .par_formulae(size, vars)
+                .find_map_first(check)
         } else {
-            SkeletonTree::gen(size)
-                .into_iter()
-                .flat_map(|skeleton| skeleton.gen_formulae::<N>(vars))
-                .find(|formula| sample.is_consistent(formula))
+            options.operators.formulae(size, vars).find_map(check)
+        };
+        match found {
+            Some(Some(solution)) => return Outcome::Solved(solution),
+            Some(None) => return limits.interrupted(size),
+            None => {}
+        }
+    }
+
+    Outcome::Exhausted {
+        max_size: limits.max_size,
+    }
+}
+
+/// The number of traces of a sample that a formula is allowed to misclassify.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ErrorBudget {
+    /// At most the given number of traces.
+    Count(usize),
+    /// At most the given fraction of all the traces of the sample, between 0 and 1.
+    Rate(f64),
+}
+
+impl ErrorBudget {
+    /// Returns the maximum number of misclassified traces out of the given number of traces.
+    pub fn max_errors(&self, traces: usize) -> usize {
+        match self {
+            ErrorBudget::Count(count) => *count,
+            ErrorBudget::Rate(rate) => (rate * traces as f64).floor() as usize,
         }
+    }
+}
+
+impl std::str::FromStr for ErrorBudget {
+    type Err = String;
+
+    /// Parses either a count, such as `3`, or a rate, either as a fraction, such as `0.05`, or as a percentage, such as `5%`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rate = |rate: f64| {
+            if (0.0..=1.0).contains(&rate) {
+                Ok(ErrorBudget::Rate(rate))
+            } else {
+                Err(format!("error rate out of range: {s}"))
+            }
+        };
+        if let Some(percentage) = s.strip_suffix('%') {
+            let percentage: f64 = percentage.trim().parse().map_err(|err| format!("{err}"))?;
+            rate(percentage / 100.0)
+        } else if let Ok(count) = s.parse() {
+            Ok(ErrorBudget::Count(count))
+        } else {
+            rate(s.parse().map_err(|err| format!("{err}"))?)
+        }
+    }
+}
+
+/// A formula approximately consistent with a sample, together with the traces it misclassifies.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApproxSolution {
+    pub formula: SyntaxTree,
+    pub misclassified: Misclassified,
+}
+
+/// Find a smallest formula misclassifying no more traces of the given sample than allowed by the budget.
+/// Uses the same search as `solve`, which it reduces to with a budget of zero errors.
+///
+/// The outcome is `Unsolvable` if the budget is below the number of errors made by every formula
+/// because of traces appearing with both labels (see `LabeledSample::min_errors`).
+pub fn solve_approx<S: LabeledSample>(
+    sample: &S,
+    budget: ErrorBudget,
+    options: &SolveOptions,
+) -> Outcome<ApproxSolution> {
+    let traces = sample.positives().len() + sample.negatives().len();
+    let max_errors = budget.max_errors(traces);
+    if sample.min_errors() > max_errors {
+        return Outcome::Unsolvable;
+    }
+
+    let bit_sample = BitSample::new(sample);
+    search(&sample.vars(), options, |formula| {
+        bit_sample
+            .misclassified(&formula, max_errors)
+            .map(|misclassified| ApproxSolution {
+                formula,
+                misclassified,
+            })
     })
 }
 
-fn check_not(child: &SyntaxTree) -> bool {
+/// The formulae found by `solve_all`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solutions {
+    pub formulae: Vec<SyntaxTree>,
+    /// The size of formulae being searched when the search was stopped (by its timeout or by cancellation),
+    /// or `None` if it was not.
+    /// All the solutions of smaller size are found, but some of this size may be missing.
+    pub truncated_at: Option<usize>,
+}
+
+impl Solutions {
+    pub fn is_complete(&self) -> bool {
+        self.truncated_at.is_none()
+    }
+}
+
+/// Find all formulae consistent with the given sample, of minimal size up to `extra_size` more.
+/// Uses the same search as `solve`, so the formulae discarded by its equivalence filters are not returned.
+///
+/// The solutions are ordered by size, and then in the order they are enumerated,
+/// regardless of multithreading.
+/// If the search is stopped after finding some solution,
+/// the solutions found up to then are returned, marked as truncated.
+pub fn solve_all<S: LabeledSample>(
+    sample: &S,
+    extra_size: usize,
+    options: &SolveOptions,
+) -> Outcome<Solutions> {
+    use std::collections::BTreeSet;
+
+    if !sample.is_solvable() {
+        return Outcome::Unsolvable;
+    }
+
+    let vars = &sample.vars();
+    let bit_sample = BitSample::new(sample);
+    let limits = Limits::new(options);
+    // Whether `check` stopped the enumeration, in which case some formulae were not checked.
+    let interrupted = AtomicBool::new(false);
+    // `None` stops the search.
+    let check = |formula: SyntaxTree| {
+        if limits.stopped() {
+            interrupted.store(true, Ordering::Relaxed);
+            None
+        } else {
+            Some(bit_sample.is_consistent(&formula).then_some(formula))
+        }
+    };
+
+    let mut solutions = Vec::new();
+    let mut truncated_at = None;
+    let mut max_size = limits.max_size;
+    for size in 1..=limits.max_size {
+        if options.log {
+            println!("Searching formulae of size {}", size);
+        }
+        let found: Vec<SyntaxTree> = if options.multithread {
+            options
+                .operators
+                .par_formulae(size, vars)
+                .map(check)
+                .while_some()
+                .flatten()
+                .collect()
+        } else {
+            options
+                .operators
+                .formulae(size, vars)
+                .map_while(check)
+                .flatten()
+                .collect()
+        };
+        if interrupted.load(Ordering::Relaxed) {
+            if solutions.is_empty() && found.is_empty() {
+                return limits.interrupted(size);
+            }
+            truncated_at = Some(size);
+        }
+        if solutions.is_empty() && !found.is_empty() {
+            max_size = size.saturating_add(extra_size);
+        }
+        solutions.extend(found);
+        if size >= max_size || truncated_at.is_some() {
+            break;
+        }
+    }
+
+    if solutions.is_empty() {
+        Outcome::Exhausted {
+            max_size: limits.max_size,
+        }
+    } else {
+        let mut seen = BTreeSet::new();
+        solutions.retain(|formula| seen.insert(formula.clone()));
+        Outcome::Solved(Solutions {
+            formulae: solutions,
+            truncated_at,
+        })
+    }
+}
+
+/// Find a formula consistent with the given sample by bottom-up enumeration modulo observational equivalence.
+///
+/// Formulae are built by increasing size out of smaller ones, as in `solve`,
+/// but every formula is evaluated over the whole sample as soon as it is built,
+/// and it is discarded if its truth values at every time instant of every trace (its signature)
+/// are the same as those of a formula already found.
+/// Since formulae with the same signature behave in the same way as subformulae too,
+/// this does not affect the size of the solution found, which is minimal,
+/// but many fewer formulae are explored than by `solve`, at the cost of keeping all signatures in memory.
+/// The search is always single-threaded.
+pub fn solve_bottom_up<S: LabeledSample>(sample: &S, options: &SolveOptions) -> Outcome {
+    use std::collections::HashSet;
+
+    if !sample.is_solvable() {
+        return Outcome::Unsolvable;
+    }
+
+    let limits = Limits::new(options);
+    let layout = SignatureLayout::new(sample);
+    let mut seen_signatures: HashSet<Signature> = HashSet::new();
+    // Representatives of each signature, indexed by size (there is no formula of size 0).
+    let mut formulae: Vec<Vec<(Arc<SyntaxTree>, Signature)>> = vec![Vec::new()];
+
+    for size in 1..=limits.max_size {
+        if limits.stopped() {
+            return limits.interrupted(size);
+        }
+        let mut candidates = 0;
+        let mut new_formulae = Vec::new();
+        // Returns the formula if it is consistent.
+        let mut add = |formula: SyntaxTree, signature: Vec<u64>| {
+            candidates += 1;
+            let signature = Signature::from(signature);
+            if seen_signatures.insert(signature.clone()) {
+                let consistent = layout.is_consistent(&signature);
+                let formula = Arc::new(formula);
+                new_formulae.push((formula.clone(), signature));
+                consistent.then(|| formula.as_ref().clone())
+            } else {
+                None
+            }
+        };
+
+        for (_, constant, value) in CONSTANTS
+            .iter()
+            .filter(|(operator, ..)| options.operators.cost(*operator) == Some(size))
+        {
+            if let Some(solution) = add(constant.clone(), layout.constant(*value)) {
+                return Outcome::Solved(solution);
+            }
+        }
+        if size == 1 {
+            for var in sample.vars() {
+                if let Some(solution) = add(SyntaxTree::Atom(var), layout.atom(sample, var)) {
+                    return Outcome::Solved(solution);
+                }
+            }
+        } else {
+            // As in `SkeletonTree::formulae`, operators are grouped by cost.
+            let operators = options.operators;
+            for cost in operators.costs(1) {
+                let Some(child_size) = size.checked_sub(cost).filter(|size| *size > 0) else {
+                    continue;
+                };
+                for (child, signature) in &formulae[child_size] {
+                    if limits.stopped() {
+                        return limits.interrupted(size);
+                    }
+                    for (_, op, eval) in UNARY_OPERATORS
+                        .iter()
+                        .filter(|(operator, ..)| operators.cost(*operator) == Some(cost))
+                    {
+                        if let Some(solution) = add(op(child.clone()), eval(&layout, signature)) {
+                            return Outcome::Solved(solution);
+                        }
+                    }
+                    for (_, op, eval) in BOUNDED_UNARY_OPERATORS
+                        .iter()
+                        .filter(|(operator, ..)| operators.cost(*operator) == Some(cost))
+                    {
+                        for interval in operators.intervals() {
+                            let formula = op(interval, child.clone());
+                            if let Some(solution) = add(formula, eval(&layout, interval, signature))
+                            {
+                                return Outcome::Solved(solution);
+                            }
+                        }
+                    }
+                }
+            }
+
+            for (cost, left_size) in operators.costs(2).into_iter().flat_map(|cost| {
+                (1..size.saturating_sub(cost)).map(move |left_size| (cost, left_size))
+            }) {
+                let right_size = size - cost - left_size;
+                for (left_idx, (left_child, left_signature)) in
+                    formulae[left_size].iter().enumerate()
+                {
+                    for (right_idx, (right_child, right_signature)) in
+                        formulae[right_size].iter().enumerate()
+                    {
+                        if limits.stopped() {
+                            return limits.interrupted(size);
+                        }
+                        // Commutative operators are only applied to children in one order,
+                        // and not to a child and itself, since φ ∧ φ ≡ φ ∨ φ ≡ φ.
+                        let binary_operators = if left_size < right_size
+                            || (left_size == right_size && left_idx < right_idx)
+                        {
+                            &BINARY_OPERATORS[..]
+                        } else {
+                            &BINARY_OPERATORS[COMMUTATIVE_OPERATORS..]
+                        };
+                        for (_, op, eval) in binary_operators
+                            .iter()
+                            .filter(|(operator, ..)| operators.cost(*operator) == Some(cost))
+                        {
+                            let formula = op(left_child.clone(), right_child.clone());
+                            let signature = eval(&layout, left_signature, right_signature);
+                            if let Some(solution) = add(formula, signature) {
+                                return Outcome::Solved(solution);
+                            }
+                        }
+                        for (_, op, eval) in BOUNDED_BINARY_OPERATORS
+                            .iter()
+                            .filter(|(operator, ..)| operators.cost(*operator) == Some(cost))
+                        {
+                            for interval in operators.intervals() {
+                                let formula = op(interval, left_child.clone(), right_child.clone());
+                                let signature =
+                                    eval(&layout, interval, left_signature, right_signature);
+                                if let Some(solution) = add(formula, signature) {
+                                    return Outcome::Solved(solution);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        if options.log {
+            println!(
+                "Searched {} formulae of size {}, of which {} with new signatures",
+                candidates,
+                size,
+                new_formulae.len()
+            );
+        }
+        formulae.push(new_formulae);
+    }
+
+    Outcome::Exhausted {
+        max_size: limits.max_size,
+    }
+}
+
+const CONSTANTS: [(Operator, SyntaxTree, bool); 2] = [
+    (Operator::True, SyntaxTree::True, true),
+    (Operator::False, SyntaxTree::False, false),
+];
+
+type BoundedUnaryOperator = (
+    Operator,
+    fn(Interval, Arc<SyntaxTree>) -> SyntaxTree,
+    fn(&SignatureLayout, Interval, &[u64]) -> Vec<u64>,
+);
+
+const BOUNDED_UNARY_OPERATORS: [BoundedUnaryOperator; 2] = [
+    (
+        Operator::BoundedGlobally,
+        SyntaxTree::BoundedGlobally,
+        |layout, interval, child| {
+            layout.unary(child, |values, len| {
+                bounded_globally_in_place(values, len, interval)
+            })
+        },
+    ),
+    (
+        Operator::BoundedFinally,
+        SyntaxTree::BoundedFinally,
+        |layout, interval, child| {
+            layout.unary(child, |values, len| {
+                bounded_finally_in_place(values, len, interval)
+            })
+        },
+    ),
+];
+
+type BoundedBinaryOperator = (
+    Operator,
+    fn(Interval, Arc<SyntaxTree>, Arc<SyntaxTree>) -> SyntaxTree,
+    fn(&SignatureLayout, Interval, &[u64], &[u64]) -> Vec<u64>,
+);
+
+const BOUNDED_BINARY_OPERATORS: [BoundedBinaryOperator; 1] = [(
+    Operator::BoundedUntil,
+    SyntaxTree::BoundedUntil,
+    |layout, interval, left, right| {
+        // The result of `bounded_until_in_place` takes the place of the right operand.
+        layout.binary(right, left, |right, left, len| {
+            bounded_until_in_place(left, right, len, interval)
+        })
+    },
+)];
+
+type UnaryOperator = (
+    Operator,
+    fn(Arc<SyntaxTree>) -> SyntaxTree,
+    fn(&SignatureLayout, &[u64]) -> Vec<u64>,
+);
+
+const UNARY_OPERATORS: [UnaryOperator; 8] = [
+    (Operator::Not, SyntaxTree::Not, |layout, child| {
+        layout.unary(child, not_in_place)
+    }),
+    (Operator::Next, SyntaxTree::Next, |layout, child| {
+        layout.unary(child, |values, _| next_in_place(values))
+    }),
+    (Operator::WeakNext, SyntaxTree::WeakNext, |layout, child| {
+        layout.unary(child, weak_next_in_place)
+    }),
+    (Operator::Globally, SyntaxTree::Globally, |layout, child| {
+        layout.unary(child, globally_in_place)
+    }),
+    (Operator::Finally, SyntaxTree::Finally, |layout, child| {
+        layout.unary(child, |values, _| finally_in_place(values))
+    }),
+    (
+        Operator::Yesterday,
+        SyntaxTree::Yesterday,
+        |layout, child| layout.unary(child, yesterday_in_place),
+    ),
+    (Operator::Once, SyntaxTree::Once, |layout, child| {
+        layout.unary(child, once_in_place)
+    }),
+    (
+        Operator::Historically,
+        SyntaxTree::Historically,
+        |layout, child| layout.unary(child, historically_in_place),
+    ),
+];
+
+type BinaryOperator = (
+    Operator,
+    fn(Arc<SyntaxTree>, Arc<SyntaxTree>) -> SyntaxTree,
+    fn(&SignatureLayout, &[u64], &[u64]) -> Vec<u64>,
+);
+
+/// Number of commutative operators, which come first in `BINARY_OPERATORS`.
+const COMMUTATIVE_OPERATORS: usize = 2;
+
+const BINARY_OPERATORS: [BinaryOperator; 7] = [
+    (Operator::And, SyntaxTree::And, |layout, left, right| {
+        layout.binary(left, right, |left, right, _| and_in_place(left, right))
+    }),
+    (Operator::Or, SyntaxTree::Or, |layout, left, right| {
+        layout.binary(left, right, |left, right, _| or_in_place(left, right))
+    }),
+    (
+        Operator::Implies,
+        SyntaxTree::Implies,
+        |layout, left, right| layout.binary(left, right, implies_in_place),
+    ),
+    (Operator::Until, SyntaxTree::Until, |layout, left, right| {
+        // The result of `until_in_place` takes the place of the right operand.
+        layout.binary(right, left, |right, left, _| until_in_place(left, right))
+    }),
+    (
+        Operator::Release,
+        SyntaxTree::Release,
+        |layout, left, right| layout.binary(left, right, release_in_place),
+    ),
+    (
+        Operator::WeakUntil,
+        SyntaxTree::WeakUntil,
+        |layout, left, right| {
+            // The result of `weak_until_in_place` takes the place of the right operand.
+            layout.binary(right, left, |right, left, len| {
+                weak_until_in_place(left, right, len)
+            })
+        },
+    ),
+    (Operator::Since, SyntaxTree::Since, |layout, left, right| {
+        // The result of `since_in_place` takes the place of the right operand.
+        layout.binary(right, left, |right, left, len| {
+            since_in_place(left, right, len)
+        })
+    }),
+];
+
+/// The truth values of a formula at every time instant of every trace of a sample,
+/// given by the concatenation of the bitsets of each trace.
+type Signature = Arc<[u64]>;
+
+/// Where the bitset of each trace of a sample is found in a `Signature`.
+struct SignatureLayout {
+    /// Starting word and length of each trace, positive traces first.
+    traces: Vec<(usize, usize)>,
+    positives: usize,
+    words: usize,
+}
+
+impl SignatureLayout {
+    fn new<S: LabeledSample>(sample: &S) -> Self {
+        let mut total_words = 0;
+        let traces = sample
+            .positives()
+            .iter()
+            .chain(sample.negatives())
+            .map(|trace| {
+                let start = total_words;
+                total_words += words(trace.len());
+                (start, trace.len())
+            })
+            .collect();
+        SignatureLayout {
+            traces,
+            positives: sample.positives().len(),
+            words: total_words,
+        }
+    }
+
+    /// The signature of a constant, holding either at every time instant or at none.
+    fn constant(&self, value: bool) -> Vec<u64> {
+        let signature = vec![0; self.words];
+        if value {
+            self.unary(&signature, not_in_place)
+        } else {
+            signature
+        }
+    }
+
+    fn atom<S: LabeledSample>(&self, sample: &S, var: Idx) -> Vec<u64> {
+        let mut signature = vec![0; self.words];
+        for (trace, (start, len)) in sample
+            .positives()
+            .iter()
+            .chain(sample.negatives())
+            .zip(&self.traces)
+        {
+            let end = start + words(*len);
+            trace.atom_values(var, &mut signature[*start..end]);
+        }
+        signature
+    }
+
+    /// Applies a unary operator to the bitset of each trace.
+    fn unary(&self, child: &[u64], op: impl Fn(&mut [u64], usize)) -> Vec<u64> {
+        let mut signature = child.to_vec();
+        for (start, len) in &self.traces {
+            op(&mut signature[*start..start + words(*len)], *len);
+        }
+        signature
+    }
+
+    /// Applies a binary operator to the bitsets of each trace, with the result taking the place of the first operand.
+    fn binary(
+        &self,
+        first: &[u64],
+        second: &[u64],
+        op: impl Fn(&mut [u64], &[u64], usize),
+    ) -> Vec<u64> {
+        let mut signature = first.to_vec();
+        for (start, len) in &self.traces {
+            let end = start + words(*len);
+            op(&mut signature[*start..end], &second[*start..end], *len);
+        }
+        signature
+    }
+
+    /// Checks whether the formula with the given signature holds on all positive traces and on no negative trace.
+    fn is_consistent(&self, signature: &[u64]) -> bool {
+        self.traces.iter().enumerate().all(|(idx, (start, len))| {
+            (*len > 0 && signature[*start] & 1 == 1) == (idx < self.positives)
+        })
+    }
+}
+
+// The filtering criteria rewriting a formula with operators it does not contain
+// only apply if those operators are allowed and do not cost more (see `Operators::can_replace`).
+
+fn check_not(child: &SyntaxTree, operators: &Operators) -> bool {
+    use Operator::*;
     match child {
         // ¬¬φ ≡ φ
-        SyntaxTree::Not(_)
+        SyntaxTree::Not(_) => false,
+        // ¬⊤ ≡ ⊥
+        SyntaxTree::True if operators.can_replace(&[Not, True], False) => false,
+        // ¬⊥ ≡ ⊤
+        SyntaxTree::False if operators.can_replace(&[Not, False], True) => false,
         // ¬(φ -> ψ) ≡ φ ∧ ¬ψ
-        | SyntaxTree::Implies(_, _)
+        SyntaxTree::Implies(_, _) if operators.can_replace(&[Implies], And) => false,
         // ¬ F φ ≡ G ¬ φ
-        | SyntaxTree::Finally(_) => false,
+        SyntaxTree::Finally(_) if operators.can_replace(&[Finally], Globally) => false,
+        // ¬ X φ ≡ N ¬ φ
+        SyntaxTree::Next(_) if operators.can_replace(&[Next], WeakNext) => false,
+        // ¬ N φ ≡ X ¬ φ
+        SyntaxTree::WeakNext(_) if operators.can_replace(&[WeakNext], Next) => false,
+        // ¬ O φ ≡ H ¬ φ
+        SyntaxTree::Once(_) if operators.can_replace(&[Once], Historically) => false,
+        // ¬ F[a,b] φ ≡ G[a,b] ¬ φ
+        SyntaxTree::BoundedFinally(..)
+            if operators.can_replace(&[BoundedFinally], BoundedGlobally) =>
+        {
+            false
+        }
         // ¬(¬φ ∨ ψ) ≡ φ ∧ ¬ψ
         SyntaxTree::Or(left_child, _)
+            if matches!(left_child.as_ref(), SyntaxTree::Not(_))
+                && operators.can_replace(&[Or, Not], And) =>
+        {
+            false
+        }
         // ¬(¬φ ∧ ψ) ≡ φ ∨ ¬ψ
-        | SyntaxTree::And(left_child, _) if matches!(left_child.as_ref(), SyntaxTree::Not(_)) => false,
+        SyntaxTree::And(left_child, _)
+            if matches!(left_child.as_ref(), SyntaxTree::Not(_))
+                && operators.can_replace(&[And, Not], Or) =>
+        {
+            false
+        }
         // ¬(φ ∨ ¬ψ) ≡ ¬φ ∧ ψ
         SyntaxTree::Or(_, right_child)
+            if matches!(right_child.as_ref(), SyntaxTree::Not(_))
+                && operators.can_replace(&[Or, Not], And) =>
+        {
+            false
+        }
         // ¬(φ ∧ ¬ψ) ≡ ¬φ ∨ ψ
-        | SyntaxTree::And(_, right_child) if matches!(right_child.as_ref(), SyntaxTree::Not(_)) => false,
+        SyntaxTree::And(_, right_child)
+            if matches!(right_child.as_ref(), SyntaxTree::Not(_))
+                && operators.can_replace(&[And, Not], Or) =>
+        {
+            false
+        }
         _ => true,
     }
 }
@@ -199,113 +1498,225 @@ fn check_not(child: &SyntaxTree) -> bool {
 fn check_next(child: &SyntaxTree) -> bool {
     !matches!(
         child,
-        // X ¬ φ ≡ ¬ X φ // FALSE on finite trace semantics: neXt and Not do not commute on a trace of length 1!
-        // SyntaxTree::Not(_)
-        // X G φ ≡ G X φ // FALSE on finite trace semantics: GX(...) = False on any trace!
-        // SyntaxTree::Globally(_)
-        // X F φ ≡ F X φ // Holds even on finite trace semantics
+        // Not pruned: X ¬ φ ≢ ¬ X φ on finite trace semantics, as they differ on a trace of length 1
+        // Not pruned: X G φ ≢ G X φ on finite trace semantics, as G X φ is false on any trace
+        // X F φ ≡ F X φ
         SyntaxTree::Finally(_)
+        // X ⊥ ≡ ⊥ (whereas X ⊤ only fails at the end of the trace)
+        | SyntaxTree::False
     )
 }
 
-fn check_globally(child: &SyntaxTree) -> bool {
+fn check_weak_next(child: &SyntaxTree, operators: &Operators) -> bool {
+    // On infinite trace semantics: N φ ≡ X φ, as there is always a next time instant
+    (operators.semantics() == Semantics::Finite
+        || !operators.can_replace(&[Operator::WeakNext], Operator::Next))
+        && !matches!(
+            child,
+            // N G φ ≡ G N φ // Holds on finite trace semantics, as both require φ from the next time instant on
+            SyntaxTree::Globally(_)
+            // N ⊤ ≡ ⊤ (whereas N ⊥ only holds at the end of the trace)
+            | SyntaxTree::True
+        )
+}
+
+fn check_globally(child: &SyntaxTree, operators: &Operators) -> bool {
+    use Operator::*;
+    match child {
+        // G G φ ≡ G φ
+        SyntaxTree::Globally(_)
+        // G ⊤ ≡ ⊤ and G ⊥ ≡ ⊥
+        | SyntaxTree::True | SyntaxTree::False => false,
+        // On finite trace semantics: GX(...) = False on any trace!
+        SyntaxTree::Next(_)
+            if operators.semantics() == Semantics::Finite
+                && operators.can_replace(&[Globally, Next], False) =>
+        {
+            false
+        }
+        _ => true,
+    }
+}
+
+fn check_finally(child: &SyntaxTree, operators: &Operators) -> bool {
+    use Operator::*;
+    match child {
+        // F F φ ≡ F φ
+        SyntaxTree::Finally(_)
+        // F ⊤ ≡ ⊤ and F ⊥ ≡ ⊥
+        | SyntaxTree::True | SyntaxTree::False => false,
+        // On finite trace semantics: FN(...) = True on any trace!
+        SyntaxTree::WeakNext(_)
+            if operators.semantics() == Semantics::Finite
+                && operators.can_replace(&[Finally, WeakNext], True) =>
+        {
+            false
+        }
+        _ => true,
+    }
+}
+
+fn check_yesterday(child: &SyntaxTree) -> bool {
     !matches!(
         child,
-        // G G φ ≡ G φ
-        SyntaxTree::Globally(_) | SyntaxTree::Next(_) // On finite trace semantics: GX(...) = False on any trace!
+        // Y O φ ≡ O Y φ
+        SyntaxTree::Once(_)
+        // Y ⊥ ≡ ⊥ (whereas Y ⊤ only fails at the start of the trace)
+        | SyntaxTree::False
     )
 }
 
-fn check_finally(child: &SyntaxTree) -> bool {
+fn check_once(child: &SyntaxTree) -> bool {
     !matches!(
         child,
-        // F F φ ≡ F φ
-        SyntaxTree::Finally(_) // // X F φ ≡ F X φ
-                               // | SyntaxTree::Unary { op: UnaryOp::Next, .. }
-                               // // F False ≡ False
-                               // | SyntaxTree::Zeroary { op: ZeroaryOp::False }
+        // O O φ ≡ O φ
+        SyntaxTree::Once(_)
+        // O ⊤ ≡ ⊤ and O ⊥ ≡ ⊥
+        | SyntaxTree::True | SyntaxTree::False
     )
 }
 
-fn check_and(left_child: &SyntaxTree, right_child: &SyntaxTree) -> bool {
-    // Commutative law WARNING: CORRECTNESS OF COMM+ASSOC IS NOT PROVEN
+fn check_historically(child: &SyntaxTree, operators: &Operators) -> bool {
+    use Operator::*;
+    match child {
+        // H H φ ≡ H φ
+        SyntaxTree::Historically(_)
+        // H ⊤ ≡ ⊤ and H ⊥ ≡ ⊥
+        | SyntaxTree::True | SyntaxTree::False => false,
+        // HY(...) = False on any trace, as Y does not hold at the start!
+        SyntaxTree::Yesterday(_) if operators.can_replace(&[Historically, Yesterday], False) => {
+            false
+        }
+        _ => true,
+    }
+}
+
+fn check_bounded_globally(interval: Interval, child: &SyntaxTree, operators: &Operators) -> bool {
+    // G[0,0] φ ≡ φ
+    interval.end > 0
+        && match child {
+            // G[a,b] ⊤ ≡ ⊤ (whereas G[a,b] ⊥ only holds close to the end of the trace)
+            SyntaxTree::True => false,
+            // G[a,b] G[c,d] φ ≡ G[a+c,b+d] φ, if within the horizon
+            SyntaxTree::BoundedGlobally(child_interval, _) => {
+                interval.end + child_interval.end > operators.horizon()
+            }
+            _ => true,
+        }
+}
+
+fn check_bounded_finally(interval: Interval, child: &SyntaxTree, operators: &Operators) -> bool {
+    // F[0,0] φ ≡ φ
+    interval.end > 0
+        && match child {
+            // F[a,b] ⊥ ≡ ⊥ (whereas F[a,b] ⊤ only fails close to the end of the trace)
+            SyntaxTree::False => false,
+            // F[a,b] F[c,d] φ ≡ F[a+c,b+d] φ, if within the horizon
+            SyntaxTree::BoundedFinally(child_interval, _) => {
+                interval.end + child_interval.end > operators.horizon()
+            }
+            _ => true,
+        }
+}
+
+fn check_and(left_child: &SyntaxTree, right_child: &SyntaxTree, operators: &Operators) -> bool {
+    use Operator::*;
+    // Commutative law, which together with the associative law below keeps a single ordering and nesting of the operands.
+    // Not proven in general: `Operators::verify_pruning` checks it exhaustively, in its tests
+    // up to size 5 (size 4 on infinite traces) over 2 variables, on traces up to 3 long.
     left_child < right_child
-    // left_child != right_child
         && match (left_child, right_child) {
         //  Excluded middle
         (child, SyntaxTree::Not(neg_child ))
-        |(SyntaxTree::Not(neg_child), child) if child == neg_child.as_ref() => false,
-        // // Domination law
-        // (.., SyntaxTree::Zeroary { op: ZeroaryOp::False })
-        // | (SyntaxTree::Zeroary { op: ZeroaryOp::False }, ..)
+        |(SyntaxTree::Not(neg_child), child) if child == neg_child.as_ref() && operators.can_replace(&[And, Not], False) => false,
+        // Domination law
+        | (_, SyntaxTree::False)
+        | (SyntaxTree::False, _)
+        // Identity law
+        | (_, SyntaxTree::True)
+        | (SyntaxTree::True, _)
         // Associative laws
         | (SyntaxTree::And(_, _), _)
-        // De Morgan's laws
-        | (SyntaxTree::Not(_), SyntaxTree::Not(_))
         // X (φ ∧ ψ) ≡ (X φ) ∧ (X ψ)
         | (SyntaxTree::Next(_), SyntaxTree::Next(_))
+        // N (φ ∧ ψ) ≡ (N φ) ∧ (N ψ)
+        | (SyntaxTree::WeakNext(_), SyntaxTree::WeakNext(_))
         // G (φ ∧ ψ)≡ (G φ) ∧ (G ψ)
-        | (SyntaxTree::Globally(_), SyntaxTree::Globally(_)) => false,
+        | (SyntaxTree::Globally(_), SyntaxTree::Globally(_))
+        // Y (φ ∧ ψ) ≡ (Y φ) ∧ (Y ψ)
+        | (SyntaxTree::Yesterday(_), SyntaxTree::Yesterday(_))
+        // H (φ ∧ ψ) ≡ (H φ) ∧ (H ψ)
+        | (SyntaxTree::Historically(_), SyntaxTree::Historically(_)) => false,
+        // De Morgan's laws
+        (SyntaxTree::Not(_), SyntaxTree::Not(_)) if operators.can_replace(&[And, Not], Or) => false,
         // (φ -> ψ_1) ∧ (φ -> ψ_2) ≡ φ -> (ψ_1 ∧ ψ_2)
+        (SyntaxTree::Implies(c_1_0, _), SyntaxTree::Implies(c_2_0, _)) if c_1_0 == c_2_0 => false,
         // (φ_1 -> ψ) ∧ (φ_2 -> ψ) ≡ (φ_1 ∨ φ_2) -> ψ
-        (SyntaxTree::Implies(c_1_0, c_1_1), SyntaxTree::Implies(c_2_0, c_2_1)) if c_1_0 == c_2_0 || c_1_1 == c_2_1 => false,
+        (SyntaxTree::Implies(_, c_1_1), SyntaxTree::Implies(_, c_2_1)) if c_1_1 == c_2_1 && operators.can_replace(&[And, Implies], Or) => false,
         // (φ_1 U ψ) ∧ (φ_2 U ψ) ≡ (φ_1 ∧ φ_2) U ψ
         (SyntaxTree::Until(_, c_1), SyntaxTree::Until(_, c_2)) if c_1 == c_2 => false,
+        // (φ_1 W ψ) ∧ (φ_2 W ψ) ≡ (φ_1 ∧ φ_2) W ψ
+        (SyntaxTree::WeakUntil(_, c_1), SyntaxTree::WeakUntil(_, c_2)) if c_1 == c_2 => false,
+        // (φ R ψ_1) ∧ (φ R ψ_2) ≡ φ R (ψ_1 ∧ ψ_2)
+        (SyntaxTree::Release(c_1, _), SyntaxTree::Release(c_2, _)) if c_1 == c_2 => false,
+        // (φ_1 S ψ) ∧ (φ_2 S ψ) ≡ (φ_1 ∧ φ_2) S ψ
+        (SyntaxTree::Since(_, c_1), SyntaxTree::Since(_, c_2)) if c_1 == c_2 => false,
+        // G[a,b] (φ ∧ ψ) ≡ (G[a,b] φ) ∧ (G[a,b] ψ)
+        (SyntaxTree::BoundedGlobally(i_1, _), SyntaxTree::BoundedGlobally(i_2, _)) if i_1 == i_2 => false,
         // Absorption laws
         (SyntaxTree::Or(c_0, c_1), right_child) if c_0.as_ref() == right_child || c_1.as_ref() == right_child => false,
         (left_child, SyntaxTree::Or(c_0, c_1)) if c_0.as_ref() == left_child || c_1.as_ref() == left_child => false,
         // Distributive laws
         (SyntaxTree::Or(c_1_0, c_1_1), SyntaxTree::Or(c_2_0, c_2_1)) if c_1_0 == c_2_0 || c_1_0 == c_2_1 || c_1_1 == c_2_0 || c_1_1 == c_2_1 => false,
-        // Does not hold in LTL_f
-        // // G φ ≡ φ ∧ X(G φ)
-        // (
-        //     left_child,
-        //     SyntaxTree::Next(child)
-        // ) => if let SyntaxTree::Globally(child) = child.as_ref() {
-        //     child.as_ref() != left_child
-        // } else {
-        //     true
-        // },
-        // Does not hold in LTL_f
-        // // G φ ≡ X(G φ) ∧ φ
-        // (
-        //     SyntaxTree::Next(child),
-        //     right_child,
-        // ) => if let SyntaxTree::Globally(child) = child.as_ref() {
-        //     child.as_ref() != right_child
-        // } else {
-        //     true
-        // },
+        // Not pruned: G φ ≢ φ ∧ X(G φ) on finite trace semantics, as X fails at the end of the trace
         _ => true,
     }
 }
 
-fn check_or(left_child: &SyntaxTree, right_child: &SyntaxTree) -> bool {
-    // Commutative law WARNING: CORRECTNESS OF COMM+ASSOC IS NOT PROVEN
+fn check_or(left_child: &SyntaxTree, right_child: &SyntaxTree, operators: &Operators) -> bool {
+    use Operator::*;
+    // Commutative law, which together with the associative law below keeps a single ordering and nesting of the operands.
+    // Not proven in general: `Operators::verify_pruning` checks it exhaustively, in its tests
+    // up to size 5 (size 4 on infinite traces) over 2 variables, on traces up to 3 long.
     left_child < right_child
-    // left_child != right_child
         && match (left_child, right_child) {
         //  Excluded middle
         (child, SyntaxTree::Not(neg_child))
-        | (SyntaxTree::Not(neg_child), child) if child == neg_child.as_ref() => false,
-        // // Identity law
-        // (.., SyntaxTree::Zeroary { op: ZeroaryOp::False })
-        // | (SyntaxTree::Zeroary { op: ZeroaryOp::False }, ..)
+        | (SyntaxTree::Not(neg_child), child) if child == neg_child.as_ref() && operators.can_replace(&[Or, Not], True) => false,
+        // Identity law
+        | (_, SyntaxTree::False)
+        | (SyntaxTree::False, _)
+        // Domination law
+        | (_, SyntaxTree::True)
+        | (SyntaxTree::True, _)
         // Associative laws
         | (SyntaxTree::Or(_, _), _)
-        // // De Morgan's laws
-        // | (SyntaxTree::Unary { op: UnaryOp::Not, .. }, SyntaxTree::Unary { op: UnaryOp::Not, .. })
-        // ¬φ ∨ ψ ≡ φ -> ψ, subsumes De Morgan's laws
-        | (SyntaxTree::Not(_), _)
         // X (φ ∨ ψ) ≡ (X φ) ∨ (X ψ)
         | (SyntaxTree::Next(_), SyntaxTree::Next(_))
+        // N (φ ∨ ψ) ≡ (N φ) ∨ (N ψ)
+        | (SyntaxTree::WeakNext(_), SyntaxTree::WeakNext(_))
+        // Y (φ ∨ ψ) ≡ (Y φ) ∨ (Y ψ)
+        | (SyntaxTree::Yesterday(_), SyntaxTree::Yesterday(_))
+        // O (φ ∨ ψ) ≡ (O φ) ∨ (O ψ)
+        | (SyntaxTree::Once(_), SyntaxTree::Once(_))
         // F (φ ∨ ψ) ≡ (F φ) ∨ (F ψ)
         | (SyntaxTree::Finally(_), SyntaxTree::Finally(_)) => false,
+        // ¬φ ∨ ψ ≡ φ -> ψ, subsumes De Morgan's laws
+        (SyntaxTree::Not(_), _) if operators.can_replace(&[Or, Not], Implies) => false,
         // (φ -> ψ_1) ∨ (φ -> ψ_2) ≡ φ -> (ψ_1 ∨ ψ_2)
+        (SyntaxTree::Implies(c_1_0, _), SyntaxTree::Implies(c_2_0, _)) if c_1_0 == c_2_0 => false,
         // (φ_1 -> ψ) ∨ (φ_2 -> ψ) ≡ (φ_1 ∧ φ_2) -> ψ
-        (SyntaxTree::Implies(c_1_0, c_1_1), SyntaxTree::Implies(c_2_0, c_2_1)) if c_1_0 == c_2_0 || c_1_1 == c_2_1 => false,
+        (SyntaxTree::Implies(_, c_1_1), SyntaxTree::Implies(_, c_2_1)) if c_1_1 == c_2_1 && operators.can_replace(&[Or, Implies], And) => false,
         // (φ U ψ_1) ∨ (φ U ψ_2) ≡ φ U (ψ_1 ∨ ψ_2)
         (SyntaxTree::Until(c_1, _), SyntaxTree::Until(c_2, _)) if c_1 == c_2 => false,
+        // (φ W ψ_1) ∨ (φ W ψ_2) ≡ φ W (ψ_1 ∨ ψ_2)
+        (SyntaxTree::WeakUntil(c_1, _), SyntaxTree::WeakUntil(c_2, _)) if c_1 == c_2 => false,
+        // (φ_1 R ψ) ∨ (φ_2 R ψ) ≡ (φ_1 ∨ φ_2) R ψ
+        (SyntaxTree::Release(_, c_1), SyntaxTree::Release(_, c_2)) if c_1 == c_2 => false,
+        // (φ S ψ_1) ∨ (φ S ψ_2) ≡ φ S (ψ_1 ∨ ψ_2)
+        (SyntaxTree::Since(c_1, _), SyntaxTree::Since(c_2, _)) if c_1 == c_2 => false,
+        // F[a,b] (φ ∨ ψ) ≡ (F[a,b] φ) ∨ (F[a,b] ψ)
+        (SyntaxTree::BoundedFinally(i_1, _), SyntaxTree::BoundedFinally(i_2, _)) if i_1 == i_2 => false,
         // Absorption laws
         (SyntaxTree::And(c_0, c_1), right_child) if c_0.as_ref() == right_child || c_1.as_ref() == right_child => false,
         (left_child, SyntaxTree::And(c_0, c_1)) if c_0.as_ref() == left_child || c_1.as_ref() == left_child => false,
@@ -373,30 +1784,27 @@ fn check_or(left_child: &SyntaxTree, right_child: &SyntaxTree) -> bool {
     }
 }
 
-fn check_implies(left_child: &SyntaxTree, right_child: &SyntaxTree) -> bool {
-    left_child != right_child
-        && !matches!(
-            (left_child, right_child),
-            // // Ex falso quodlibet (True defined as ¬False)
-            // (
-            //     SyntaxTree::Zeroary { op: ZeroaryOp::False },
-            //     ..,
-            // )
-            // // φ -> False ≡ ¬φ
-            // | (
-            //     ..,
-            //     SyntaxTree::Zeroary { op: ZeroaryOp::False },
-            // )
-            // // (SyntaxTree::Zeroary { op: ZeroaryOp::False, .. }, ..)
-            // // φ -> ψ ≡ ¬ψ -> ¬φ // subsumed by following rule
-            // (SyntaxTree::Unary { op: UnaryOp::Not, .. }, SyntaxTree::Unary { op: UnaryOp::Not, .. }) => false,
+fn check_implies(left_child: &SyntaxTree, right_child: &SyntaxTree, operators: &Operators) -> bool {
+    use Operator::*;
+    // φ -> φ ≡ ⊤
+    (left_child != right_child || !operators.can_replace(&[Implies], True))
+        && !match (left_child, right_child) {
+            // ⊤ -> φ ≡ φ
+            (SyntaxTree::True, _)
+            // φ -> ⊤ ≡ ⊤
+            | (_, SyntaxTree::True) => true,
+            // Ex falso quodlibet: ⊥ -> φ ≡ ⊤
+            (SyntaxTree::False, _) if operators.can_replace(&[Implies, False], True) => true,
+            // φ -> ⊥ ≡ ¬φ
+            (_, SyntaxTree::False) if operators.can_replace(&[Implies, False], Not) => true,
+            // φ -> ψ ≡ ¬ψ -> ¬φ is subsumed by the following rule
             // ¬φ -> ψ ≡ ψ ∨ φ
             (
                 SyntaxTree::Not(_),
                 _,
-            )
+            ) if operators.can_replace(&[Implies, Not], Or) => true,
             // φ -> ¬ψ ≡ ¬(ψ ∧ φ)
-            | (
+            (
                 _,
                 SyntaxTree::Not(_),
             )
@@ -405,32 +1813,844 @@ fn check_implies(left_child: &SyntaxTree, right_child: &SyntaxTree) -> bool {
             | (
                 _,
                 SyntaxTree::Implies(_, _),
-            )
-        )
+            ) if operators.can_replace(&[Implies], And) => true,
+            _ => false,
+        }
 }
 
-fn check_until(left_child: &SyntaxTree, right_child: &SyntaxTree) -> bool {
+fn check_until(left_child: &SyntaxTree, right_child: &SyntaxTree, operators: &Operators) -> bool {
+    use Operator::*;
     // φ U φ ≡ φ
     left_child != right_child
         && match (left_child, right_child) {
-            // // φ U False ≡ G φ
-            // (
-            //     ..,
-            //     SyntaxTree::Zeroary {
-            //         op: ZeroaryOp::False
-            //     },
-            // )
-            // // False U φ ≡ φ
-            // | (
-            //     SyntaxTree::Zeroary {
-            //         op: ZeroaryOp::False
-            //     },
-            //     ..
-            // )
+            // φ U ⊤ ≡ ⊤ and φ U ⊥ ≡ ⊥ (as Until is strong)
+            (_, SyntaxTree::True | SyntaxTree::False)
+            // ⊥ U φ ≡ φ
+            | (SyntaxTree::False, _)
             // X (φ U ψ) ≡ (X φ) U (X ψ)
-            (SyntaxTree::Next(_), SyntaxTree::Next(_)) => false,
+            | (SyntaxTree::Next(_), SyntaxTree::Next(_)) => false,
+            // ⊤ U φ ≡ F φ
+            (SyntaxTree::True, _) if operators.can_replace(&[Until, True], Finally) => false,
             // φ U ψ ≡ φ U (φ U ψ)
             (left_child, SyntaxTree::Until(child, _)) if left_child == child.as_ref() => false,
             _ => true,
         }
 }
+
+fn check_release(left_child: &SyntaxTree, right_child: &SyntaxTree, operators: &Operators) -> bool {
+    use Operator::*;
+    // φ R φ ≡ φ
+    left_child != right_child
+        && match (left_child, right_child) {
+            // φ R ⊤ ≡ ⊤ and φ R ⊥ ≡ ⊥
+            (_, SyntaxTree::True | SyntaxTree::False)
+            // ⊤ R φ ≡ φ
+            | (SyntaxTree::True, _)
+            // N (φ R ψ) ≡ (N φ) R (N ψ)
+            | (SyntaxTree::WeakNext(_), SyntaxTree::WeakNext(_)) => false,
+            // ⊥ R φ ≡ G φ
+            (SyntaxTree::False, _) if operators.can_replace(&[Release, False], Globally) => false,
+            // φ R ψ ≡ φ R (φ R ψ)
+            (left_child, SyntaxTree::Release(child, _)) if left_child == child.as_ref() => false,
+            _ => true,
+        }
+}
+
+fn check_weak_until(
+    left_child: &SyntaxTree,
+    right_child: &SyntaxTree,
+    operators: &Operators,
+) -> bool {
+    use Operator::*;
+    // φ W φ ≡ φ
+    left_child != right_child
+        && match (left_child, right_child) {
+            // φ W ⊤ ≡ ⊤ and ⊤ W φ ≡ ⊤
+            (_, SyntaxTree::True)
+            | (SyntaxTree::True, _)
+            // ⊥ W φ ≡ φ
+            | (SyntaxTree::False, _)
+            // N (φ W ψ) ≡ (N φ) W (N ψ)
+            | (SyntaxTree::WeakNext(_), SyntaxTree::WeakNext(_)) => false,
+            // φ W ⊥ ≡ G φ
+            (_, SyntaxTree::False) if operators.can_replace(&[WeakUntil, False], Globally) => false,
+            // φ W ψ ≡ φ W (φ W ψ)
+            (left_child, SyntaxTree::WeakUntil(child, _)) if left_child == child.as_ref() => false,
+            _ => true,
+        }
+}
+
+fn check_since(left_child: &SyntaxTree, right_child: &SyntaxTree, operators: &Operators) -> bool {
+    use Operator::*;
+    // φ S φ ≡ φ
+    left_child != right_child
+        && match (left_child, right_child) {
+            // φ S ⊤ ≡ ⊤ and φ S ⊥ ≡ ⊥
+            (_, SyntaxTree::True | SyntaxTree::False)
+            // ⊥ S φ ≡ φ
+            | (SyntaxTree::False, _)
+            // Y (φ S ψ) ≡ (Y φ) S (Y ψ)
+            | (SyntaxTree::Yesterday(_), SyntaxTree::Yesterday(_)) => false,
+            // ⊤ S φ ≡ O φ
+            (SyntaxTree::True, _) if operators.can_replace(&[Since, True], Once) => false,
+            // φ S ψ ≡ φ S (φ S ψ)
+            (left_child, SyntaxTree::Since(child, _)) if left_child == child.as_ref() => false,
+            _ => true,
+        }
+}
+
+fn check_bounded_until(
+    interval: Interval,
+    left_child: &SyntaxTree,
+    right_child: &SyntaxTree,
+    operators: &Operators,
+) -> bool {
+    use Operator::*;
+    // φ U[0,0] ψ ≡ ψ
+    interval.end > 0
+        && match (left_child, right_child) {
+            // φ U[0,b] φ ≡ φ
+            (left_child, right_child) if left_child == right_child && interval.start == 0 => false,
+            // φ U[a,b] ⊥ ≡ ⊥, and ⊥ U[a,b] ψ is either ψ (if a = 0) or ⊥
+            (_, SyntaxTree::False) | (SyntaxTree::False, _) => false,
+            // φ U[0,b] ⊤ ≡ ⊤
+            (_, SyntaxTree::True) if interval.start == 0 => false,
+            // ⊤ U[a,b] ψ ≡ F[a,b] ψ
+            (SyntaxTree::True, _)
+                if operators.can_replace(&[BoundedUntil, True], BoundedFinally) =>
+            {
+                false
+            }
+            _ => true,
+        }
+}
+
+#[cfg(test)]
+mod bottom_up {
+    use super::*;
+
+    #[test]
+    fn same_size_as_enumerative() {
+        for path in [
+            "SATcomparison/abscence/0000.ron",
+            "SATcomparison/abscence/0003.ron",
+            "SATcomparison/universality/0000.ron",
+            "SATcomparison/universality/0004.ron",
+        ] {
+            let sample = load_sample(path);
+            let expected = solve(&sample, &SolveOptions::default())
+                .solution()
+                .expect("solution");
+            let solution = solve_bottom_up(&sample, &SolveOptions::default())
+                .solution()
+                .expect("solution");
+            assert!(sample.is_consistent(&solution), "{path}: {solution}");
+            assert_eq!(solution.size(), expected.size(), "{path}");
+        }
+    }
+
+    #[test]
+    fn unsolvable() {
+        let sample = DynSample {
+            var_names: vec!["x0".to_string()],
+            positive_traces: vec![vec![vec![true], vec![false]]],
+            negative_traces: vec![vec![vec![true], vec![false]]],
+        };
+        assert_eq!(
+            solve_bottom_up(&sample, &SolveOptions::default()),
+            Outcome::Unsolvable
+        );
+    }
+}
+
+#[cfg(test)]
+mod approx {
+    use super::*;
+
+    fn sample() -> DynSample {
+        // `G(x0)` holds on all positive traces and no negative trace, but the last one,
+        // which is the same as the first positive trace.
+        DynSample {
+            var_names: vec!["x0".to_string(), "x1".to_string()],
+            positive_traces: vec![
+                vec![vec![true, false], vec![true, true]],
+                vec![vec![true, true], vec![true, false], vec![true, true]],
+                vec![vec![true, false]],
+            ],
+            negative_traces: vec![
+                vec![vec![false, false], vec![true, true]],
+                vec![vec![true, true], vec![false, false]],
+                vec![vec![false, true]],
+                vec![vec![true, false], vec![true, true]],
+            ],
+        }
+    }
+
+    #[test]
+    fn budget() {
+        assert_eq!("3".parse(), Ok(ErrorBudget::Count(3)));
+        assert_eq!("0.1".parse(), Ok(ErrorBudget::Rate(0.1)));
+        assert_eq!("5%".parse(), Ok(ErrorBudget::Rate(0.05)));
+        assert!("120%".parse::<ErrorBudget>().is_err());
+        assert!("x".parse::<ErrorBudget>().is_err());
+        assert_eq!(ErrorBudget::Rate(0.25).max_errors(9), 2);
+    }
+
+    #[test]
+    fn min_errors() {
+        let sample = sample();
+        assert_eq!(sample.min_errors(), 1);
+        assert!(!sample.is_solvable());
+        assert_eq!(
+            solve(&sample, &SolveOptions::default()),
+            Outcome::Unsolvable
+        );
+        assert_eq!(
+            solve_approx(&sample, ErrorBudget::Count(0), &SolveOptions::default()),
+            Outcome::Unsolvable
+        );
+    }
+
+    #[test]
+    fn noisy_sample() {
+        let sample = sample();
+        let solution = solve_approx(&sample, ErrorBudget::Count(1), &SolveOptions::default())
+            .solution()
+            .expect("solution");
+        assert_eq!(
+            solution.formula,
+            SyntaxTree::Globally(Arc::new(SyntaxTree::Atom(0)))
+        );
+        assert_eq!(
+            solution.misclassified,
+            Misclassified {
+                positives: vec![],
+                negatives: vec![3],
+            }
+        );
+        assert_eq!(
+            sample.misclassified(&solution.formula),
+            solution.misclassified
+        );
+    }
+
+    #[test]
+    fn trivial_budget() {
+        // Misclassifying 4 traces out of 7 is allowed, so an atom holding on all positive traces is enough.
+        let sample = sample();
+        let solution = solve_approx(&sample, ErrorBudget::Rate(0.6), &SolveOptions::default())
+            .solution()
+            .expect("solution");
+        assert_eq!(solution.formula.size(), 1);
+        assert!(solution.misclassified.len() <= 4);
+    }
+}
+
+#[cfg(test)]
+mod limits {
+    use super::*;
+    use crate::solve_sat;
+
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    type Solver = fn(&DynSample, &SolveOptions) -> Outcome;
+
+    const SOLVERS: [Solver; 3] = [solve, solve_bottom_up, solve_sat];
+
+    /// A sample with random labels, requiring a large solution.
+    fn random_sample() -> DynSample {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut sample = DynSample::new(3);
+        while sample.positive_traces() + sample.negative_traces() < 40 {
+            let trace = (0..8)
+                .map(|_| (0..3).map(|_| rng.gen()).collect())
+                .collect();
+            let _ = if rng.gen() {
+                sample.add_positive_trace(trace)
+            } else {
+                sample.add_negative_trace(trace)
+            };
+        }
+        sample
+    }
+
+    #[test]
+    fn max_size() {
+        // The solution is `¬(F(x0))`, of size 3.
+        let sample = load_sample("SATcomparison/abscence/0000.ron");
+        for solver in SOLVERS {
+            let options = SolveOptions {
+                max_size: Some(2),
+                ..Default::default()
+            };
+            assert_eq!(
+                solver(&sample, &options),
+                Outcome::Exhausted { max_size: 2 }
+            );
+            let options = SolveOptions {
+                max_size: Some(3),
+                ..Default::default()
+            };
+            assert!(solver(&sample, &options).is_solved());
+        }
+    }
+
+    #[test]
+    fn timeout() {
+        let sample = load_sample("SATcomparison/abscence/0000.ron");
+        for solver in SOLVERS {
+            let options = SolveOptions {
+                timeout: Some(Duration::ZERO),
+                ..Default::default()
+            };
+            assert_eq!(solver(&sample, &options), Outcome::TimedOut { size: 1 });
+        }
+    }
+
+    #[test]
+    fn cancelled() {
+        let sample = load_sample("SATcomparison/abscence/0000.ron");
+        let cancellation = CancellationToken::new();
+        cancellation.cancel();
+        for solver in SOLVERS {
+            let options = SolveOptions {
+                cancellation: Some(cancellation.clone()),
+                ..Default::default()
+            };
+            assert_eq!(solver(&sample, &options), Outcome::Cancelled { size: 1 });
+        }
+    }
+
+    #[test]
+    fn cancelled_from_other_thread() {
+        let sample = random_sample();
+        for solver in SOLVERS {
+            for multithread in [false, true] {
+                let cancellation = CancellationToken::new();
+                let options = SolveOptions {
+                    multithread,
+                    cancellation: Some(cancellation.clone()),
+                    ..Default::default()
+                };
+                let outcome = std::thread::scope(|scope| {
+                    let search = scope.spawn(|| solver(&sample, &options));
+                    std::thread::sleep(Duration::from_millis(50));
+                    cancellation.cancel();
+                    search.join().expect("search thread")
+                });
+                assert!(
+                    matches!(outcome, Outcome::Cancelled { size } if size > 1),
+                    "{outcome:?}"
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod all {
+    use super::*;
+
+    #[test]
+    fn minimal() {
+        let sample = load_sample("SATcomparison/abscence/0000.ron");
+        let solutions = solve_all(&sample, 0, &SolveOptions::default())
+            .solution()
+            .expect("solutions");
+        assert!(solutions.is_complete());
+        let solutions = solutions
+            .formulae
+            .iter()
+            .map(|f| f.to_string())
+            .collect_vec();
+        // `¬(F(x0))` is equivalent, but discarded by `check_not`.
+        assert_eq!(solutions, ["G(¬(x0))"]);
+    }
+
+    #[test]
+    fn extra_size() {
+        let sample = load_sample("SATcomparison/abscence/0000.ron");
+        let solution = solve(&sample, &SolveOptions::default())
+            .solution()
+            .expect("solution");
+        let solutions = solve_all(&sample, 1, &SolveOptions::default())
+            .solution()
+            .expect("solutions")
+            .formulae;
+        assert_eq!(solutions[0], solution);
+        assert!(solutions.len() > 1);
+        assert!(solutions
+            .iter()
+            .all(|formula| sample.is_consistent(formula)));
+        assert!(solutions
+            .windows(2)
+            .all(|pair| pair[0].size() <= pair[1].size()));
+        assert_eq!(solutions.last().map(SyntaxTree::size), Some(4));
+        assert_eq!(
+            solutions.iter().unique().count(),
+            solutions.len(),
+            "duplicate solutions"
+        );
+    }
+
+    #[test]
+    fn deterministic() {
+        let sample = load_sample("SySLite-comparison/glba_6802_1250.ron");
+        let sequential = solve_all(&sample, 0, &SolveOptions::default());
+        let options = SolveOptions {
+            multithread: true,
+            ..Default::default()
+        };
+        for _ in 0..4 {
+            assert_eq!(solve_all(&sample, 0, &options), sequential);
+        }
+    }
+
+    #[test]
+    fn max_size() {
+        let sample = load_sample("SATcomparison/abscence/0000.ron");
+        let options = SolveOptions {
+            max_size: Some(3),
+            ..Default::default()
+        };
+        let solutions = solve_all(&sample, 2, &options)
+            .solution()
+            .expect("solutions")
+            .formulae;
+        assert!(solutions.iter().all(|formula| formula.size() == 3));
+        let options = SolveOptions {
+            max_size: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(
+            solve_all(&sample, 2, &options),
+            Outcome::Exhausted { max_size: 2 }
+        );
+    }
+
+    // Whenever the timeout expires, the solutions found are the first ones of the complete search,
+    // including all of those smaller than the size being searched, and are marked as truncated.
+    #[test]
+    fn timeout() {
+        let sample = load_sample("SATcomparison/abscence/0000.ron");
+        let complete = solve_all(&sample, 3, &SolveOptions::default())
+            .solution()
+            .expect("solutions");
+        assert!(complete.is_complete());
+        for micros in (0..20).map(|n| 1 << n) {
+            let options = SolveOptions {
+                timeout: Some(Duration::from_micros(micros)),
+                ..Default::default()
+            };
+            match solve_all(&sample, 3, &options) {
+                Outcome::Solved(solutions) => {
+                    let found = solutions.formulae.len();
+                    assert_eq!(solutions.formulae, complete.formulae[..found]);
+                    if let Some(size) = solutions.truncated_at {
+                        assert!(complete.formulae[found..]
+                            .iter()
+                            .all(|formula| formula.size() >= size));
+                    } else {
+                        assert_eq!(solutions, complete);
+                    }
+                }
+                Outcome::TimedOut { size } => {
+                    assert!(complete
+                        .formulae
+                        .iter()
+                        .all(|formula| formula.size() >= size))
+                }
+                outcome => panic!("unexpected outcome {outcome:?}"),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod multithread {
+    use super::*;
+
+    #[test]
+    fn same_as_sequential() {
+        let options = SolveOptions {
+            multithread: true,
+            ..Default::default()
+        };
+        for path in [
+            "SATcomparison/abscence/0027.ron",
+            "SATcomparison/abscence/0048.ron",
+            "SATcomparison/universality/0039.ron",
+            "SySLite-comparison/bank_transaction_1250.ron",
+        ] {
+            let sample = load_sample(path);
+            let sequential = solve(&sample, &SolveOptions::default());
+            for _ in 0..3 {
+                assert_eq!(solve(&sample, &options), sequential, "{path}");
+            }
+            let budget = ErrorBudget::Count(2);
+            let sequential = solve_approx(&sample, budget, &SolveOptions::default());
+            for _ in 0..3 {
+                assert_eq!(
+                    solve_approx(&sample, budget, &options),
+                    sequential,
+                    "{path}"
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod enumeration {
+    use super::*;
+
+    #[test]
+    fn skeletons() {
+        // Motzkin numbers
+        let counts = (1..=10)
+            .map(|size| SkeletonTree::gen(size).len())
+            .collect_vec();
+        assert_eq!(counts, [1, 1, 2, 4, 9, 21, 51, 127, 323, 835]);
+    }
+
+    #[test]
+    fn parallel_order() {
+        for size in 1..=6 {
+            let sequential = formulae(size, &[0, 1, 2]).collect_vec();
+            let parallel: Vec<SyntaxTree> = par_formulae(size, &[0, 1, 2]).collect();
+            assert_eq!(parallel, sequential);
+        }
+    }
+
+    #[test]
+    fn lazy() {
+        // Taking the first formulae does not require generating the others.
+        let first = formulae(12, &[0, 1, 2, 3]).take(10).collect_vec();
+        assert_eq!(first.len(), 10);
+        assert!(first.iter().all(|formula| formula.size() == 12));
+    }
+}
+
+#[cfg(test)]
+mod operators {
+    use super::*;
+
+    /// The size of the formula according to the costs of the operators, or `None` if some operator is not allowed.
+    fn cost(formula: &SyntaxTree, operators: &Operators) -> Option<usize> {
+        let (op, children) = match formula {
+            SyntaxTree::Atom(_) => return Some(1),
+            SyntaxTree::True => (Operator::True, vec![]),
+            SyntaxTree::False => (Operator::False, vec![]),
+            SyntaxTree::Not(child) => (Operator::Not, vec![child]),
+            SyntaxTree::Next(child) => (Operator::Next, vec![child]),
+            SyntaxTree::WeakNext(child) => (Operator::WeakNext, vec![child]),
+            SyntaxTree::Globally(child) => (Operator::Globally, vec![child]),
+            SyntaxTree::Finally(child) => (Operator::Finally, vec![child]),
+            SyntaxTree::Yesterday(child) => (Operator::Yesterday, vec![child]),
+            SyntaxTree::Once(child) => (Operator::Once, vec![child]),
+            SyntaxTree::Historically(child) => (Operator::Historically, vec![child]),
+            SyntaxTree::BoundedGlobally(_, child) => (Operator::BoundedGlobally, vec![child]),
+            SyntaxTree::BoundedFinally(_, child) => (Operator::BoundedFinally, vec![child]),
+            SyntaxTree::And(left, right) => (Operator::And, vec![left, right]),
+            SyntaxTree::Or(left, right) => (Operator::Or, vec![left, right]),
+            SyntaxTree::Implies(left, right) => (Operator::Implies, vec![left, right]),
+            SyntaxTree::Until(left, right) => (Operator::Until, vec![left, right]),
+            SyntaxTree::Release(left, right) => (Operator::Release, vec![left, right]),
+            SyntaxTree::WeakUntil(left, right) => (Operator::WeakUntil, vec![left, right]),
+            SyntaxTree::Since(left, right) => (Operator::Since, vec![left, right]),
+            SyntaxTree::BoundedUntil(_, left, right) => (Operator::BoundedUntil, vec![left, right]),
+        };
+        children
+            .into_iter()
+            .try_fold(operators.cost(op)?, |size, child| {
+                Some(size + cost(child, operators)?)
+            })
+    }
+
+    // Bounded operators are searched with horizon 2, to keep the number of formulae manageable.
+    const OPERATOR_SETS: [&str; 16] = [
+        "G,F,&,|",
+        "!,X,&",
+        "!,G,->",
+        "!,F,&,|",
+        "!,X,F,->",
+        "F,G,&,|,U:2,->:2",
+        "!:2,X,F,G,&,|,->,U",
+        "!,F,G,&,|:3,->,U",
+        "N,G,&,|,R,W",
+        "!,X,N,F,U,W:2",
+        "!,X,N,G,F,&,|,->,U,R,W",
+        "!,X,F,Y,O,H,&,|,S",
+        "⊤,⊥,!,X,G,&,|,->,U",
+        "true:2,false,N,F,&,|,R,W",
+        "!,G[],F[],&,|,U[]",
+        "⊤,X,F,F[]:2,&,|,U[]:3",
+    ];
+
+    fn parse_operators(operators: &str) -> Operators {
+        operators
+            .parse::<Operators>()
+            .expect("operators")
+            .with_horizon(2)
+    }
+
+    #[test]
+    fn parse() {
+        let operators: Operators = "G,F,&,|,->".parse().expect("operators");
+        assert_eq!(
+            operators.iter().collect_vec(),
+            vec![
+                (Operator::Globally, 1),
+                (Operator::Finally, 1),
+                (Operator::And, 1),
+                (Operator::Or, 1),
+                (Operator::Implies, 1)
+            ]
+        );
+        assert_eq!(operators.to_string(), "G,F,∧,∨,→");
+        assert_eq!(operators.to_string().parse(), Ok(operators));
+
+        let operators: Operators = "X, U:2".parse().expect("operators");
+        assert_eq!(
+            operators,
+            Operators::none()
+                .with(Operator::Next)
+                .with_cost(Operator::Until, 2)
+        );
+        assert_eq!(operators.to_string(), "X,U:2");
+        assert_eq!(
+            Operators::default().to_string().parse(),
+            Ok(Operators::default())
+        );
+
+        let operators: Operators = "F[],U[]:2".parse().expect("operators");
+        assert_eq!(
+            operators,
+            Operators::none()
+                .with(Operator::BoundedFinally)
+                .with_cost(Operator::BoundedUntil, 2)
+        );
+        assert_eq!(operators.to_string(), "F[],U[]:2");
+
+        assert!("".parse::<Operators>().is_err());
+        assert!("G,".parse::<Operators>().is_err());
+        assert!("Z".parse::<Operators>().is_err());
+        assert!("U:0".parse::<Operators>().is_err());
+        assert!("U:x".parse::<Operators>().is_err());
+    }
+
+    #[test]
+    fn default_operators() {
+        let vars = [0, 1];
+        for size in 1..=6 {
+            assert_eq!(
+                Operators::default().gen_formulae(size, &vars),
+                gen_formulae(size, &vars)
+            );
+        }
+    }
+
+    #[test]
+    fn allowed_operators_and_costs() {
+        let vars = [0, 1];
+        for operators in OPERATOR_SETS {
+            let operators = parse_operators(operators);
+            for size in 1..=7 {
+                let formulae = operators.gen_formulae(size, &vars);
+                assert_eq!(
+                    formulae,
+                    operators.par_formulae(size, &vars).collect::<Vec<_>>()
+                );
+                for formula in formulae {
+                    assert_eq!(cost(&formula, &operators), Some(size), "{formula}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn constants() {
+        let operators: Operators = "⊤,⊥,!,X,N,G,F".parse().expect("operators");
+        let formulae = |size| {
+            operators
+                .gen_formulae(size, &[0])
+                .iter()
+                .map(SyntaxTree::to_string)
+                .collect_vec()
+        };
+        assert_eq!(formulae(1), ["⊤", "⊥", "x0"]);
+        // Only X ⊤ (not at the end of the trace) and N ⊥ (at the end of the trace) are not constants.
+        assert_eq!(
+            formulae(2),
+            ["X(⊤)", "N(⊥)", "¬(x0)", "X(x0)", "N(x0)", "G(x0)", "F(x0)"]
+        );
+
+        // ⊤ U φ ≡ F φ is the only rule on constants which does not apply, as F is not allowed.
+        let operators: Operators = "⊤,⊥,!,&,|,->,U".parse().expect("operators");
+        let formulae = operators
+            .gen_formulae(3, &[0])
+            .iter()
+            .map(SyntaxTree::to_string)
+            .filter(|formula| formula.contains(['⊤', '⊥']))
+            .collect_vec();
+        assert_eq!(formulae, ["(⊤)U(x0)"]);
+    }
+
+    #[test]
+    fn bounded() {
+        // x0 holds within 2 steps from the start on positive traces only.
+        let mut sample = DynSample::new(1);
+        for (trace, positive) in [
+            ("100000", true),
+            ("010000", true),
+            ("001000", true),
+            ("000100", false),
+            ("000001", false),
+            ("000000", false),
+        ] {
+            let trace = trace.chars().map(|c| vec![c == '1']).collect();
+            let _ = if positive {
+                sample.add_positive_trace(trace)
+            } else {
+                sample.add_negative_trace(trace)
+            };
+        }
+        let options = SolveOptions {
+            operators: "!,X,G,F,&,|,G[],F[]"
+                .parse::<Operators>()
+                .expect("operators")
+                .with_horizon(3),
+            ..Default::default()
+        };
+        for solution in [
+            solve(&sample, &options),
+            solve_bottom_up(&sample, &options),
+            crate::solve_sat(&sample, &options),
+        ] {
+            assert_eq!(
+                solution.solution().map(|solution| solution.to_string()),
+                Some("F[0,2](x0)".to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn tautology() {
+        let mut sample = DynSample::new(1);
+        sample.add_positive_trace(vec![vec![true]]).expect("trace");
+        sample.add_positive_trace(vec![vec![false]]).expect("trace");
+        let options = SolveOptions {
+            operators: "⊤,⊥,!,X,F,&,|".parse().expect("operators"),
+            ..Default::default()
+        };
+        assert_eq!(solve(&sample, &options).solution(), Some(SyntaxTree::True));
+    }
+
+    #[test]
+    fn same_size_as_bottom_up() {
+        use rand::{rngs::StdRng, Rng, SeedableRng};
+
+        // Bottom-up search does not rely on the filtering criteria,
+        // so it finds solutions of minimal size for every set of operators.
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..20 {
+            let mut sample = DynSample::new(2);
+            while sample.positive_traces() + sample.negative_traces() < 6 {
+                let trace = (0..4)
+                    .map(|_| (0..2).map(|_| rng.gen()).collect())
+                    .collect();
+                let _ = if rng.gen() {
+                    sample.add_positive_trace(trace)
+                } else {
+                    sample.add_negative_trace(trace)
+                };
+            }
+            for operators in OPERATOR_SETS {
+                let options = SolveOptions {
+                    max_size: Some(6),
+                    operators: parse_operators(operators),
+                    ..Default::default()
+                };
+                let outcome = solve(&sample, &options);
+                if let Outcome::Solved(solution) = &outcome {
+                    assert!(sample.is_consistent(solution), "{solution}");
+                }
+                assert_eq!(
+                    outcome.map(|solution| cost(&solution, &options.operators)),
+                    solve_bottom_up(&sample, &options)
+                        .map(|solution| cost(&solution, &options.operators)),
+                    "{sample:?} with {operators}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn sat() {
+        // The solution with all operators is `G(x0)`.
+        let sample = load_sample("SATcomparison/universality/0000.ron");
+        let options = SolveOptions {
+            max_size: Some(7),
+            operators: "!,F,&,|".parse().expect("operators"),
+            ..Default::default()
+        };
+        let solution = crate::solve_sat(&sample, &options)
+            .solution()
+            .expect("solution");
+        assert!(sample.is_consistent(&solution), "{solution}");
+        assert!(cost(&solution, &options.operators).is_some(), "{solution}");
+    }
+}
+
+#[cfg(test)]
+mod infinite {
+    use super::*;
+
+    fn lasso(prefix: &[[bool; 2]], cycle: &[[bool; 2]]) -> Lasso {
+        let states = |states: &[[bool; 2]]| states.iter().map(|state| state.to_vec()).collect();
+        Lasso::new(states(prefix), states(cycle))
+    }
+
+    #[test]
+    fn solve_lasso_sample() {
+        let mut sample = LassoSample::new(2);
+        // x0 holds infinitely often on positive traces only,
+        // while negative traces agree with positive ones on x0 up to some time instant.
+        let (off, on) = ([false, false], [true, false]);
+        for positive in [
+            lasso(&[], &[on, off]),
+            lasso(&[off, off], &[[false, true], [false, true], [true, true]]),
+        ] {
+            sample.add_positive_trace(positive).expect("positive trace");
+        }
+        for negative in [
+            lasso(&[on], &[off]),
+            lasso(&[off, on], &[off]),
+            lasso(&[on, off, on, off], &[[false, true]]),
+            lasso(&[off, off, off, off, [true, true]], &[off]),
+        ] {
+            sample.add_negative_trace(negative).expect("negative trace");
+        }
+        let solution = solve_lasso(&sample, &SolveOptions::default())
+            .solution()
+            .expect("solution");
+        assert!(sample.is_consistent(&solution));
+        assert_eq!(solution, "G F x0".parse().expect("formula"));
+    }
+
+    #[test]
+    fn unsolvable() {
+        let mut sample = LassoSample::new(2);
+        sample
+            .add_positive_trace(lasso(&[], &[[true, false], [false, true]]))
+            .expect("positive trace");
+        sample
+            .negative_traces
+            .push(lasso(&[[true, false]], &[[false, true], [true, false]]));
+        assert_eq!(
+            solve_lasso(&sample, &SolveOptions::default()),
+            Outcome::Unsolvable
+        );
+    }
+}
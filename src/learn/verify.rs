@@ -0,0 +1,367 @@
+//! Exhaustive verification of the filtering criteria pruning the enumeration of formulae.
+
+use super::*;
+use std::collections::{HashMap, HashSet};
+
+/// A formula discarded by the filtering criteria of the enumeration
+/// with no equivalent formula, no larger than it, being kept in its place,
+/// so that solvers might miss the solutions it stands for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PruningCounterexample {
+    /// The discarded formula, built from kept subformulae.
+    pub discarded: SyntaxTree,
+    /// The kept formula, no larger than the discarded one, disagreeing with it at the fewest time instants.
+    pub closest: SyntaxTree,
+    /// A shortest trace on which the two formulae differ.
+    pub trace: DynTrace,
+    /// For infinite-trace semantics, the time instant of the trace from which its states repeat forever.
+    pub loop_start: Option<Time>,
+    /// The first time instant of the trace at which the two formulae differ.
+    pub time: Time,
+}
+
+impl fmt::Display for PruningCounterexample {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} is discarded, but it differs from {} at time {} of trace {:?}",
+            self.discarded, self.closest, self.time, self.trace
+        )?;
+        if let Some(loop_start) = self.loop_start {
+            write!(f, " looping back to time {loop_start}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Operators {
+    /// Checks the filtering criteria of the enumeration of formulae (the `check_*` functions) against the semantics:
+    /// every formula up to the given size over the given variables, built from kept subformulae but discarded by the criteria,
+    /// has to be equivalent to some kept formula no larger than it.
+    /// By induction on the size, every formula is then equivalent to a kept one, so that no solution is missed.
+    ///
+    /// Formulae are deemed equivalent if they have the same truth value at every time instant
+    /// of every trace up to `max_len` long, so the check is exhaustive up to that bound.
+    /// Under infinite-trace semantics (see `Operators::with_semantics`), the traces are the lassos
+    /// with prefix and cycle up to `max_len` long in total.
+    ///
+    /// Returns the counterexamples of the smallest size for which there are any,
+    /// or an empty vector if the criteria are sound up to the given bounds.
+    pub fn verify_pruning(
+        &self,
+        max_size: usize,
+        vars: &[Idx],
+        max_len: usize,
+    ) -> Vec<PruningCounterexample> {
+        self.pruning_counterexamples(max_size, vars, max_len, |size| {
+            self.gen_formulae(size, vars)
+        })
+    }
+
+    /// Same as `verify_pruning`, with the kept formulae of each size given by `kept`.
+    fn pruning_counterexamples(
+        &self,
+        max_size: usize,
+        vars: &[Idx],
+        max_len: usize,
+        kept: impl Fn(usize) -> Vec<SyntaxTree>,
+    ) -> Vec<PruningCounterexample> {
+        // The traces, each with the start of its loop (if infinite) and the number of time instants to compare.
+        let traces = match self.semantics() {
+            Semantics::Finite => all_traces(vars, max_len)
+                .map(|trace| {
+                    let len = trace.len();
+                    (trace, None, len)
+                })
+                .collect_vec(),
+            // Subformulae are periodic after as many repetitions of the cycle as they nest past operators.
+            Semantics::Infinite => all_lassos(vars, max_len)
+                .map(|lasso| {
+                    let len = lasso.loop_start() + (max_size + 1) * lasso.cycle().len();
+                    let trace = [lasso.prefix(), lasso.cycle()].concat();
+                    (trace, Some(lasso.loop_start()), len)
+                })
+                .collect_vec(),
+        };
+        let bit_traces = traces
+            .iter()
+            .map(|(trace, _, _)| BitTrace::new(trace))
+            .collect_vec();
+        let lassos = traces
+            .iter()
+            .filter_map(|(trace, loop_start, _)| {
+                loop_start.map(|loop_start| {
+                    Lasso::new(trace[..loop_start].to_vec(), trace[loop_start..].to_vec())
+                })
+            })
+            .collect_vec();
+        let signature = |formula: &SyntaxTree| -> Vec<u64> {
+            let mut signature = Vec::new();
+            match self.semantics() {
+                Semantics::Finite => {
+                    let mut buffer = Vec::new();
+                    for trace in &bit_traces {
+                        let words = words(trace.trace_len());
+                        buffer.resize(words * formula.eval_buffers(), 0);
+                        formula.eval_all_times_in(trace, &mut buffer);
+                        signature.extend_from_slice(&buffer[..words]);
+                    }
+                }
+                Semantics::Infinite => {
+                    for (lasso, (_, _, len)) in lassos.iter().zip(&traces) {
+                        let mut words = vec![0; words(*len)];
+                        for (time, value) in formula
+                            .eval_lasso_all_times(lasso, *len)
+                            .into_iter()
+                            .enumerate()
+                        {
+                            words[time / 64] |= (value as u64) << (time % 64);
+                        }
+                        signature.extend(words);
+                    }
+                }
+            }
+            signature
+        };
+
+        // `kept_formulae[n]` contains the kept formulae of size `n`.
+        let mut kept_formulae: Vec<Vec<Arc<SyntaxTree>>> = vec![Vec::new()];
+        // The signatures of all kept formulae so far, each with the smallest formula having it.
+        let mut kept_signatures: HashMap<Vec<u64>, Arc<SyntaxTree>> = HashMap::new();
+        for size in 1..=max_size {
+            let formulae = kept(size).into_iter().map(Arc::new).collect_vec();
+            for formula in &formulae {
+                kept_signatures
+                    .entry(signature(formula))
+                    .or_insert_with(|| formula.clone());
+            }
+            let kept_set: HashSet<&SyntaxTree> = formulae.iter().map(AsRef::as_ref).collect();
+            kept_formulae.push(formulae.clone());
+
+            let counterexamples = self
+                .compositions(size, &kept_formulae)
+                .into_iter()
+                .filter(|formula| !kept_set.contains(formula))
+                .filter_map(|discarded| {
+                    let discarded_signature = signature(&discarded);
+                    if kept_signatures.contains_key(&discarded_signature) {
+                        return None;
+                    }
+                    let (closest, closest_signature) = kept_signatures
+                        .iter()
+                        .min_by_key(|(signature, formula)| {
+                            let distance: u32 = signature
+                                .iter()
+                                .zip(&discarded_signature)
+                                .map(|(word, other)| (word ^ other).count_ones())
+                                .sum();
+                            (distance, formula.size())
+                        })
+                        .map(|(signature, formula)| (formula.as_ref().clone(), signature))
+                        .expect("discarded formulae are built from kept ones");
+                    let (trace, loop_start, time) =
+                        first_difference(&traces, &discarded_signature, closest_signature);
+                    Some(PruningCounterexample {
+                        discarded,
+                        closest,
+                        trace,
+                        loop_start,
+                        time,
+                    })
+                })
+                .collect_vec();
+            if !counterexamples.is_empty() {
+                return counterexamples;
+            }
+        }
+        Vec::new()
+    }
+
+    /// All the formulae of the given size obtained by applying an allowed operator to kept formulae,
+    /// regardless of the filtering criteria.
+    /// Constants and atoms are left out, as they are never discarded.
+    fn compositions(&self, size: usize, kept: &[Vec<Arc<SyntaxTree>>]) -> Vec<SyntaxTree> {
+        let mut formulae = Vec::new();
+        for (op, cost) in self.iter() {
+            match op.arity() {
+                1 if cost < size => {
+                    for child in &kept[size - cost] {
+                        let child = child.clone();
+                        match op {
+                            Operator::Not => formulae.push(SyntaxTree::Not(child)),
+                            Operator::Next => formulae.push(SyntaxTree::Next(child)),
+                            Operator::WeakNext => formulae.push(SyntaxTree::WeakNext(child)),
+                            Operator::Globally => formulae.push(SyntaxTree::Globally(child)),
+                            Operator::Finally => formulae.push(SyntaxTree::Finally(child)),
+                            Operator::Yesterday => formulae.push(SyntaxTree::Yesterday(child)),
+                            Operator::Once => formulae.push(SyntaxTree::Once(child)),
+                            Operator::Historically => {
+                                formulae.push(SyntaxTree::Historically(child))
+                            }
+                            Operator::BoundedGlobally => {
+                                formulae.extend(self.intervals().map(|interval| {
+                                    SyntaxTree::BoundedGlobally(interval, child.clone())
+                                }))
+                            }
+                            Operator::BoundedFinally => {
+                                formulae.extend(self.intervals().map(|interval| {
+                                    SyntaxTree::BoundedFinally(interval, child.clone())
+                                }))
+                            }
+                            _ => unreachable!("{op} is not unary"),
+                        }
+                    }
+                }
+                2 if cost + 1 < size => {
+                    for left_size in 1..size - cost {
+                        for (left, right) in kept[left_size]
+                            .iter()
+                            .cartesian_product(&kept[size - cost - left_size])
+                        {
+                            let (left, right) = (left.clone(), right.clone());
+                            match op {
+                                Operator::And => formulae.push(SyntaxTree::And(left, right)),
+                                Operator::Or => formulae.push(SyntaxTree::Or(left, right)),
+                                Operator::Implies => {
+                                    formulae.push(SyntaxTree::Implies(left, right))
+                                }
+                                Operator::Until => formulae.push(SyntaxTree::Until(left, right)),
+                                Operator::Release => {
+                                    formulae.push(SyntaxTree::Release(left, right))
+                                }
+                                Operator::WeakUntil => {
+                                    formulae.push(SyntaxTree::WeakUntil(left, right))
+                                }
+                                Operator::Since => formulae.push(SyntaxTree::Since(left, right)),
+                                Operator::BoundedUntil => {
+                                    formulae.extend(self.intervals().map(|interval| {
+                                        SyntaxTree::BoundedUntil(
+                                            interval,
+                                            left.clone(),
+                                            right.clone(),
+                                        )
+                                    }))
+                                }
+                                _ => unreachable!("{op} is not binary"),
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        formulae
+    }
+}
+
+/// The first trace on which two signatures differ (with the start of its loop, if any),
+/// and the first time instant at which they do.
+fn first_difference(
+    traces: &[(DynTrace, Option<Time>, usize)],
+    signature: &[u64],
+    other: &[u64],
+) -> (DynTrace, Option<Time>, Time) {
+    let mut start = 0;
+    for (trace, loop_start, len) in traces {
+        if let Some(time) = (0..*len).find(|time| {
+            let (word, bit) = (start + time / 64, time % 64);
+            (signature[word] ^ other[word]) & (1 << bit) != 0
+        }) {
+            return (trace.clone(), *loop_start, time);
+        }
+        start += words(*len);
+    }
+    unreachable!("signatures differ")
+}
+
+#[cfg(test)]
+mod pruning {
+    use super::*;
+
+    // Bounded operators are checked with horizon 1, as every interval multiplies the formulae to check.
+    const OPERATOR_SETS: [&str; 10] = [
+        "!,X,G,F,&,|,->,U",
+        "!,X,&",
+        "!,F,G,&,|:3,->,U",
+        "F,G,&,|,U:2,->:2",
+        "!,X,N,G,F,&,|,->,U,R,W",
+        "!,X,F,Y,O,H,&,|,S",
+        "⊤,⊥,!,X,G,&,|,->,U",
+        "true:2,false,N,F,&,|,R,W",
+        "⊤,⊥,!,G[],F[],&,|,U[]",
+        "⊤,X,F,F[]:2,&,|,U[]:3",
+    ];
+
+    #[test]
+    fn sound() {
+        for operators in OPERATOR_SETS {
+            let operators = operators
+                .parse::<Operators>()
+                .expect("operators")
+                .with_horizon(1);
+            let counterexamples = operators.verify_pruning(5, &[0, 1], 3);
+            assert!(
+                counterexamples.is_empty(),
+                "{operators}: {}",
+                counterexamples.iter().join("\n")
+            );
+        }
+    }
+
+    #[test]
+    fn sound_infinite() {
+        for operators in OPERATOR_SETS {
+            let operators = operators
+                .parse::<Operators>()
+                .expect("operators")
+                .with_horizon(1)
+                .with_semantics(Semantics::Infinite);
+            let counterexamples = operators.verify_pruning(4, &[0, 1], 3);
+            assert!(
+                counterexamples.is_empty(),
+                "{operators}: {}",
+                counterexamples.iter().join("\n")
+            );
+        }
+    }
+
+    // The filtering criteria for infinite traces are unsound on finite ones, as N φ ≡ X φ only on infinite traces.
+    #[test]
+    fn infinite_on_finite() {
+        let operators = "!,X,N".parse::<Operators>().expect("operators");
+        let infinite = operators.with_semantics(Semantics::Infinite);
+        assert!(infinite.verify_pruning(3, &[0], 3).is_empty());
+        let counterexamples =
+            operators.pruning_counterexamples(3, &[0], 3, |size| infinite.gen_formulae(size, &[0]));
+        assert_eq!(counterexamples.len(), 1);
+        let counterexample = &counterexamples[0];
+        assert_eq!(counterexample.discarded, "N x0".parse().expect("formula"));
+        assert_eq!(counterexample.loop_start, None);
+        assert_eq!(counterexample.trace, vec![vec![false]]);
+    }
+
+    // The verification has to notice if a formula without equivalent ones goes missing.
+    #[test]
+    fn counterexample() {
+        let operators = Operators::default();
+        let missing: SyntaxTree = "F x0".parse().expect("formula");
+        let counterexamples = operators.pruning_counterexamples(4, &[0, 1], 3, |size| {
+            operators
+                .gen_formulae(size, &[0, 1])
+                .into_iter()
+                .filter(|formula| formula != &missing)
+                .collect()
+        });
+        assert_eq!(counterexamples.len(), 1);
+        let counterexample = &counterexamples[0];
+        assert_eq!(counterexample.discarded, missing);
+        assert_ne!(
+            missing.eval_at_time(&counterexample.trace, counterexample.time),
+            counterexample
+                .closest
+                .eval_at_time(&counterexample.trace, counterexample.time)
+        );
+        // Traces of length 1 cannot tell F x0 apart from x0.
+        assert_eq!(counterexample.trace.len(), 2);
+    }
+}
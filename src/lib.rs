@@ -21,44 +21,194 @@
 //! let until = SyntaxTree::Until(Arc::new(ATOM_0), Arc::new(ATOM_1));
 //! ```
 //!
+//! Formulae can also be parsed from the same notation they are printed with,
+//! or from its ASCII alternative.
+//!
+//! ```
+//! # use learn_ltl::SyntaxTree;
+//! # use std::sync::Arc;
+//! let until: SyntaxTree = "(x0)U(x1)".parse().expect("valid formula");
+//! assert_eq!(until, SyntaxTree::Until(Arc::new(SyntaxTree::Atom(0)), Arc::new(SyntaxTree::Atom(1))));
+//! assert_eq!(until, "x0 U x1".parse().expect("valid formula"));
+//! ```
+//!
 //! [`Trace`]s are defined as `Trace<N> = Vec<[bool; N]>` where `N` is a `const` parameter.
 //! A [`SyntaxTree`] can be evaluated over a [`Trace`].
 //!
 //! ```
+//! # use learn_ltl::SyntaxTree;
+//! # use std::sync::Arc;
+//! # let and = SyntaxTree::And(Arc::new(SyntaxTree::Atom(0)), Arc::new(SyntaxTree::Atom(1)));
 //! let tt_trace = vec![[true, true]];
-//! assert!(tt_trace.eval(&and));
+//! assert!(and.eval(&tt_trace));
 //!
 //! let ff_trace = vec![[false, false]];
-//! assert!(!ff_trace.eval(&and));
+//! assert!(!and.eval(&ff_trace));
 //! ```
 //!
 //! A sample is given by two [`Vec`]s of [`Trace`]s, and (optionally) custom variable names.
 //!
-//! A [`SyntaxTree`] can be evaluated over a [`Sample`].
+//! A [`SyntaxTree`] can be evaluated over a [`Sample`],
+//! through the methods of the [`LabeledSample`] trait (which [`Sample`] also provides without importing it).
 //! ```
 //! use learn_ltl::Sample;
+//! # use learn_ltl::SyntaxTree;
+//! # use std::sync::Arc;
+//! # let and = SyntaxTree::And(Arc::new(SyntaxTree::Atom(0)), Arc::new(SyntaxTree::Atom(1)));
+//! # let or = SyntaxTree::Or(Arc::new(SyntaxTree::Atom(0)), Arc::new(SyntaxTree::Atom(1)));
 //!
 //! let sample = Sample {
-//!     var_names: Sample::var_names(),
 //!     positive_traces: vec![vec![[true, true]]],
 //!     negative_traces: vec![
 //!         vec![[false, true]],
 //!         vec![[true, false]],
 //!         vec![[false, false]],
 //!     ],
+//!     ..Default::default()
 //! };
 //!
 //! assert!(sample.is_consistent(&and));
-//! assert!(!sample.is_consistent(&and));
+//! assert!(!sample.is_consistent(&or));
+//! ```
+//!
+//! When the number of variables is only known at runtime (e.g., when loading a sample from file),
+//! a [`DynSample`] can be used instead, with traces of type [`DynTrace`] `= Vec<Vec<bool>>`.
+//!
+//! ```
+//! use learn_ltl::{DynSample, LabeledSample};
+//! # use learn_ltl::SyntaxTree;
+//! # use std::sync::Arc;
+//! # let and = SyntaxTree::And(Arc::new(SyntaxTree::Atom(0)), Arc::new(SyntaxTree::Atom(1)));
+//!
+//! let sample: DynSample = ron::de::from_str(
+//!     "(positive_traces: [[(true, true)]], negative_traces: [[(false, true)], [(true, false)]])",
+//! ).expect("valid sample");
+//!
+//! assert_eq!(sample.num_vars(), 2);
+//! assert!(sample.is_consistent(&and));
+//! ```
+//!
+//! A formula consistent with a sample is learned by [`solve`] (or by one of the alternative algorithms),
+//! which can be bounded in the size of the formulae it searches and in time.
+//!
+//! ```
+//! use learn_ltl::{solve, Outcome, SolveOptions};
+//! # use learn_ltl::Sample;
+//! # use std::time::Duration;
+//! # let sample = Sample {
+//! #     positive_traces: vec![vec![[true, true]]],
+//! #     negative_traces: vec![vec![[false, true]], vec![[true, false]], vec![[false, false]]],
+//! #     ..Default::default()
+//! # };
+//!
+//! let options = SolveOptions {
+//!     max_size: Some(5),
+//!     timeout: Some(Duration::from_secs(10)),
+//!     ..Default::default()
+//! };
+//!
+//! match solve(&sample, &options) {
+//!     Outcome::Solved(formula) => assert_eq!(formula.to_string(), "(x0)∧(x1)"),
+//!     outcome => panic!("unexpected outcome {outcome:?}"),
+//! }
+//! ```
+//!
+//! Formulae can be simplified with sound rewriting rules under finite-trace semantics,
+//! converted to negation normal form, or put in a canonical order.
+//!
+//! ```
+//! # use learn_ltl::SyntaxTree;
+//! let formula: SyntaxTree = "!F !x0 | (x1 & !x1)".parse().expect("valid formula");
+//! assert_eq!(formula.simplify().to_string(), "G(x0)");
+//! assert_eq!(formula.to_nnf().to_string(), "(G(x0))∨((x1)∧(¬(x1)))");
+//! ```
+//!
+//! Formulae can be compared by evaluating them on all traces up to a given length,
+//! getting a shortest trace telling them apart if they differ.
+//!
 //! ```
+//! use learn_ltl::Comparison;
+//! # use learn_ltl::SyntaxTree;
+//! let next: SyntaxTree = "X x0".parse().expect("valid formula");
+//! let weak_next: SyntaxTree = "N x0".parse().expect("valid formula");
+//! assert_eq!(next.compare(&weak_next, 4), Comparison::Stronger(vec![vec![false]]));
+//! ```
+//!
+//! Formulae can also be compiled into minimal deterministic finite automata over the variables they use,
+//! which can be run on traces, checked for emptiness, serialized, or exported to DOT.
+//!
+//! ```
+//! # use learn_ltl::SyntaxTree;
+//! let formula: SyntaxTree = "x0 U x1".parse().expect("valid formula");
+//! let dfa = formula.to_dfa().expect("few variables");
+//! assert!(dfa.accepts(&[[true, false], [false, true]]));
+//! assert!(!dfa.accepts(&[[true, false], [false, false]]));
+//! assert!(!dfa.is_empty() && !dfa.is_universal());
+//! ```
+//!
+//! A [`Monitor`] evaluates a formula online, one observation at a time,
+//! telling whether the trace so far is conclusive or might still change the outcome.
+//!
+//! ```
+//! use learn_ltl::{Monitor, Truth, Verdict};
+//! # use learn_ltl::SyntaxTree;
+//! let formula: SyntaxTree = "G x0".parse().expect("valid formula");
+//! let mut monitor = Monitor::new(&formula).expect("few variables");
+//! assert_eq!(monitor.step(&[true]), Verdict::CurrentlySatisfied);
+//! assert_eq!(monitor.truth(), Truth::Inconclusive);
+//! assert_eq!(monitor.step(&[false]), Verdict::Violated);
+//! assert_eq!(monitor.truth(), Truth::False);
+//! ```
+//!
+//! The value of a formula on a trace can be explained by the values of its subformulae
+//! at the time instants justifying it, such as the first one where `G` fails.
+//!
+//! ```
+//! # use learn_ltl::SyntaxTree;
+//! let formula: SyntaxTree = "G x0".parse().expect("valid formula");
+//! let explanation = formula.explain(&[[true], [false], [false]]);
+//! assert!(!explanation.value);
+//! assert_eq!(explanation.witnesses(), vec![1]);
+//! ```
+//!
+//! Formulae can also be evaluated on infinite traces given as lassos, i.e., a prefix followed by a repeating cycle,
+//! with the standard semantics of LTL, and learned from samples of lassos with [`solve_lasso`].
+//!
+//! ```
+//! use learn_ltl::Lasso;
+//! # use learn_ltl::SyntaxTree;
+//! let lasso = Lasso::new(vec![vec![true]], vec![vec![false], vec![true]]);
+//! let formula: SyntaxTree = "G F !x0".parse().expect("valid formula");
+//! assert!(formula.eval_lasso(&lasso));
+//! ```
+
+mod automaton;
+
+mod equivalence;
+
+mod explain;
+
+mod lasso;
 
 mod learn;
 
+mod monitor;
+
+mod sat;
+
+mod simplify;
+
 /// This module contains the definition of
 mod syntax;
 
 mod trace;
 
+pub use automaton::*;
+pub use equivalence::*;
+pub use explain::*;
+pub use lasso::*;
 pub use learn::*;
+pub use monitor::*;
+pub use sat::*;
 pub use syntax::*;
 pub use trace::*;
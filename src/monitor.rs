@@ -0,0 +1,282 @@
+use crate::automaton::*;
+use crate::syntax::*;
+
+/// The verdict of a [`Monitor`] on the trace observed so far, under LTLf semantics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Verdict {
+    /// The formula holds on the trace, and on all its extensions.
+    Satisfied,
+    /// The formula does not hold on the trace, nor on any of its extensions.
+    Violated,
+    /// The formula holds on the trace, but not on some of its extensions.
+    CurrentlySatisfied,
+    /// The formula does not hold on the trace, but it does on some of its extensions.
+    CurrentlyViolated,
+}
+
+impl Verdict {
+    /// Whether the formula holds on the trace, if it were to end now.
+    pub fn value(self) -> bool {
+        matches!(self, Verdict::Satisfied | Verdict::CurrentlySatisfied)
+    }
+
+    /// Whether the verdict is final, whatever is observed next.
+    pub fn is_permanent(self) -> bool {
+        matches!(self, Verdict::Satisfied | Verdict::Violated)
+    }
+}
+
+/// The three-valued truth of a formula on a prefix of a trace, as in runtime verification:
+/// whether it is decided by the prefix, whatever the rest of the trace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Truth {
+    /// Every extension of the prefix (including itself) satisfies the formula.
+    True,
+    /// No extension of the prefix (including itself) satisfies the formula.
+    False,
+    /// Some extensions of the prefix satisfy the formula, and some do not.
+    Inconclusive,
+}
+
+impl From<Verdict> for Truth {
+    fn from(verdict: Verdict) -> Self {
+        match verdict {
+            Verdict::Satisfied => Truth::True,
+            Verdict::Violated => Truth::False,
+            Verdict::CurrentlySatisfied | Verdict::CurrentlyViolated => Truth::Inconclusive,
+        }
+    }
+}
+
+/// A monitor evaluating a formula on a trace observed one state at a time,
+/// as when the trace is received online rather than known up front.
+///
+/// The formula is compiled into an automaton beforehand (see [`SyntaxTree::to_dfa`]),
+/// so that each observation takes constant time, independent of the length of the trace.
+#[derive(Debug, Clone)]
+pub struct Monitor {
+    dfa: Dfa,
+    /// The verdict of each state of the automaton.
+    verdicts: Vec<Verdict>,
+    state: usize,
+}
+
+impl Monitor {
+    /// Builds a monitor for the given formula, which can use at most [`Dfa::MAX_VARS`] variables.
+    pub fn new(formula: &SyntaxTree) -> Result<Self, TooManyVars> {
+        formula.to_dfa().map(Monitor::from)
+    }
+
+    /// Observes the next state of the trace, returning the verdict on the trace observed so far.
+    /// Variables not appearing in the formula are ignored.
+    pub fn step(&mut self, observation: &[bool]) -> Verdict {
+        self.state = self.dfa.next(self.state, observation);
+        self.verdict()
+    }
+
+    /// The verdict on the trace observed so far.
+    /// As the empty trace satisfies no formula, it is never satisfied before the first observation.
+    pub fn verdict(&self) -> Verdict {
+        self.verdicts[self.state]
+    }
+
+    /// The three-valued truth of the formula on the trace observed so far,
+    /// taken as the prefix of an ongoing trace rather than as a complete one.
+    pub fn truth(&self) -> Truth {
+        self.verdict().into()
+    }
+
+    /// Forgets the observed trace, to start monitoring a new one.
+    pub fn reset(&mut self) {
+        self.state = self.dfa.initial;
+    }
+}
+
+impl SyntaxTree {
+    /// Evaluates the formula on a prefix of a trace: unlike [`SyntaxTree::eval`], the end of the prefix is not taken as the end of the trace,
+    /// and the result is [`Truth::Inconclusive`] if it depends on how the trace goes on.
+    ///
+    /// The formula is compiled into an automaton at each call, so a [`Monitor`] is to be preferred to evaluate many prefixes.
+    pub fn eval_prefix<S: AsRef<[bool]>>(&self, prefix: &[S]) -> Result<Truth, TooManyVars> {
+        let mut monitor = Monitor::new(self)?;
+        for observation in prefix {
+            monitor.step(observation.as_ref());
+        }
+        Ok(monitor.truth())
+    }
+}
+
+impl From<Dfa> for Monitor {
+    fn from(dfa: Dfa) -> Self {
+        // Whether some accepting (resp. rejecting) state is reachable from each state, computed as a fixpoint.
+        let reachable = |target: bool| {
+            let mut reachable = dfa
+                .accepting
+                .iter()
+                .map(|accepting| *accepting == target)
+                .collect::<Vec<_>>();
+            let mut changed = true;
+            while changed {
+                changed = false;
+                for state in 0..dfa.states() {
+                    if !reachable[state]
+                        && dfa.transitions[state].iter().any(|next| reachable[*next])
+                    {
+                        reachable[state] = true;
+                        changed = true;
+                    }
+                }
+            }
+            reachable
+        };
+        let (can_accept, can_reject) = (reachable(true), reachable(false));
+        let verdicts = (0..dfa.states())
+            .map(|state| match (can_accept[state], can_reject[state]) {
+                (true, false) => Verdict::Satisfied,
+                (false, _) => Verdict::Violated,
+                (true, true) if dfa.accepting[state] => Verdict::CurrentlySatisfied,
+                (true, true) => Verdict::CurrentlyViolated,
+            })
+            .collect();
+        let state = dfa.initial;
+        Monitor {
+            dfa,
+            verdicts,
+            state,
+        }
+    }
+}
+
+#[cfg(test)]
+mod online {
+    use super::*;
+    use crate::trace::*;
+    use crate::Operators;
+    use itertools::Itertools;
+    use std::collections::HashSet;
+
+    // At every step, the monitor agrees with the evaluation of the prefix observed so far,
+    // and permanent verdicts hold on every extension.
+    #[test]
+    fn prefixes() {
+        let vars = [0, 1];
+        let traces = all_traces(&vars, 4).collect::<Vec<_>>();
+        let extensions = all_traces(&vars, 2).collect::<Vec<_>>();
+        for formula in Operators::all().with_horizon(1).gen_formulae(3, &vars) {
+            let mut monitor = Monitor::new(&formula).unwrap();
+            for trace in &traces {
+                monitor.reset();
+                for (len, observation) in trace.iter().enumerate() {
+                    let verdict = monitor.step(observation);
+                    let prefix = &trace[..=len];
+                    assert_eq!(
+                        verdict.value(),
+                        formula.eval(prefix),
+                        "{formula} on {prefix:?}"
+                    );
+                    if verdict.is_permanent() {
+                        for extension in &extensions {
+                            let extended = prefix.iter().chain(extension).cloned().collect_vec();
+                            assert_eq!(verdict.value(), formula.eval(&extended), "{formula}");
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // Inconclusive prefixes have both satisfying and violating extensions.
+    #[test]
+    fn truth() {
+        let vars = [0, 1];
+        let prefixes = all_traces(&vars, 3).collect::<Vec<_>>();
+        let extensions = all_traces(&vars, 3).collect::<Vec<_>>();
+        for formula in Operators::all().with_horizon(1).gen_formulae(3, &vars) {
+            for prefix in &prefixes {
+                if formula.eval_prefix(prefix) == Ok(Truth::Inconclusive) {
+                    let values = extensions
+                        .iter()
+                        .map(|extension| {
+                            let extended = prefix.iter().chain(extension).cloned().collect_vec();
+                            formula.eval(&extended)
+                        })
+                        .chain(std::iter::once(formula.eval(prefix)))
+                        .collect::<HashSet<_>>();
+                    assert_eq!(values.len(), 2, "{formula} on {prefix:?}");
+                }
+            }
+        }
+
+        let formula = parse_formula("G(x0 -> F x1)");
+        assert_eq!(
+            formula.eval_prefix(&[[true, false]]),
+            Ok(Truth::Inconclusive)
+        );
+        // The request is still pending, but no alarm is raised.
+        assert!(!formula.eval(&[[true, false]]));
+        let formula = parse_formula("G !x0 & F x1");
+        assert_eq!(
+            formula.eval_prefix(&[[false, true]]),
+            Ok(Truth::Inconclusive)
+        );
+        assert_eq!(
+            formula.eval_prefix(&[[false, true], [true, false]]),
+            Ok(Truth::False)
+        );
+        let formula = parse_formula("x0 U x1");
+        assert_eq!(
+            formula.eval_prefix(&[[true, false], [false, true]]),
+            Ok(Truth::True)
+        );
+        assert_eq!(
+            formula.eval_prefix::<[bool; 2]>(&[]),
+            Ok(Truth::Inconclusive)
+        );
+    }
+
+    #[test]
+    fn verdicts() {
+        let mut monitor = Monitor::new(&parse_formula("F x0")).unwrap();
+        assert_eq!(monitor.verdict(), Verdict::CurrentlyViolated);
+        assert_eq!(monitor.step(&[false]), Verdict::CurrentlyViolated);
+        assert_eq!(monitor.step(&[true]), Verdict::Satisfied);
+        assert_eq!(monitor.step(&[false]), Verdict::Satisfied);
+
+        let mut monitor = Monitor::new(&parse_formula("G x0")).unwrap();
+        assert_eq!(monitor.step(&[true]), Verdict::CurrentlySatisfied);
+        assert_eq!(monitor.step(&[false]), Verdict::Violated);
+        monitor.reset();
+        assert_eq!(monitor.step(&[true]), Verdict::CurrentlySatisfied);
+
+        // Unsatisfiable formulae are violated from the start, valid ones satisfied from the first observation.
+        assert_eq!(
+            Monitor::new(&parse_formula("G X x0")).unwrap().verdict(),
+            Verdict::Violated
+        );
+        assert_eq!(
+            Monitor::new(&parse_formula("F N x0"))
+                .unwrap()
+                .step(&[false]),
+            Verdict::Satisfied
+        );
+
+        // Extra variables are ignored.
+        let mut monitor = Monitor::new(&parse_formula("x0 U x1")).unwrap();
+        assert_eq!(
+            monitor.step(&[true, false, true]),
+            Verdict::CurrentlyViolated
+        );
+        assert_eq!(monitor.step(&[false, true, false]), Verdict::Satisfied);
+    }
+
+    // Only the variables appearing in the formula are read, however large their indexes.
+    #[test]
+    fn sparse_vars() {
+        let mut observation = vec![false; 101];
+        let mut monitor = Monitor::new(&parse_formula("x70 U x100")).unwrap();
+        observation[70] = true;
+        assert_eq!(monitor.step(&observation), Verdict::CurrentlyViolated);
+        observation[100] = true;
+        assert_eq!(monitor.step(&observation), Verdict::Satisfied);
+    }
+}
@@ -4,12 +4,14 @@ use std::fs::File;
 use std::io::BufReader;
 use std::io::BufWriter;
 use std::io::Read;
+use std::path::Path;
 
 /// Generate a sample consistent with the given formula
 #[derive(Parser, Debug)]
 #[clap(name = "sampler")]
 struct Sampler {
-    /// Filename of the target formula
+    /// Filename of the target formula, either as a plain formula or with named variables (as saved by the solver).
+    /// Supported file types: ron, json.
     #[arg(short, long)]
     formula: String,
 
@@ -29,144 +31,62 @@ struct Sampler {
 fn main() -> std::io::Result<()> {
     let sampler = Sampler::parse();
 
-    let file = File::open(sampler.formula)?;
+    let path = Path::new(&sampler.formula);
+    let file = File::open(path)?;
     let mut buf_reader = BufReader::new(file);
     let mut contents = Vec::new();
     buf_reader.read_to_end(&mut contents)?;
-    let formula = ron::de::from_bytes::<SyntaxTree>(&contents).expect("formula");
+    let (formula, var_names) = if path.extension().is_some_and(|ext| ext == "json") {
+        serde_json::from_slice::<SyntaxTree>(&contents)
+            .map(|formula| (formula, None))
+            .or_else(|_| {
+                serde_json::from_slice::<NamedFormula>(&contents)
+                    .map(|named| (named.formula, Some(named.var_names)))
+            })
+            .expect("formula")
+    } else {
+        ron::de::from_bytes::<SyntaxTree>(&contents)
+            .map(|formula| (formula, None))
+            .or_else(|_| {
+                ron::de::from_bytes::<NamedFormula>(&contents)
+                    .map(|named| (named.formula, Some(named.var_names)))
+            })
+            .expect("formula")
+    };
     let vars = formula.vars();
 
     let name = format!("sample_{}.ron", formula);
     let file = File::create(name).expect("open sample file");
     let buf_writer = BufWriter::new(file);
 
-    // Ugly hack to get around limitations of deserialization for types with const generics.
-    match vars {
-        0 => {
-            let sample = sample::<0>(
-                &formula,
-                sampler.positives,
-                sampler.negatives,
-                sampler.length,
-            );
-            assert!(sample.is_consistent(&formula));
-            ron::ser::to_writer(buf_writer, &sample).expect("serialize sample");
-        }
-        1 => {
-            let sample = sample::<1>(
-                &formula,
-                sampler.positives,
-                sampler.negatives,
-                sampler.length,
-            );
-            assert!(sample.is_consistent(&formula));
-            ron::ser::to_writer(buf_writer, &sample).expect("serialize sample");
-        }
-        2 => {
-            let sample = sample::<2>(
-                &formula,
-                sampler.positives,
-                sampler.negatives,
-                sampler.length,
-            );
-            assert!(sample.is_consistent(&formula));
-            ron::ser::to_writer(buf_writer, &sample).expect("serialize sample");
-        }
-        3 => {
-            let sample = sample::<3>(
-                &formula,
-                sampler.positives,
-                sampler.negatives,
-                sampler.length,
-            );
-            assert!(sample.is_consistent(&formula));
-            ron::ser::to_writer(buf_writer, &sample).expect("serialize sample");
-        }
-        4 => {
-            let sample = sample::<4>(
-                &formula,
-                sampler.positives,
-                sampler.negatives,
-                sampler.length,
-            );
-            assert!(sample.is_consistent(&formula));
-            ron::ser::to_writer(buf_writer, &sample).expect("serialize sample");
-        }
-        5 => {
-            let sample = sample::<5>(
-                &formula,
-                sampler.positives,
-                sampler.negatives,
-                sampler.length,
-            );
-            assert!(sample.is_consistent(&formula));
-            ron::ser::to_writer(buf_writer, &sample).expect("serialize sample");
-        }
-        6 => {
-            let sample = sample::<6>(
-                &formula,
-                sampler.positives,
-                sampler.negatives,
-                sampler.length,
-            );
-            assert!(sample.is_consistent(&formula));
-            ron::ser::to_writer(buf_writer, &sample).expect("serialize sample");
-        }
-        7 => {
-            let sample = sample::<7>(
-                &formula,
-                sampler.positives,
-                sampler.negatives,
-                sampler.length,
-            );
-            assert!(sample.is_consistent(&formula));
-            ron::ser::to_writer(buf_writer, &sample).expect("serialize sample");
-        }
-        8 => {
-            let sample = sample::<8>(
-                &formula,
-                sampler.positives,
-                sampler.negatives,
-                sampler.length,
-            );
-            assert!(sample.is_consistent(&formula));
-            ron::ser::to_writer(buf_writer, &sample).expect("serialize sample");
-        }
-        9 => {
-            let sample = sample::<9>(
-                &formula,
-                sampler.positives,
-                sampler.negatives,
-                sampler.length,
-            );
-            assert!(sample.is_consistent(&formula));
-            ron::ser::to_writer(buf_writer, &sample).expect("serialize sample");
-        }
-        10 => {
-            let sample = sample::<10>(
-                &formula,
-                sampler.positives,
-                sampler.negatives,
-                sampler.length,
-            );
-            assert!(sample.is_consistent(&formula));
-            ron::ser::to_writer(buf_writer, &sample).expect("serialize sample");
-        }
-        _ => panic!("out-of-bound parameter"),
-    }
+    let sample = sample(
+        &formula,
+        vars as usize,
+        sampler.positives,
+        sampler.negatives,
+        sampler.length,
+        var_names.as_deref(),
+    );
+    assert!(sample.is_consistent(&formula));
+    ron::ser::to_writer(buf_writer, &sample).expect("serialize sample");
 
     Ok(())
 }
 
-fn sample<const N: usize>(
+fn sample(
     formula: &SyntaxTree,
+    vars: usize,
     positives: usize,
     negatives: usize,
     length: usize,
-) -> Sample<N> {
-    let mut sample = Sample::default();
+    var_names: Option<&[String]>,
+) -> DynSample {
+    let mut sample = DynSample::new(vars);
+    if let Some(var_names) = var_names {
+        sample.var_names.clone_from_slice(&var_names[..vars]);
+    }
     while sample.positive_traces() < positives || sample.negative_traces() < negatives {
-        let trace = Vec::from_iter((0..length).map(|_| gen_bools()));
+        let trace = Vec::from_iter((0..length).map(|_| gen_bools(vars)));
         let satisfaction = formula.eval(&trace);
         if satisfaction && sample.positive_traces() < positives {
             sample
@@ -181,9 +101,9 @@ fn sample<const N: usize>(
     sample
 }
 
-fn gen_bools<const N: usize>() -> [bool; N] {
+fn gen_bools(vars: usize) -> Vec<bool> {
     use rand::prelude::*;
-    let mut values = [true; N];
+    let mut values = vec![true; vars];
     rand::thread_rng().fill(&mut values[..]);
     values
 }
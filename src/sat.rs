@@ -0,0 +1,717 @@
+//! SAT-based learning of formulae,
+//! following [Neider, Gavran - Learning Linear Temporal Properties (2018)](https://doi.org/10.23919/FMCAD.2018.8603016),
+//! adapted to finite traces.
+
+mod cdcl;
+
+use crate::learn::{Limits, Operator, Outcome, SolveOptions};
+use crate::syntax::*;
+use crate::trace::*;
+
+use cdcl::{Lit, Solver, Var};
+
+use std::sync::Arc;
+
+/// Labels of the nodes of a formula DAG.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Label {
+    Atom(Idx),
+    True,
+    False,
+    Not,
+    Next,
+    WeakNext,
+    Globally,
+    Finally,
+    Yesterday,
+    Once,
+    Historically,
+    BoundedGlobally(Interval),
+    BoundedFinally(Interval),
+    And,
+    Or,
+    Implies,
+    Until,
+    Release,
+    WeakUntil,
+    Since,
+    BoundedUntil(Interval),
+}
+
+impl Label {
+    /// Whether the label has no children.
+    fn is_leaf(self) -> bool {
+        matches!(self, Label::Atom(_) | Label::True | Label::False)
+    }
+}
+
+const OPERATORS: [(Operator, Label); 17] = [
+    (Operator::True, Label::True),
+    (Operator::False, Label::False),
+    (Operator::Not, Label::Not),
+    (Operator::Next, Label::Next),
+    (Operator::WeakNext, Label::WeakNext),
+    (Operator::Globally, Label::Globally),
+    (Operator::Finally, Label::Finally),
+    (Operator::Yesterday, Label::Yesterday),
+    (Operator::Once, Label::Once),
+    (Operator::Historically, Label::Historically),
+    (Operator::And, Label::And),
+    (Operator::Or, Label::Or),
+    (Operator::Implies, Label::Implies),
+    (Operator::Until, Label::Until),
+    (Operator::Release, Label::Release),
+    (Operator::WeakUntil, Label::WeakUntil),
+    (Operator::Since, Label::Since),
+];
+
+type BoundedLabel = fn(Interval) -> Label;
+
+/// Bounded operators get a label for each of their intervals.
+const BOUNDED_OPERATORS: [(Operator, BoundedLabel); 3] = [
+    (Operator::BoundedGlobally, Label::BoundedGlobally),
+    (Operator::BoundedFinally, Label::BoundedFinally),
+    (Operator::BoundedUntil, Label::BoundedUntil),
+];
+
+/// Find a formula consistent with the given sample by solving SAT problems of increasing size.
+///
+/// The formula is searched as a DAG, where equal subformulae are shared, with nodes `0..size`,
+/// where the last node is the root and the children of a node come before it.
+/// Thus, the solution is minimal in the number of its distinct subformulae,
+/// rather than in its `size` (which counts repeated subformulae every time they appear).
+///
+/// Traces are added to the SAT problem only when needed:
+/// starting from no traces, the formula found for the problem is checked against the whole sample,
+/// and the first misclassified trace is added to the problem, until the formula is consistent,
+/// or the problem becomes unsatisfiable and a larger size is needed.
+/// The search is always single-threaded.
+///
+/// Only the allowed operators are used, but their costs are ignored, as every node counts as one.
+pub fn solve_sat<S: LabeledSample>(sample: &S, options: &SolveOptions) -> Outcome {
+    if !sample.is_solvable() {
+        return Outcome::Unsolvable;
+    }
+
+    let labels: Vec<Label> = sample
+        .vars()
+        .into_iter()
+        .map(Label::Atom)
+        .chain(
+            OPERATORS
+                .into_iter()
+                .filter(|(op, _)| options.operators.contains(*op))
+                .map(|(_, label)| label),
+        )
+        .chain(
+            BOUNDED_OPERATORS
+                .into_iter()
+                .filter(|(op, _)| options.operators.contains(*op))
+                .flat_map(|(_, label)| options.operators.intervals().map(label)),
+        )
+        .collect();
+    let traces = sample
+        .positives()
+        .iter()
+        .map(|trace| (trace, true))
+        .chain(sample.negatives().iter().map(|trace| (trace, false)))
+        .collect::<Vec<_>>();
+
+    let limits = Limits::new(options);
+    let stop = || limits.stopped();
+    for size in 1..=limits.max_size {
+        let mut encoding = Encoding::new(&labels, size);
+        let mut encoded = 0;
+        loop {
+            match encoding.solver.solve(&stop) {
+                Some(true) => {}
+                Some(false) => break,
+                None => return limits.interrupted(size),
+            }
+            let formula = encoding.decode();
+            match traces
+                .iter()
+                .find(|(trace, positive)| formula.eval(trace) != *positive)
+            {
+                Some((trace, positive)) => {
+                    encoding.encode_trace(trace, *positive);
+                    encoded += 1;
+                }
+                None => return Outcome::Solved(formula),
+            }
+        }
+        if options.log {
+            println!(
+                "No formula of size {} ({} traces encoded, {} variables, {} clauses)",
+                size,
+                encoded,
+                encoding.solver.num_vars(),
+                encoding.solver.num_clauses()
+            );
+        }
+    }
+
+    Outcome::Exhausted {
+        max_size: limits.max_size,
+    }
+}
+
+/// The propositional encoding of the existence of a formula DAG of given size consistent with some traces.
+struct Encoding<'a> {
+    solver: Solver,
+    labels: &'a [Label],
+    /// `label[node][k]` holds iff the node is labeled by `labels[k]`.
+    label: Vec<Vec<Var>>,
+    /// `left[node][child]` holds iff the left (or only) child of the node is `child`.
+    left: Vec<Vec<Var>>,
+    /// `right[node][child]` holds iff the right child of the node is `child`.
+    right: Vec<Vec<Var>>,
+}
+
+impl<'a> Encoding<'a> {
+    fn new(labels: &'a [Label], size: usize) -> Self {
+        let mut solver = Solver::new();
+        let label: Vec<Vec<Var>> = (0..size)
+            .map(|_| labels.iter().map(|_| solver.new_var()).collect())
+            .collect();
+        let left: Vec<Vec<Var>> = (0..size)
+            .map(|node| (0..node).map(|_| solver.new_var()).collect())
+            .collect();
+        let right: Vec<Vec<Var>> = (0..size)
+            .map(|node| (0..node).map(|_| solver.new_var()).collect())
+            .collect();
+
+        let mut encoding = Encoding {
+            solver,
+            labels,
+            label,
+            left,
+            right,
+        };
+        encoding.encode_structure();
+        encoding
+    }
+
+    fn exactly_one(&mut self, vars: &[Var]) {
+        let clause: Vec<Lit> = vars.iter().map(|&var| Lit::new(var, true)).collect();
+        self.solver.add_clause(&clause);
+        for (idx, &first) in vars.iter().enumerate() {
+            for &second in &vars[idx + 1..] {
+                self.solver
+                    .add_clause(&[Lit::new(first, false), Lit::new(second, false)]);
+            }
+        }
+    }
+
+    /// Every node has exactly one label and exactly one left and right child,
+    /// even if its label does not use them.
+    fn encode_structure(&mut self) {
+        for node in 0..self.label.len() {
+            self.exactly_one(&self.label[node].clone());
+            if node == 0 {
+                // The first node has no children, so it must be an atom or a constant.
+                for (k, label) in self.labels.iter().enumerate() {
+                    if !label.is_leaf() {
+                        self.solver.add_clause(&[Lit::new(self.label[0][k], false)]);
+                    }
+                }
+            } else {
+                self.exactly_one(&self.left[node].clone());
+                self.exactly_one(&self.right[node].clone());
+                // Break symmetries: children not used by the label are the first node,
+                // and children of commutative operators are ordered.
+                for (k, label) in self.labels.iter().enumerate() {
+                    let guard = Lit::new(self.label[node][k], false);
+                    match label {
+                        Label::Atom(_) | Label::True | Label::False => {
+                            self.solver
+                                .add_clause(&[guard, Lit::new(self.left[node][0], true)]);
+                            self.solver
+                                .add_clause(&[guard, Lit::new(self.right[node][0], true)]);
+                        }
+                        Label::Not
+                        | Label::Next
+                        | Label::WeakNext
+                        | Label::Globally
+                        | Label::Finally
+                        | Label::Yesterday
+                        | Label::Once
+                        | Label::Historically
+                        | Label::BoundedGlobally(_)
+                        | Label::BoundedFinally(_) => {
+                            self.solver
+                                .add_clause(&[guard, Lit::new(self.right[node][0], true)]);
+                        }
+                        Label::And | Label::Or => {
+                            for left in 0..node {
+                                for right in 0..=left {
+                                    self.solver.add_clause(&[
+                                        guard,
+                                        Lit::new(self.left[node][left], false),
+                                        Lit::new(self.right[node][right], false),
+                                    ]);
+                                }
+                            }
+                        }
+                        Label::Implies
+                        | Label::Until
+                        | Label::Release
+                        | Label::WeakUntil
+                        | Label::Since
+                        | Label::BoundedUntil(_) => {}
+                    }
+                }
+            }
+        }
+
+        // In a DAG of minimal size every node but the root is the child of some other node,
+        // and no two nodes are the same atom or constant.
+        // Note that the first node is the default child, so it is excluded from the first condition.
+        let size = self.label.len();
+        for child in 1..size.saturating_sub(1) {
+            let clause: Vec<Lit> = (child + 1..size)
+                .flat_map(|node| {
+                    [
+                        Lit::new(self.left[node][child], true),
+                        Lit::new(self.right[node][child], true),
+                    ]
+                })
+                .collect();
+            self.solver.add_clause(&clause);
+        }
+        for (k, label) in self.labels.iter().enumerate() {
+            if label.is_leaf() {
+                let vars: Vec<Var> = (0..size).map(|node| self.label[node][k]).collect();
+                for (idx, &first) in vars.iter().enumerate() {
+                    for &second in &vars[idx + 1..] {
+                        self.solver
+                            .add_clause(&[Lit::new(first, false), Lit::new(second, false)]);
+                    }
+                }
+            }
+        }
+    }
+
+    /// The root node holds on the trace iff the trace is positive.
+    fn encode_trace<T: AsRef<[bool]>>(&mut self, trace: &[T], positive: bool) {
+        let size = self.label.len();
+        let len = trace.len();
+        // `value[node][time]` holds iff the subformula at node holds at time.
+        let value: Vec<Vec<Var>> = (0..size)
+            .map(|_| (0..len).map(|_| self.solver.new_aux_var()).collect())
+            .collect();
+
+        for node in 0..size {
+            // Values of the left and right child of the node.
+            let mut left_value = Vec::new();
+            let mut right_value = Vec::new();
+            if node > 0 {
+                left_value = self.child_values(&self.left[node].clone(), &value, len);
+                right_value = self.child_values(&self.right[node].clone(), &value, len);
+            }
+
+            for (k, &label) in self.labels.iter().enumerate() {
+                let guard = Lit::new(self.label[node][k], false);
+                if node == 0 && !label.is_leaf() {
+                    continue;
+                }
+                for time in 0..len {
+                    let val = Lit::new(value[node][time], true);
+                    let next = (time + 1 < len).then(|| Lit::new(value[node][time + 1], true));
+                    let prev = (time > 0).then(|| Lit::new(value[node][time - 1], true));
+                    let add = |solver: &mut Solver, clauses: &[&[Lit]]| {
+                        for clause in clauses {
+                            let mut clause = clause.to_vec();
+                            clause.push(guard);
+                            solver.add_clause(&clause);
+                        }
+                    };
+                    match label {
+                        Label::Atom(var) => {
+                            let holds = trace[time].as_ref()[var as usize];
+                            add(&mut self.solver, &[&[if holds { val } else { !val }]]);
+                        }
+                        Label::True => add(&mut self.solver, &[&[val]]),
+                        Label::False => add(&mut self.solver, &[&[!val]]),
+                        Label::Not => {
+                            let left = left_value[time];
+                            add(&mut self.solver, &[&[!val, !left], &[val, left]]);
+                        }
+                        Label::Next => match time + 1 < len {
+                            true => {
+                                let left = left_value[time + 1];
+                                add(&mut self.solver, &[&[!val, left], &[val, !left]]);
+                            }
+                            false => add(&mut self.solver, &[&[!val]]),
+                        },
+                        Label::WeakNext => match time + 1 < len {
+                            true => {
+                                let left = left_value[time + 1];
+                                add(&mut self.solver, &[&[!val, left], &[val, !left]]);
+                            }
+                            false => add(&mut self.solver, &[&[val]]),
+                        },
+                        Label::Globally => {
+                            let left = left_value[time];
+                            match next {
+                                Some(next) => add(
+                                    &mut self.solver,
+                                    &[&[!val, left], &[!val, next], &[val, !left, !next]],
+                                ),
+                                None => add(&mut self.solver, &[&[!val, left], &[val, !left]]),
+                            }
+                        }
+                        Label::Finally => {
+                            let left = left_value[time];
+                            match next {
+                                Some(next) => add(
+                                    &mut self.solver,
+                                    &[&[!val, left, next], &[val, !left], &[val, !next]],
+                                ),
+                                None => add(&mut self.solver, &[&[!val, left], &[val, !left]]),
+                            }
+                        }
+                        Label::And => {
+                            let (left, right) = (left_value[time], right_value[time]);
+                            add(
+                                &mut self.solver,
+                                &[&[!val, left], &[!val, right], &[val, !left, !right]],
+                            );
+                        }
+                        Label::Or => {
+                            let (left, right) = (left_value[time], right_value[time]);
+                            add(
+                                &mut self.solver,
+                                &[&[!val, left, right], &[val, !left], &[val, !right]],
+                            );
+                        }
+                        Label::Implies => {
+                            let (left, right) = (left_value[time], right_value[time]);
+                            add(
+                                &mut self.solver,
+                                &[&[!val, !left, right], &[val, left], &[val, !right]],
+                            );
+                        }
+                        Label::Until => {
+                            let (left, right) = (left_value[time], right_value[time]);
+                            match next {
+                                Some(next) => add(
+                                    &mut self.solver,
+                                    &[
+                                        &[!val, right, left],
+                                        &[!val, right, next],
+                                        &[val, !right],
+                                        &[val, !left, !next],
+                                    ],
+                                ),
+                                None => add(&mut self.solver, &[&[!val, right], &[val, !right]]),
+                            }
+                        }
+                        Label::Release => {
+                            let (left, right) = (left_value[time], right_value[time]);
+                            match next {
+                                Some(next) => add(
+                                    &mut self.solver,
+                                    &[
+                                        &[!val, right],
+                                        &[!val, left, next],
+                                        &[val, !right, !left],
+                                        &[val, !right, !next],
+                                    ],
+                                ),
+                                None => add(&mut self.solver, &[&[!val, right], &[val, !right]]),
+                            }
+                        }
+                        Label::WeakUntil => {
+                            let (left, right) = (left_value[time], right_value[time]);
+                            match next {
+                                Some(next) => add(
+                                    &mut self.solver,
+                                    &[
+                                        &[!val, right, left],
+                                        &[!val, right, next],
+                                        &[val, !right],
+                                        &[val, !left, !next],
+                                    ],
+                                ),
+                                None => add(
+                                    &mut self.solver,
+                                    &[&[!val, right, left], &[val, !right], &[val, !left]],
+                                ),
+                            }
+                        }
+                        Label::Yesterday => match time > 0 {
+                            true => {
+                                let left = left_value[time - 1];
+                                add(&mut self.solver, &[&[!val, left], &[val, !left]]);
+                            }
+                            false => add(&mut self.solver, &[&[!val]]),
+                        },
+                        Label::Once => {
+                            let left = left_value[time];
+                            match prev {
+                                Some(prev) => add(
+                                    &mut self.solver,
+                                    &[&[!val, left, prev], &[val, !left], &[val, !prev]],
+                                ),
+                                None => add(&mut self.solver, &[&[!val, left], &[val, !left]]),
+                            }
+                        }
+                        Label::Historically => {
+                            let left = left_value[time];
+                            match prev {
+                                Some(prev) => add(
+                                    &mut self.solver,
+                                    &[&[!val, left], &[!val, prev], &[val, !left, !prev]],
+                                ),
+                                None => add(&mut self.solver, &[&[!val, left], &[val, !left]]),
+                            }
+                        }
+                        Label::Since => {
+                            let (left, right) = (left_value[time], right_value[time]);
+                            match prev {
+                                Some(prev) => add(
+                                    &mut self.solver,
+                                    &[
+                                        &[!val, right, left],
+                                        &[!val, right, prev],
+                                        &[val, !right],
+                                        &[val, !left, !prev],
+                                    ],
+                                ),
+                                None => add(&mut self.solver, &[&[!val, right], &[val, !right]]),
+                            }
+                        }
+                        Label::BoundedGlobally(interval) => {
+                            let window = &left_value[interval.window(time, len)];
+                            // The node holds iff all values in the window do.
+                            let mut all: Vec<Lit> = window.iter().map(|left| !*left).collect();
+                            all.push(val);
+                            add(&mut self.solver, &[&all]);
+                            for &left in window {
+                                add(&mut self.solver, &[&[!val, left]]);
+                            }
+                        }
+                        Label::BoundedFinally(interval) => {
+                            let window = &left_value[interval.window(time, len)];
+                            // The node holds iff some value in the window does.
+                            let mut any: Vec<Lit> = window.to_vec();
+                            any.push(!val);
+                            add(&mut self.solver, &[&any]);
+                            for &left in window {
+                                add(&mut self.solver, &[&[val, !left]]);
+                            }
+                        }
+                        Label::BoundedUntil(interval) => {
+                            // `reached[k]` holds iff the right child holds at the k-th time instant of the window,
+                            // and the left child holds at every time instant before it.
+                            let reached: Vec<Lit> = interval
+                                .window(time, len)
+                                .map(|end| {
+                                    let reached = Lit::new(self.solver.new_aux_var(), true);
+                                    let right = right_value[end];
+                                    let mut clause = vec![reached, !right];
+                                    self.solver.add_clause(&[!reached, right]);
+                                    for &left in &left_value[time..end] {
+                                        self.solver.add_clause(&[!reached, left]);
+                                        clause.push(!left);
+                                    }
+                                    self.solver.add_clause(&clause);
+                                    reached
+                                })
+                                .collect();
+                            let mut any = reached.clone();
+                            any.push(!val);
+                            add(&mut self.solver, &[&any]);
+                            for reached in reached {
+                                add(&mut self.solver, &[&[val, !reached]]);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        if let Some(&root) = value[size - 1].first() {
+            self.solver.add_clause(&[Lit::new(root, positive)]);
+        }
+    }
+
+    /// Introduces variables for the values of a child of a node,
+    /// equal to the values of the node selected as child.
+    fn child_values(&mut self, child: &[Var], value: &[Vec<Var>], len: usize) -> Vec<Lit> {
+        (0..len)
+            .map(|time| {
+                let child_value = Lit::new(self.solver.new_aux_var(), true);
+                for (node, &is_child) in child.iter().enumerate() {
+                    let is_child = Lit::new(is_child, false);
+                    let node_value = Lit::new(value[node][time], true);
+                    self.solver
+                        .add_clause(&[is_child, !child_value, node_value]);
+                    self.solver
+                        .add_clause(&[is_child, child_value, !node_value]);
+                }
+                child_value
+            })
+            .collect()
+    }
+
+    /// Reads the formula off a satisfying assignment.
+    fn decode(&self) -> SyntaxTree {
+        let selected = |vars: &[Var]| {
+            vars.iter()
+                .position(|&var| self.solver.model_value(var))
+                .expect("exactly one variable holds")
+        };
+        let mut nodes: Vec<Arc<SyntaxTree>> = Vec::with_capacity(self.label.len());
+        for node in 0..self.label.len() {
+            let label = self.labels[selected(&self.label[node])];
+            let child = |children: &[Vec<Var>]| nodes[selected(&children[node])].clone();
+            let formula = match label {
+                Label::Atom(var) => SyntaxTree::Atom(var),
+                Label::True => SyntaxTree::True,
+                Label::False => SyntaxTree::False,
+                Label::Not => SyntaxTree::Not(child(&self.left)),
+                Label::Next => SyntaxTree::Next(child(&self.left)),
+                Label::WeakNext => SyntaxTree::WeakNext(child(&self.left)),
+                Label::Globally => SyntaxTree::Globally(child(&self.left)),
+                Label::Finally => SyntaxTree::Finally(child(&self.left)),
+                Label::Yesterday => SyntaxTree::Yesterday(child(&self.left)),
+                Label::Once => SyntaxTree::Once(child(&self.left)),
+                Label::Historically => SyntaxTree::Historically(child(&self.left)),
+                Label::BoundedGlobally(interval) => {
+                    SyntaxTree::BoundedGlobally(interval, child(&self.left))
+                }
+                Label::BoundedFinally(interval) => {
+                    SyntaxTree::BoundedFinally(interval, child(&self.left))
+                }
+                Label::And => SyntaxTree::And(child(&self.left), child(&self.right)),
+                Label::Or => SyntaxTree::Or(child(&self.left), child(&self.right)),
+                Label::Implies => SyntaxTree::Implies(child(&self.left), child(&self.right)),
+                Label::Until => SyntaxTree::Until(child(&self.left), child(&self.right)),
+                Label::Release => SyntaxTree::Release(child(&self.left), child(&self.right)),
+                Label::WeakUntil => SyntaxTree::WeakUntil(child(&self.left), child(&self.right)),
+                Label::Since => SyntaxTree::Since(child(&self.left), child(&self.right)),
+                Label::BoundedUntil(interval) => {
+                    SyntaxTree::BoundedUntil(interval, child(&self.left), child(&self.right))
+                }
+            };
+            nodes.push(Arc::new(formula));
+        }
+        nodes.pop().expect("non-empty formula").as_ref().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solve;
+
+    use std::collections::BTreeSet;
+
+    fn subformulae(formula: &SyntaxTree, set: &mut BTreeSet<SyntaxTree>) {
+        if set.insert(formula.clone()) {
+            match formula {
+                SyntaxTree::Atom(_) | SyntaxTree::True | SyntaxTree::False => {}
+                SyntaxTree::Not(branch)
+                | SyntaxTree::Next(branch)
+                | SyntaxTree::WeakNext(branch)
+                | SyntaxTree::Globally(branch)
+                | SyntaxTree::Finally(branch)
+                | SyntaxTree::Yesterday(branch)
+                | SyntaxTree::Once(branch)
+                | SyntaxTree::Historically(branch)
+                | SyntaxTree::BoundedGlobally(_, branch)
+                | SyntaxTree::BoundedFinally(_, branch) => subformulae(branch, set),
+                SyntaxTree::And(left_branch, right_branch)
+                | SyntaxTree::Or(left_branch, right_branch)
+                | SyntaxTree::Implies(left_branch, right_branch)
+                | SyntaxTree::Until(left_branch, right_branch)
+                | SyntaxTree::Release(left_branch, right_branch)
+                | SyntaxTree::WeakUntil(left_branch, right_branch)
+                | SyntaxTree::Since(left_branch, right_branch)
+                | SyntaxTree::BoundedUntil(_, left_branch, right_branch) => {
+                    subformulae(left_branch, set);
+                    subformulae(right_branch, set);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn sat_comparison() {
+        for path in [
+            "SATcomparison/abscence/0000.ron",
+            "SATcomparison/abscence/0003.ron",
+            "SATcomparison/universality/0000.ron",
+            "SATcomparison/universality/0004.ron",
+        ] {
+            let sample = load_sample(path);
+            let expected = solve(&sample, &SolveOptions::default())
+                .solution()
+                .expect("solution");
+            let solution = solve_sat(&sample, &SolveOptions::default())
+                .solution()
+                .expect("solution");
+            assert!(sample.is_consistent(&solution), "{path}: {solution}");
+            let mut set = BTreeSet::new();
+            subformulae(&solution, &mut set);
+            assert!(set.len() <= expected.size(), "{path}: {solution}");
+        }
+    }
+
+    #[test]
+    fn weak_operators() {
+        use crate::{solve_bottom_up, Operators};
+        use rand::{rngs::StdRng, Rng, SeedableRng};
+
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..10 {
+            let mut sample = DynSample::new(2);
+            while sample.positive_traces() + sample.negative_traces() < 6 {
+                let trace = (0..4)
+                    .map(|_| (0..2).map(|_| rng.gen()).collect())
+                    .collect();
+                let _ = if rng.gen() {
+                    sample.add_positive_trace(trace)
+                } else {
+                    sample.add_negative_trace(trace)
+                };
+            }
+            let options = SolveOptions {
+                max_size: Some(6),
+                operators: Operators::all().with_horizon(2),
+                ..Default::default()
+            };
+            if let Some(expected) = solve_bottom_up(&sample, &options).solution() {
+                let solution = solve_sat(&sample, &options).solution().expect("solution");
+                assert!(sample.is_consistent(&solution), "{solution}");
+                let mut set = BTreeSet::new();
+                subformulae(&solution, &mut set);
+                assert!(set.len() <= expected.size(), "{solution}");
+            }
+        }
+    }
+
+    #[test]
+    fn syslite() {
+        let sample = load_sample("SySLite-comparison/bank_transaction_1250.ron");
+        let solution = solve_sat(&sample, &SolveOptions::default())
+            .solution()
+            .expect("solution");
+        assert!(sample.is_consistent(&solution), "{solution}");
+    }
+
+    #[test]
+    fn unsolvable() {
+        let sample = DynSample {
+            var_names: vec!["x0".to_string()],
+            positive_traces: vec![vec![vec![true], vec![false]]],
+            negative_traces: vec![vec![vec![true], vec![false]]],
+        };
+        assert_eq!(
+            solve_sat(&sample, &SolveOptions::default()),
+            Outcome::Unsolvable
+        );
+    }
+}
@@ -0,0 +1,619 @@
+//! A small CDCL SAT solver, with two watched literals, first-UIP clause learning,
+//! recursive clause minimization, VSIDS branching heuristic, phase saving, Luby restarts
+//! and deletion of learnt clauses by literal block distance.
+
+use std::ops::Not;
+
+/// A propositional variable of a `Solver`.
+pub(crate) type Var = u32;
+
+/// A propositional variable or its negation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct Lit(u32);
+
+impl Lit {
+    pub(crate) fn new(var: Var, positive: bool) -> Self {
+        Lit(2 * var + if positive { 0 } else { 1 })
+    }
+
+    pub(crate) fn var(self) -> Var {
+        self.0 / 2
+    }
+
+    pub(crate) fn is_positive(self) -> bool {
+        self.0.is_multiple_of(2)
+    }
+
+    fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl Not for Lit {
+    type Output = Lit;
+
+    fn not(self) -> Lit {
+        Lit(self.0 ^ 1)
+    }
+}
+
+/// Index of a clause in the clause database.
+type ClauseRef = usize;
+
+const VAR_DECAY: f64 = 0.95;
+const RESTART_BASE: u64 = 100;
+/// Number of conflicts between checks of whether to stop.
+const STOP_CHECK: u64 = 64;
+/// Number of learnt clauses kept before the first reduction of the clause database,
+/// and its increment at every reduction.
+const LEARNTS_BASE: usize = 2000;
+const LEARNTS_INC: usize = 300;
+
+#[derive(Debug, Default)]
+pub(crate) struct Solver {
+    /// Problem and learnt clauses; deleted clauses are left empty.
+    clauses: Vec<Vec<Lit>>,
+    /// Learnt clauses with their literal block distance.
+    learnts: Vec<(ClauseRef, usize)>,
+    max_learnts: usize,
+    /// Clauses watching each literal, to be visited when the literal becomes false.
+    watches: Vec<Vec<ClauseRef>>,
+    assigns: Vec<Option<bool>>,
+    level: Vec<usize>,
+    reason: Vec<Option<ClauseRef>>,
+    trail: Vec<Lit>,
+    trail_lim: Vec<usize>,
+    propagated: usize,
+    activity: Vec<f64>,
+    var_inc: f64,
+    order: VarHeap,
+    polarity: Vec<bool>,
+    /// Whether the variable is branched on.
+    decision: Vec<bool>,
+    seen: Vec<bool>,
+    unsat: bool,
+}
+
+impl Solver {
+    pub(crate) fn new() -> Self {
+        Solver {
+            var_inc: 1.0,
+            max_learnts: LEARNTS_BASE,
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn new_var(&mut self) -> Var {
+        let var = self.new_aux_var();
+        self.decision[var as usize] = true;
+        self.order.insert(var, &self.activity);
+        var
+    }
+
+    /// Creates a variable that is not branched on, unless no other choice is left,
+    /// meant for variables whose value is implied by the other ones.
+    pub(crate) fn new_aux_var(&mut self) -> Var {
+        let var = self.assigns.len() as Var;
+        self.watches.push(Vec::new());
+        self.watches.push(Vec::new());
+        self.assigns.push(None);
+        self.level.push(0);
+        self.reason.push(None);
+        self.activity.push(0.0);
+        self.polarity.push(false);
+        self.seen.push(false);
+        self.decision.push(false);
+        var
+    }
+
+    pub(crate) fn num_vars(&self) -> usize {
+        self.assigns.len()
+    }
+
+    pub(crate) fn num_clauses(&self) -> usize {
+        self.clauses.len()
+    }
+
+    /// Adds a clause to the problem.
+    /// Clauses can be added after solving too, to solve the extended problem.
+    pub(crate) fn add_clause(&mut self, lits: &[Lit]) {
+        self.backtrack(0);
+        if self.unsat {
+            return;
+        }
+        let mut clause = Vec::with_capacity(lits.len());
+        for &lit in lits {
+            match self.value(lit) {
+                Some(true) => return,
+                Some(false) => {}
+                None if clause.contains(&!lit) => return,
+                None if !clause.contains(&lit) => clause.push(lit),
+                None => {}
+            }
+        }
+        match clause.len() {
+            0 => self.unsat = true,
+            1 => self.enqueue(clause[0], None),
+            _ => {
+                self.attach(clause);
+            }
+        }
+    }
+
+    /// Solves the problem, returning whether it is satisfiable,
+    /// or `None` if `stop` returned true, which is checked periodically.
+    /// If satisfiable, the satisfying assignment can be read with `model_value`.
+    pub(crate) fn solve(&mut self, stop: &dyn Fn() -> bool) -> Option<bool> {
+        if self.unsat {
+            return Some(false);
+        }
+        let mut restarts = 0;
+        loop {
+            match self.search(RESTART_BASE * luby(restarts), stop) {
+                Some(sat) => {
+                    self.unsat = !sat;
+                    return Some(sat);
+                }
+                None if stop() => return None,
+                None => restarts += 1,
+            }
+        }
+    }
+
+    /// Value of a variable in the satisfying assignment found by the last call of `solve`,
+    /// valid until clauses are added.
+    pub(crate) fn model_value(&self, var: Var) -> bool {
+        self.assigns[var as usize].unwrap_or(false)
+    }
+
+    fn value(&self, lit: Lit) -> Option<bool> {
+        self.assigns[lit.var() as usize].map(|value| value == lit.is_positive())
+    }
+
+    fn decision_level(&self) -> usize {
+        self.trail_lim.len()
+    }
+
+    fn attach(&mut self, clause: Vec<Lit>) -> ClauseRef {
+        let cref = self.clauses.len();
+        self.watches[clause[0].index()].push(cref);
+        self.watches[clause[1].index()].push(cref);
+        self.clauses.push(clause);
+        cref
+    }
+
+    fn enqueue(&mut self, lit: Lit, reason: Option<ClauseRef>) {
+        let var = lit.var() as usize;
+        self.assigns[var] = Some(lit.is_positive());
+        self.level[var] = self.decision_level();
+        self.reason[var] = reason;
+        self.trail.push(lit);
+    }
+
+    /// Propagates all enqueued assignments, returning a conflicting clause, if any.
+    fn propagate(&mut self) -> Option<ClauseRef> {
+        while self.propagated < self.trail.len() {
+            let false_lit = !self.trail[self.propagated];
+            self.propagated += 1;
+            let mut watchers = std::mem::take(&mut self.watches[false_lit.index()]);
+            let mut kept = 0;
+            let mut idx = 0;
+            let mut conflict = None;
+            while idx < watchers.len() {
+                let cref = watchers[idx];
+                idx += 1;
+                let clause = &mut self.clauses[cref];
+                if clause.is_empty() {
+                    continue;
+                }
+                if clause[0] == false_lit {
+                    clause.swap(0, 1);
+                }
+                let first = clause[0];
+                if self.assigns[first.var() as usize] == Some(first.is_positive()) {
+                    watchers[kept] = cref;
+                    kept += 1;
+                    continue;
+                }
+                let new_watch = (2..clause.len()).find(|&k| {
+                    let lit = clause[k];
+                    self.assigns[lit.var() as usize] != Some(!lit.is_positive())
+                });
+                if let Some(k) = new_watch {
+                    clause.swap(1, k);
+                    self.watches[clause[1].index()].push(cref);
+                    continue;
+                }
+                watchers[kept] = cref;
+                kept += 1;
+                if self.value(first) == Some(false) {
+                    conflict = Some(cref);
+                    while idx < watchers.len() {
+                        watchers[kept] = watchers[idx];
+                        kept += 1;
+                        idx += 1;
+                    }
+                } else {
+                    self.enqueue(first, Some(cref));
+                }
+            }
+            watchers.truncate(kept);
+            self.watches[false_lit.index()] = watchers;
+            if conflict.is_some() {
+                return conflict;
+            }
+        }
+        None
+    }
+
+    /// Derives the first-UIP clause from a conflict, with the asserting literal first
+    /// and a literal of the backjump level second.
+    fn analyze(&mut self, conflict: ClauseRef) -> (Vec<Lit>, usize) {
+        let mut learnt = vec![Lit(0)];
+        let mut pending = 0;
+        let mut cref = conflict;
+        let mut implied = None;
+        let mut idx = self.trail.len();
+        loop {
+            let start = if implied.is_some() { 1 } else { 0 };
+            for k in start..self.clauses[cref].len() {
+                let lit = self.clauses[cref][k];
+                let var = lit.var() as usize;
+                if !self.seen[var] && self.level[var] > 0 {
+                    self.seen[var] = true;
+                    self.bump(lit.var());
+                    if self.level[var] >= self.decision_level() {
+                        pending += 1;
+                    } else {
+                        learnt.push(lit);
+                    }
+                }
+            }
+            loop {
+                idx -= 1;
+                if self.seen[self.trail[idx].var() as usize] {
+                    break;
+                }
+            }
+            let lit = self.trail[idx];
+            self.seen[lit.var() as usize] = false;
+            implied = Some(lit);
+            pending -= 1;
+            if pending == 0 {
+                break;
+            }
+            cref = self.reason[lit.var() as usize].expect("implied literal");
+        }
+        learnt[0] = !implied.expect("conflict at decision level");
+
+        // Remove literals implied by the other ones.
+        let mut to_clear: Vec<usize> = learnt[1..].iter().map(|lit| lit.var() as usize).collect();
+        let mut kept = 1;
+        for k in 1..learnt.len() {
+            let lit = learnt[k];
+            if self.reason[lit.var() as usize].is_none() || !self.is_redundant(lit, &mut to_clear) {
+                learnt[kept] = lit;
+                kept += 1;
+            }
+        }
+        learnt.truncate(kept);
+        for var in to_clear {
+            self.seen[var] = false;
+        }
+
+        let mut backjump = 0;
+        if learnt.len() > 1 {
+            let max = (1..learnt.len())
+                .max_by_key(|&k| self.level[learnt[k].var() as usize])
+                .expect("non-empty");
+            learnt.swap(1, max);
+            backjump = self.level[learnt[1].var() as usize];
+        }
+        (learnt, backjump)
+    }
+
+    /// Checks whether a literal of a learnt clause is implied by the other literals,
+    /// which are marked as seen, as are the literals found to be implied.
+    fn is_redundant(&mut self, lit: Lit, to_clear: &mut Vec<usize>) -> bool {
+        let top = to_clear.len();
+        let mut stack = vec![lit];
+        while let Some(lit) = stack.pop() {
+            let cref = self.reason[lit.var() as usize].expect("implied literal");
+            for k in 1..self.clauses[cref].len() {
+                let reason_lit = self.clauses[cref][k];
+                let var = reason_lit.var() as usize;
+                if !self.seen[var] && self.level[var] > 0 {
+                    if self.reason[var].is_some() {
+                        self.seen[var] = true;
+                        stack.push(reason_lit);
+                        to_clear.push(var);
+                    } else {
+                        for var in to_clear.drain(top..) {
+                            self.seen[var] = false;
+                        }
+                        return false;
+                    }
+                }
+            }
+        }
+        true
+    }
+
+    /// Number of distinct decision levels in a clause.
+    fn lbd(&self, clause: &[Lit]) -> usize {
+        let mut levels: Vec<usize> = clause
+            .iter()
+            .map(|lit| self.level[lit.var() as usize])
+            .collect();
+        levels.sort_unstable();
+        levels.dedup();
+        levels.len()
+    }
+
+    /// Deletes the half of learnt clauses with the highest literal block distance,
+    /// except for those with distance at most 2 and those that are the reason of an assignment.
+    fn reduce_learnts(&mut self) {
+        self.learnts.sort_by_key(|&(_, lbd)| lbd);
+        let mut kept = self.learnts.len() / 2;
+        for idx in self.learnts.len() / 2..self.learnts.len() {
+            let (cref, lbd) = self.learnts[idx];
+            let first = self.clauses[cref][0].var() as usize;
+            let locked = self.reason[first] == Some(cref);
+            if lbd <= 2 || locked {
+                self.learnts[kept] = (cref, lbd);
+                kept += 1;
+            } else {
+                self.clauses[cref] = Vec::new();
+            }
+        }
+        self.learnts.truncate(kept);
+        self.max_learnts += LEARNTS_INC;
+    }
+
+    fn backtrack(&mut self, level: usize) {
+        if self.decision_level() > level {
+            for lit in self.trail.drain(self.trail_lim[level]..).rev() {
+                let var = lit.var() as usize;
+                self.assigns[var] = None;
+                self.reason[var] = None;
+                self.polarity[var] = lit.is_positive();
+                if self.decision[var] {
+                    self.order.insert(lit.var(), &self.activity);
+                }
+            }
+            self.trail_lim.truncate(level);
+            self.propagated = self.trail.len();
+        }
+    }
+
+    fn bump(&mut self, var: Var) {
+        self.activity[var as usize] += self.var_inc;
+        if self.activity[var as usize] > 1e100 {
+            for activity in &mut self.activity {
+                *activity *= 1e-100;
+            }
+            self.var_inc *= 1e-100;
+        }
+        self.order.increased(var, &self.activity);
+    }
+
+    /// Searches for a satisfying assignment until the given number of conflicts is reached,
+    /// or until `stop` returns true.
+    fn search(&mut self, max_conflicts: u64, stop: &dyn Fn() -> bool) -> Option<bool> {
+        let mut conflicts = 0;
+        loop {
+            if let Some(conflict) = self.propagate() {
+                if self.decision_level() == 0 {
+                    return Some(false);
+                }
+                conflicts += 1;
+                let (learnt, backjump) = self.analyze(conflict);
+                let lbd = self.lbd(&learnt);
+                self.backtrack(backjump);
+                let asserting = learnt[0];
+                if learnt.len() == 1 {
+                    self.enqueue(asserting, None);
+                } else {
+                    let cref = self.attach(learnt);
+                    self.learnts.push((cref, lbd));
+                    self.enqueue(asserting, Some(cref));
+                }
+                self.var_inc /= VAR_DECAY;
+                if self.learnts.len() >= self.max_learnts {
+                    self.reduce_learnts();
+                }
+            } else if conflicts >= max_conflicts || (conflicts % STOP_CHECK == 0 && stop()) {
+                self.backtrack(0);
+                return None;
+            } else {
+                let next = loop {
+                    match self.order.pop(&self.activity) {
+                        Some(var) if self.assigns[var as usize].is_some() => continue,
+                        Some(var) => break Some(var),
+                        None => {
+                            break (0..self.num_vars() as Var)
+                                .find(|&var| self.assigns[var as usize].is_none())
+                        }
+                    }
+                };
+                match next {
+                    Some(var) => {
+                        self.trail_lim.push(self.trail.len());
+                        self.enqueue(Lit::new(var, self.polarity[var as usize]), None);
+                    }
+                    None => return Some(true),
+                }
+            }
+        }
+    }
+}
+
+/// The Luby sequence 1, 1, 2, 1, 1, 2, 4, 1, ...
+fn luby(mut idx: u64) -> u64 {
+    let mut size = 1;
+    let mut seq = 0;
+    while size < idx + 1 {
+        seq += 1;
+        size = 2 * size + 1;
+    }
+    while size - 1 != idx {
+        size = (size - 1) / 2;
+        seq -= 1;
+        idx %= size;
+    }
+    1 << seq
+}
+
+/// A max-heap of variables ordered by activity.
+#[derive(Debug, Default)]
+struct VarHeap {
+    heap: Vec<Var>,
+    positions: Vec<Option<usize>>,
+}
+
+impl VarHeap {
+    fn insert(&mut self, var: Var, activity: &[f64]) {
+        if self.positions.len() <= var as usize {
+            self.positions.resize(var as usize + 1, None);
+        }
+        if self.positions[var as usize].is_none() {
+            self.positions[var as usize] = Some(self.heap.len());
+            self.heap.push(var);
+            self.sift_up(self.heap.len() - 1, activity);
+        }
+    }
+
+    fn increased(&mut self, var: Var, activity: &[f64]) {
+        if let Some(&Some(pos)) = self.positions.get(var as usize) {
+            self.sift_up(pos, activity);
+        }
+    }
+
+    fn pop(&mut self, activity: &[f64]) -> Option<Var> {
+        let top = *self.heap.first()?;
+        let last = self.heap.pop().expect("non-empty");
+        self.positions[top as usize] = None;
+        if !self.heap.is_empty() {
+            self.heap[0] = last;
+            self.positions[last as usize] = Some(0);
+            self.sift_down(0, activity);
+        }
+        Some(top)
+    }
+
+    fn swap(&mut self, i: usize, j: usize) {
+        self.heap.swap(i, j);
+        self.positions[self.heap[i] as usize] = Some(i);
+        self.positions[self.heap[j] as usize] = Some(j);
+    }
+
+    fn sift_up(&mut self, mut pos: usize, activity: &[f64]) {
+        while pos > 0 {
+            let parent = (pos - 1) / 2;
+            if activity[self.heap[pos] as usize] <= activity[self.heap[parent] as usize] {
+                break;
+            }
+            self.swap(pos, parent);
+            pos = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut pos: usize, activity: &[f64]) {
+        loop {
+            let mut largest = pos;
+            for child in [2 * pos + 1, 2 * pos + 2] {
+                if child < self.heap.len()
+                    && activity[self.heap[child] as usize] > activity[self.heap[largest] as usize]
+                {
+                    largest = child;
+                }
+            }
+            if largest == pos {
+                break;
+            }
+            self.swap(pos, largest);
+            pos = largest;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    fn satisfies(clauses: &[Vec<Lit>], assignment: impl Fn(Var) -> bool) -> bool {
+        clauses.iter().all(|clause| {
+            clause
+                .iter()
+                .any(|lit| assignment(lit.var()) == lit.is_positive())
+        })
+    }
+
+    #[test]
+    fn luby_sequence() {
+        let seq: Vec<u64> = (0..15).map(luby).collect();
+        assert_eq!(seq, [1, 1, 2, 1, 1, 2, 4, 1, 1, 2, 1, 1, 2, 4, 8]);
+    }
+
+    #[test]
+    fn pigeonhole() {
+        // 4 pigeons do not fit in 3 holes.
+        const PIGEONS: u32 = 4;
+        const HOLES: u32 = 3;
+        let mut solver = Solver::new();
+        for _ in 0..PIGEONS * HOLES {
+            solver.new_var();
+        }
+        let var = |pigeon: u32, hole: u32| pigeon * HOLES + hole;
+        for pigeon in 0..PIGEONS {
+            let clause: Vec<Lit> = (0..HOLES)
+                .map(|hole| Lit::new(var(pigeon, hole), true))
+                .collect();
+            solver.add_clause(&clause);
+        }
+        for hole in 0..HOLES {
+            for first in 0..PIGEONS {
+                for second in first + 1..PIGEONS {
+                    solver.add_clause(&[
+                        Lit::new(var(first, hole), false),
+                        Lit::new(var(second, hole), false),
+                    ]);
+                }
+            }
+        }
+        assert_eq!(solver.solve(&|| false), Some(false));
+    }
+
+    #[test]
+    fn random_3sat() {
+        const VARS: u32 = 12;
+        let mut rng = StdRng::seed_from_u64(0);
+        for clauses_num in [20, 40, 50, 60, 80] {
+            for _ in 0..20 {
+                let clauses: Vec<Vec<Lit>> = (0..clauses_num)
+                    .map(|_| {
+                        (0..3)
+                            .map(|_| Lit::new(rng.gen_range(0..VARS), rng.gen()))
+                            .collect()
+                    })
+                    .collect();
+                let mut solver = Solver::new();
+                for _ in 0..VARS {
+                    solver.new_var();
+                }
+                for clause in &clauses {
+                    solver.add_clause(clause);
+                }
+                let expected =
+                    (0..1u32 << VARS).any(|bits| satisfies(&clauses, |var| bits & (1 << var) != 0));
+                assert_eq!(solver.solve(&|| false), Some(expected));
+                if expected {
+                    assert!(satisfies(&clauses, |var| solver.model_value(var)));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,1098 @@
+use crate::syntax::*;
+use std::sync::Arc;
+
+impl SyntaxTree {
+    /// Simplifies the formula by rewriting it with sound LTLf equivalences
+    /// (the same ones the enumeration of formulae uses to skip redundant candidates, see `learn.rs`),
+    /// until a fixpoint is reached.
+    ///
+    /// The result is equivalent to the formula at every time instant of every trace,
+    /// it is never larger than the formula, and it is in canonical order (see [`SyntaxTree::to_canonical`]).
+    pub fn simplify(&self) -> SyntaxTree {
+        // Every rewriting either makes the formula smaller,
+        // or keeps its size while pushing a negation (or an implication) deeper into it,
+        // so a fixpoint is eventually reached.
+        let mut formula = self.to_canonical();
+        loop {
+            let simplified = formula.simplify_step().to_canonical();
+            if simplified == formula {
+                return formula;
+            }
+            formula = simplified;
+        }
+    }
+
+    /// Rewrites the formula bottom-up, applying at most one rule to each subformula.
+    fn simplify_step(&self) -> SyntaxTree {
+        rewrite(self.map_children(SyntaxTree::simplify_step))
+    }
+
+    /// Converts the formula into negation normal form:
+    /// implications are eliminated and negations are pushed down to the atoms,
+    /// using the duality of the temporal operators.
+    ///
+    /// Yesterday and bounded until have no dual in the syntax (weak yesterday and bounded release, respectively),
+    /// so negations in front of them are kept.
+    /// Converting to negation normal form can make the formula larger.
+    pub fn to_nnf(&self) -> SyntaxTree {
+        self.nnf(false)
+    }
+
+    fn nnf(&self, negated: bool) -> SyntaxTree {
+        let pos = |branch: &Arc<SyntaxTree>| Arc::new(branch.nnf(false));
+        let neg = |branch: &Arc<SyntaxTree>| Arc::new(branch.nnf(true));
+        match (self, negated) {
+            (SyntaxTree::Atom(_), false) => self.clone(),
+            (SyntaxTree::Atom(_), true) => SyntaxTree::Not(Arc::new(self.clone())),
+            (SyntaxTree::True, false) | (SyntaxTree::False, true) => SyntaxTree::True,
+            (SyntaxTree::True, true) | (SyntaxTree::False, false) => SyntaxTree::False,
+            (SyntaxTree::Not(branch), _) => branch.nnf(!negated),
+            (SyntaxTree::Next(branch), false) => SyntaxTree::Next(pos(branch)),
+            // ¬ X φ ≡ N ¬ φ
+            (SyntaxTree::Next(branch), true) => SyntaxTree::WeakNext(neg(branch)),
+            (SyntaxTree::WeakNext(branch), false) => SyntaxTree::WeakNext(pos(branch)),
+            // ¬ N φ ≡ X ¬ φ
+            (SyntaxTree::WeakNext(branch), true) => SyntaxTree::Next(neg(branch)),
+            (SyntaxTree::Globally(branch), false) => SyntaxTree::Globally(pos(branch)),
+            // ¬ G φ ≡ F ¬ φ
+            (SyntaxTree::Globally(branch), true) => SyntaxTree::Finally(neg(branch)),
+            (SyntaxTree::Finally(branch), false) => SyntaxTree::Finally(pos(branch)),
+            // ¬ F φ ≡ G ¬ φ
+            (SyntaxTree::Finally(branch), true) => SyntaxTree::Globally(neg(branch)),
+            (SyntaxTree::Yesterday(branch), false) => SyntaxTree::Yesterday(pos(branch)),
+            (SyntaxTree::Yesterday(branch), true) => {
+                SyntaxTree::Not(Arc::new(SyntaxTree::Yesterday(pos(branch))))
+            }
+            (SyntaxTree::Once(branch), false) => SyntaxTree::Once(pos(branch)),
+            // ¬ O φ ≡ H ¬ φ
+            (SyntaxTree::Once(branch), true) => SyntaxTree::Historically(neg(branch)),
+            (SyntaxTree::Historically(branch), false) => SyntaxTree::Historically(pos(branch)),
+            // ¬ H φ ≡ O ¬ φ
+            (SyntaxTree::Historically(branch), true) => SyntaxTree::Once(neg(branch)),
+            (SyntaxTree::BoundedGlobally(interval, branch), false) => {
+                SyntaxTree::BoundedGlobally(*interval, pos(branch))
+            }
+            // ¬ G[a,b] φ ≡ F[a,b] ¬ φ
+            (SyntaxTree::BoundedGlobally(interval, branch), true) => {
+                SyntaxTree::BoundedFinally(*interval, neg(branch))
+            }
+            (SyntaxTree::BoundedFinally(interval, branch), false) => {
+                SyntaxTree::BoundedFinally(*interval, pos(branch))
+            }
+            // ¬ F[a,b] φ ≡ G[a,b] ¬ φ
+            (SyntaxTree::BoundedFinally(interval, branch), true) => {
+                SyntaxTree::BoundedGlobally(*interval, neg(branch))
+            }
+            (SyntaxTree::And(left_branch, right_branch), false) => {
+                SyntaxTree::And(pos(left_branch), pos(right_branch))
+            }
+            // ¬(φ ∧ ψ) ≡ ¬φ ∨ ¬ψ
+            (SyntaxTree::And(left_branch, right_branch), true) => {
+                SyntaxTree::Or(neg(left_branch), neg(right_branch))
+            }
+            (SyntaxTree::Or(left_branch, right_branch), false) => {
+                SyntaxTree::Or(pos(left_branch), pos(right_branch))
+            }
+            // ¬(φ ∨ ψ) ≡ ¬φ ∧ ¬ψ
+            (SyntaxTree::Or(left_branch, right_branch), true) => {
+                SyntaxTree::And(neg(left_branch), neg(right_branch))
+            }
+            // φ -> ψ ≡ ¬φ ∨ ψ
+            (SyntaxTree::Implies(left_branch, right_branch), false) => {
+                SyntaxTree::Or(neg(left_branch), pos(right_branch))
+            }
+            // ¬(φ -> ψ) ≡ φ ∧ ¬ψ
+            (SyntaxTree::Implies(left_branch, right_branch), true) => {
+                SyntaxTree::And(pos(left_branch), neg(right_branch))
+            }
+            (SyntaxTree::Until(left_branch, right_branch), false) => {
+                SyntaxTree::Until(pos(left_branch), pos(right_branch))
+            }
+            // ¬(φ U ψ) ≡ ¬φ R ¬ψ
+            (SyntaxTree::Until(left_branch, right_branch), true) => {
+                SyntaxTree::Release(neg(left_branch), neg(right_branch))
+            }
+            (SyntaxTree::Release(left_branch, right_branch), false) => {
+                SyntaxTree::Release(pos(left_branch), pos(right_branch))
+            }
+            // ¬(φ R ψ) ≡ ¬φ U ¬ψ
+            (SyntaxTree::Release(left_branch, right_branch), true) => {
+                SyntaxTree::Until(neg(left_branch), neg(right_branch))
+            }
+            (SyntaxTree::WeakUntil(left_branch, right_branch), false) => {
+                SyntaxTree::WeakUntil(pos(left_branch), pos(right_branch))
+            }
+            // ¬(φ W ψ) ≡ ¬ψ U (¬φ ∧ ¬ψ)
+            (SyntaxTree::WeakUntil(left_branch, right_branch), true) => {
+                let right_branch = neg(right_branch);
+                SyntaxTree::Until(
+                    right_branch.clone(),
+                    Arc::new(SyntaxTree::And(neg(left_branch), right_branch)),
+                )
+            }
+            (SyntaxTree::Since(left_branch, right_branch), false) => {
+                SyntaxTree::Since(pos(left_branch), pos(right_branch))
+            }
+            // ¬(φ S ψ) ≡ H ¬ψ ∨ (¬ψ S (¬φ ∧ ¬ψ))
+            (SyntaxTree::Since(left_branch, right_branch), true) => {
+                let right_branch = neg(right_branch);
+                SyntaxTree::Or(
+                    Arc::new(SyntaxTree::Historically(right_branch.clone())),
+                    Arc::new(SyntaxTree::Since(
+                        right_branch.clone(),
+                        Arc::new(SyntaxTree::And(neg(left_branch), right_branch)),
+                    )),
+                )
+            }
+            (SyntaxTree::BoundedUntil(interval, left_branch, right_branch), false) => {
+                SyntaxTree::BoundedUntil(*interval, pos(left_branch), pos(right_branch))
+            }
+            (SyntaxTree::BoundedUntil(interval, left_branch, right_branch), true) => {
+                SyntaxTree::Not(Arc::new(SyntaxTree::BoundedUntil(
+                    *interval,
+                    pos(left_branch),
+                    pos(right_branch),
+                )))
+            }
+        }
+    }
+
+    /// Puts the formula in canonical order:
+    /// nested conjunctions (and disjunctions) are flattened, their operands sorted, and nested again to the right.
+    /// Formulae that only differ by commutativity and associativity of ∧ and ∨ have the same canonical order.
+    pub fn to_canonical(&self) -> SyntaxTree {
+        let formula = self.map_children(SyntaxTree::to_canonical);
+        match &formula {
+            SyntaxTree::And(..) | SyntaxTree::Or(..) => {
+                let mut operands = Vec::new();
+                flatten(&formula, &mut operands);
+                operands.sort();
+                rebuild(&formula, operands)
+            }
+            _ => formula,
+        }
+    }
+
+    /// Rebuilds the root of the formula, replacing its branches with the image of the given function.
+    fn map_children(&self, f: fn(&SyntaxTree) -> SyntaxTree) -> SyntaxTree {
+        let map = |branch: &Arc<SyntaxTree>| Arc::new(f(branch));
+        match self {
+            SyntaxTree::Atom(_) | SyntaxTree::True | SyntaxTree::False => self.clone(),
+            SyntaxTree::Not(branch) => SyntaxTree::Not(map(branch)),
+            SyntaxTree::Next(branch) => SyntaxTree::Next(map(branch)),
+            SyntaxTree::WeakNext(branch) => SyntaxTree::WeakNext(map(branch)),
+            SyntaxTree::Globally(branch) => SyntaxTree::Globally(map(branch)),
+            SyntaxTree::Finally(branch) => SyntaxTree::Finally(map(branch)),
+            SyntaxTree::Yesterday(branch) => SyntaxTree::Yesterday(map(branch)),
+            SyntaxTree::Once(branch) => SyntaxTree::Once(map(branch)),
+            SyntaxTree::Historically(branch) => SyntaxTree::Historically(map(branch)),
+            SyntaxTree::BoundedGlobally(interval, branch) => {
+                SyntaxTree::BoundedGlobally(*interval, map(branch))
+            }
+            SyntaxTree::BoundedFinally(interval, branch) => {
+                SyntaxTree::BoundedFinally(*interval, map(branch))
+            }
+            SyntaxTree::And(left_branch, right_branch) => {
+                SyntaxTree::And(map(left_branch), map(right_branch))
+            }
+            SyntaxTree::Or(left_branch, right_branch) => {
+                SyntaxTree::Or(map(left_branch), map(right_branch))
+            }
+            SyntaxTree::Implies(left_branch, right_branch) => {
+                SyntaxTree::Implies(map(left_branch), map(right_branch))
+            }
+            SyntaxTree::Until(left_branch, right_branch) => {
+                SyntaxTree::Until(map(left_branch), map(right_branch))
+            }
+            SyntaxTree::Release(left_branch, right_branch) => {
+                SyntaxTree::Release(map(left_branch), map(right_branch))
+            }
+            SyntaxTree::WeakUntil(left_branch, right_branch) => {
+                SyntaxTree::WeakUntil(map(left_branch), map(right_branch))
+            }
+            SyntaxTree::Since(left_branch, right_branch) => {
+                SyntaxTree::Since(map(left_branch), map(right_branch))
+            }
+            SyntaxTree::BoundedUntil(interval, left_branch, right_branch) => {
+                SyntaxTree::BoundedUntil(*interval, map(left_branch), map(right_branch))
+            }
+        }
+    }
+}
+
+/// Collects the operands of a chain of conjunctions (or disjunctions, depending on the root of the formula).
+fn flatten(formula: &SyntaxTree, operands: &mut Vec<Arc<SyntaxTree>>) {
+    for branch in branches(formula) {
+        if std::mem::discriminant(branch.as_ref()) == std::mem::discriminant(formula) {
+            flatten(branch, operands);
+        } else {
+            operands.push(branch.clone());
+        }
+    }
+}
+
+fn branches(formula: &SyntaxTree) -> Vec<&Arc<SyntaxTree>> {
+    match formula {
+        SyntaxTree::And(left_branch, right_branch) | SyntaxTree::Or(left_branch, right_branch) => {
+            vec![left_branch, right_branch]
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// The operands of a conjunction, or the formula itself if it is not a conjunction.
+fn conjuncts(formula: &Arc<SyntaxTree>) -> Vec<Arc<SyntaxTree>> {
+    match formula.as_ref() {
+        SyntaxTree::And(..) => {
+            let mut operands = Vec::new();
+            flatten(formula, &mut operands);
+            operands
+        }
+        _ => vec![formula.clone()],
+    }
+}
+
+/// The operands of a disjunction, or the formula itself if it is not a disjunction.
+fn disjuncts(formula: &Arc<SyntaxTree>) -> Vec<Arc<SyntaxTree>> {
+    match formula.as_ref() {
+        SyntaxTree::Or(..) => {
+            let mut operands = Vec::new();
+            flatten(formula, &mut operands);
+            operands
+        }
+        _ => vec![formula.clone()],
+    }
+}
+
+/// Nests the (non-empty) operands to the right with the same operator as the root of `formula`.
+fn rebuild(formula: &SyntaxTree, mut operands: Vec<Arc<SyntaxTree>>) -> SyntaxTree {
+    let last = operands.pop().expect("at least one operand");
+    operands
+        .into_iter()
+        .rev()
+        .fold(last.as_ref().clone(), |acc, operand| match formula {
+            SyntaxTree::And(..) => SyntaxTree::And(operand, Arc::new(acc)),
+            SyntaxTree::Or(..) => SyntaxTree::Or(operand, Arc::new(acc)),
+            _ => unreachable!("only conjunctions and disjunctions are rebuilt"),
+        })
+}
+
+fn and(left: Vec<Arc<SyntaxTree>>, right: Vec<Arc<SyntaxTree>>) -> Arc<SyntaxTree> {
+    let and = SyntaxTree::And(Arc::new(SyntaxTree::True), Arc::new(SyntaxTree::True));
+    Arc::new(rebuild(&and, left.into_iter().chain(right).collect()))
+}
+
+fn or(left: Vec<Arc<SyntaxTree>>, right: Vec<Arc<SyntaxTree>>) -> Arc<SyntaxTree> {
+    let or = SyntaxTree::Or(Arc::new(SyntaxTree::False), Arc::new(SyntaxTree::False));
+    Arc::new(rebuild(&or, left.into_iter().chain(right).collect()))
+}
+
+/// ¬φ, removing a double negation if φ is already negated.
+fn negate(formula: &Arc<SyntaxTree>) -> Arc<SyntaxTree> {
+    match formula.as_ref() {
+        SyntaxTree::Not(branch) => branch.clone(),
+        _ => Arc::new(SyntaxTree::Not(formula.clone())),
+    }
+}
+
+fn is_negation_of(formula: &SyntaxTree, other: &SyntaxTree) -> bool {
+    matches!(formula, SyntaxTree::Not(branch) if branch.as_ref() == other)
+}
+
+fn is_subset(operands: &[Arc<SyntaxTree>], other: &[Arc<SyntaxTree>]) -> bool {
+    operands.iter().all(|operand| other.contains(operand))
+}
+
+/// The interval `[a+c,b+d]` of a bounded operator with interval `[a,b]` applied to one with interval `[c,d]`,
+/// if its bounds fit in `Time`.
+fn nested_interval(interval: &Interval, child_interval: &Interval) -> Option<Interval> {
+    Some(Interval::new(
+        interval.start.checked_add(child_interval.start)?,
+        interval.end.checked_add(child_interval.end)?,
+    ))
+}
+
+/// Applies (at most) one rewriting rule to the root of the formula, whose branches are already simplified.
+fn rewrite(formula: SyntaxTree) -> SyntaxTree {
+    match &formula {
+        SyntaxTree::Atom(_) | SyntaxTree::True | SyntaxTree::False => formula,
+        SyntaxTree::Not(child) => match child.as_ref() {
+            // ¬¬φ ≡ φ
+            SyntaxTree::Not(grandchild) => grandchild.as_ref().clone(),
+            // ¬⊤ ≡ ⊥
+            SyntaxTree::True => SyntaxTree::False,
+            // ¬⊥ ≡ ⊤
+            SyntaxTree::False => SyntaxTree::True,
+            // ¬(φ -> ψ) ≡ φ ∧ ¬ψ
+            SyntaxTree::Implies(left_child, right_child) => {
+                SyntaxTree::And(left_child.clone(), negate(right_child))
+            }
+            // ¬ X φ ≡ N ¬ φ
+            SyntaxTree::Next(child) => SyntaxTree::WeakNext(negate(child)),
+            // ¬ N φ ≡ X ¬ φ
+            SyntaxTree::WeakNext(child) => SyntaxTree::Next(negate(child)),
+            // ¬ G φ ≡ F ¬ φ
+            SyntaxTree::Globally(child) => SyntaxTree::Finally(negate(child)),
+            // ¬ F φ ≡ G ¬ φ
+            SyntaxTree::Finally(child) => SyntaxTree::Globally(negate(child)),
+            // ¬ O φ ≡ H ¬ φ
+            SyntaxTree::Once(child) => SyntaxTree::Historically(negate(child)),
+            // ¬ H φ ≡ O ¬ φ
+            SyntaxTree::Historically(child) => SyntaxTree::Once(negate(child)),
+            // ¬ G[a,b] φ ≡ F[a,b] ¬ φ
+            SyntaxTree::BoundedGlobally(interval, child) => {
+                SyntaxTree::BoundedFinally(*interval, negate(child))
+            }
+            // ¬ F[a,b] φ ≡ G[a,b] ¬ φ
+            SyntaxTree::BoundedFinally(interval, child) => {
+                SyntaxTree::BoundedGlobally(*interval, negate(child))
+            }
+            // ¬(¬φ ∨ ψ) ≡ φ ∧ ¬ψ and ¬(φ ∨ ¬ψ) ≡ ¬φ ∧ ψ
+            SyntaxTree::Or(left_child, right_child)
+                if matches!(left_child.as_ref(), SyntaxTree::Not(_))
+                    || matches!(right_child.as_ref(), SyntaxTree::Not(_)) =>
+            {
+                SyntaxTree::And(negate(left_child), negate(right_child))
+            }
+            // ¬(¬φ ∧ ψ) ≡ φ ∨ ¬ψ and ¬(φ ∧ ¬ψ) ≡ ¬φ ∨ ψ
+            SyntaxTree::And(left_child, right_child)
+                if matches!(left_child.as_ref(), SyntaxTree::Not(_))
+                    || matches!(right_child.as_ref(), SyntaxTree::Not(_)) =>
+            {
+                SyntaxTree::Or(negate(left_child), negate(right_child))
+            }
+            _ => formula,
+        },
+        SyntaxTree::Next(child) => match child.as_ref() {
+            // X ⊥ ≡ ⊥
+            SyntaxTree::False => SyntaxTree::False,
+            _ => formula,
+        },
+        SyntaxTree::WeakNext(child) => match child.as_ref() {
+            // N ⊤ ≡ ⊤
+            SyntaxTree::True => SyntaxTree::True,
+            _ => formula,
+        },
+        SyntaxTree::Globally(child) => match child.as_ref() {
+            // G G φ ≡ G φ, G ⊤ ≡ ⊤ and G ⊥ ≡ ⊥
+            SyntaxTree::Globally(_) | SyntaxTree::True | SyntaxTree::False => {
+                child.as_ref().clone()
+            }
+            // G X φ ≡ ⊥, as X φ does not hold at the end of the trace
+            SyntaxTree::Next(_) => SyntaxTree::False,
+            _ => formula,
+        },
+        SyntaxTree::Finally(child) => match child.as_ref() {
+            // F F φ ≡ F φ, F ⊤ ≡ ⊤ and F ⊥ ≡ ⊥
+            SyntaxTree::Finally(_) | SyntaxTree::True | SyntaxTree::False => child.as_ref().clone(),
+            // F N φ ≡ ⊤, as N φ holds at the end of the trace
+            SyntaxTree::WeakNext(_) => SyntaxTree::True,
+            _ => formula,
+        },
+        SyntaxTree::Yesterday(child) => match child.as_ref() {
+            // Y ⊥ ≡ ⊥
+            SyntaxTree::False => SyntaxTree::False,
+            _ => formula,
+        },
+        SyntaxTree::Once(child) => match child.as_ref() {
+            // O O φ ≡ O φ, O ⊤ ≡ ⊤ and O ⊥ ≡ ⊥
+            SyntaxTree::Once(_) | SyntaxTree::True | SyntaxTree::False => child.as_ref().clone(),
+            _ => formula,
+        },
+        SyntaxTree::Historically(child) => match child.as_ref() {
+            // H H φ ≡ H φ, H ⊤ ≡ ⊤ and H ⊥ ≡ ⊥
+            SyntaxTree::Historically(_) | SyntaxTree::True | SyntaxTree::False => {
+                child.as_ref().clone()
+            }
+            // H Y φ ≡ ⊥, as Y φ does not hold at the start of the trace
+            SyntaxTree::Yesterday(_) => SyntaxTree::False,
+            _ => formula,
+        },
+        SyntaxTree::BoundedGlobally(interval, child) => match child.as_ref() {
+            // G[0,0] φ ≡ φ
+            _ if interval.end == 0 => child.as_ref().clone(),
+            // G[a,b] ⊤ ≡ ⊤
+            SyntaxTree::True => SyntaxTree::True,
+            // G[a,b] G[c,d] φ ≡ G[a+c,b+d] φ, unless the bounds overflow
+            SyntaxTree::BoundedGlobally(child_interval, grandchild) => {
+                match nested_interval(interval, child_interval) {
+                    Some(nested) => SyntaxTree::BoundedGlobally(nested, grandchild.clone()),
+                    None => formula,
+                }
+            }
+            _ => formula,
+        },
+        SyntaxTree::BoundedFinally(interval, child) => match child.as_ref() {
+            // F[0,0] φ ≡ φ
+            _ if interval.end == 0 => child.as_ref().clone(),
+            // F[a,b] ⊥ ≡ ⊥
+            SyntaxTree::False => SyntaxTree::False,
+            // F[a,b] F[c,d] φ ≡ F[a+c,b+d] φ, unless the bounds overflow
+            SyntaxTree::BoundedFinally(child_interval, grandchild) => {
+                match nested_interval(interval, child_interval) {
+                    Some(nested) => SyntaxTree::BoundedFinally(nested, grandchild.clone()),
+                    None => formula,
+                }
+            }
+            _ => formula,
+        },
+        SyntaxTree::And(..) => {
+            let mut operands = Vec::new();
+            flatten(&formula, &mut operands);
+            combine_all(&formula, operands, |left, right| {
+                rewrite_and(left, right).or_else(|| rewrite_and(right, left))
+            })
+        }
+        SyntaxTree::Or(..) => {
+            let mut operands = Vec::new();
+            flatten(&formula, &mut operands);
+            combine_all(&formula, operands, |left, right| {
+                rewrite_or(left, right).or_else(|| rewrite_or(right, left))
+            })
+        }
+        SyntaxTree::Implies(left_child, right_child) => {
+            match (left_child.as_ref(), right_child.as_ref()) {
+                // φ -> φ ≡ ⊤, φ -> ⊤ ≡ ⊤ and, ex falso quodlibet, ⊥ -> φ ≡ ⊤
+                _ if left_child == right_child => SyntaxTree::True,
+                (_, SyntaxTree::True) | (SyntaxTree::False, _) => SyntaxTree::True,
+                // ⊤ -> φ ≡ φ
+                (SyntaxTree::True, _) => right_child.as_ref().clone(),
+                // φ -> ⊥ ≡ ¬φ
+                (_, SyntaxTree::False) => negate(left_child).as_ref().clone(),
+                // ¬φ -> ψ ≡ ψ ∨ φ
+                (SyntaxTree::Not(child), _) => SyntaxTree::Or(right_child.clone(), child.clone()),
+                // Currying
+                // φ_1 -> (φ_2 -> ψ) ≡ (φ_1 ∧ φ_2) -> ψ
+                (_, SyntaxTree::Implies(c_1_0, c_1_1)) => SyntaxTree::Implies(
+                    Arc::new(SyntaxTree::And(left_child.clone(), c_1_0.clone())),
+                    c_1_1.clone(),
+                ),
+                _ => formula,
+            }
+        }
+        SyntaxTree::Until(left_child, right_child) => {
+            match (left_child.as_ref(), right_child.as_ref()) {
+                // φ U φ ≡ φ
+                _ if left_child == right_child => right_child.as_ref().clone(),
+                // φ U ⊤ ≡ ⊤, φ U ⊥ ≡ ⊥ (as Until is strong) and ⊥ U φ ≡ φ
+                (_, SyntaxTree::True | SyntaxTree::False) | (SyntaxTree::False, _) => {
+                    right_child.as_ref().clone()
+                }
+                // ⊤ U φ ≡ F φ
+                (SyntaxTree::True, _) => SyntaxTree::Finally(right_child.clone()),
+                // X (φ U ψ) ≡ (X φ) U (X ψ)
+                (SyntaxTree::Next(c_0), SyntaxTree::Next(c_1)) => {
+                    SyntaxTree::Next(Arc::new(SyntaxTree::Until(c_0.clone(), c_1.clone())))
+                }
+                // φ U ψ ≡ φ U (φ U ψ)
+                (_, SyntaxTree::Until(child, _)) if child == left_child => {
+                    right_child.as_ref().clone()
+                }
+                _ => formula,
+            }
+        }
+        SyntaxTree::Release(left_child, right_child) => {
+            match (left_child.as_ref(), right_child.as_ref()) {
+                // φ R φ ≡ φ
+                _ if left_child == right_child => right_child.as_ref().clone(),
+                // φ R ⊤ ≡ ⊤, φ R ⊥ ≡ ⊥ and ⊤ R φ ≡ φ
+                (_, SyntaxTree::True | SyntaxTree::False) | (SyntaxTree::True, _) => {
+                    right_child.as_ref().clone()
+                }
+                // ⊥ R φ ≡ G φ
+                (SyntaxTree::False, _) => SyntaxTree::Globally(right_child.clone()),
+                // N (φ R ψ) ≡ (N φ) R (N ψ)
+                (SyntaxTree::WeakNext(c_0), SyntaxTree::WeakNext(c_1)) => {
+                    SyntaxTree::WeakNext(Arc::new(SyntaxTree::Release(c_0.clone(), c_1.clone())))
+                }
+                // φ R ψ ≡ φ R (φ R ψ)
+                (_, SyntaxTree::Release(child, _)) if child == left_child => {
+                    right_child.as_ref().clone()
+                }
+                _ => formula,
+            }
+        }
+        SyntaxTree::WeakUntil(left_child, right_child) => {
+            match (left_child.as_ref(), right_child.as_ref()) {
+                // φ W φ ≡ φ
+                _ if left_child == right_child => right_child.as_ref().clone(),
+                // φ W ⊤ ≡ ⊤ and ⊤ W φ ≡ ⊤
+                (_, SyntaxTree::True) | (SyntaxTree::True, _) => SyntaxTree::True,
+                // ⊥ W φ ≡ φ
+                (SyntaxTree::False, _) => right_child.as_ref().clone(),
+                // φ W ⊥ ≡ G φ
+                (_, SyntaxTree::False) => SyntaxTree::Globally(left_child.clone()),
+                // N (φ W ψ) ≡ (N φ) W (N ψ)
+                (SyntaxTree::WeakNext(c_0), SyntaxTree::WeakNext(c_1)) => {
+                    SyntaxTree::WeakNext(Arc::new(SyntaxTree::WeakUntil(c_0.clone(), c_1.clone())))
+                }
+                // φ W ψ ≡ φ W (φ W ψ)
+                (_, SyntaxTree::WeakUntil(child, _)) if child == left_child => {
+                    right_child.as_ref().clone()
+                }
+                _ => formula,
+            }
+        }
+        SyntaxTree::Since(left_child, right_child) => {
+            match (left_child.as_ref(), right_child.as_ref()) {
+                // φ S φ ≡ φ
+                _ if left_child == right_child => right_child.as_ref().clone(),
+                // φ S ⊤ ≡ ⊤, φ S ⊥ ≡ ⊥ and ⊥ S φ ≡ φ
+                (_, SyntaxTree::True | SyntaxTree::False) | (SyntaxTree::False, _) => {
+                    right_child.as_ref().clone()
+                }
+                // ⊤ S φ ≡ O φ
+                (SyntaxTree::True, _) => SyntaxTree::Once(right_child.clone()),
+                // Y (φ S ψ) ≡ (Y φ) S (Y ψ)
+                (SyntaxTree::Yesterday(c_0), SyntaxTree::Yesterday(c_1)) => {
+                    SyntaxTree::Yesterday(Arc::new(SyntaxTree::Since(c_0.clone(), c_1.clone())))
+                }
+                // φ S ψ ≡ φ S (φ S ψ)
+                (_, SyntaxTree::Since(child, _)) if child == left_child => {
+                    right_child.as_ref().clone()
+                }
+                _ => formula,
+            }
+        }
+        SyntaxTree::BoundedUntil(interval, left_child, right_child) => {
+            match (left_child.as_ref(), right_child.as_ref()) {
+                // φ U[0,0] ψ ≡ ψ
+                _ if interval.end == 0 => right_child.as_ref().clone(),
+                // φ U[0,b] φ ≡ φ
+                _ if left_child == right_child && interval.start == 0 => {
+                    right_child.as_ref().clone()
+                }
+                // φ U[a,b] ⊥ ≡ ⊥
+                (_, SyntaxTree::False) => SyntaxTree::False,
+                // ⊥ U[0,b] ψ ≡ ψ
+                (SyntaxTree::False, _) if interval.start == 0 => right_child.as_ref().clone(),
+                // ⊥ U[a,b] ψ ≡ ⊥, if a > 0
+                (SyntaxTree::False, _) => SyntaxTree::False,
+                // φ U[0,b] ⊤ ≡ ⊤
+                (_, SyntaxTree::True) if interval.start == 0 => SyntaxTree::True,
+                // ⊤ U[a,b] ψ ≡ F[a,b] ψ
+                (SyntaxTree::True, _) => SyntaxTree::BoundedFinally(*interval, right_child.clone()),
+                _ => formula,
+            }
+        }
+    }
+}
+
+/// Repeatedly replaces pairs of operands of a conjunction (or disjunction) with their combination, as long as possible.
+fn combine_all(
+    formula: &SyntaxTree,
+    mut operands: Vec<Arc<SyntaxTree>>,
+    combine: impl Fn(&Arc<SyntaxTree>, &Arc<SyntaxTree>) -> Option<SyntaxTree>,
+) -> SyntaxTree {
+    'combine: loop {
+        for right in 1..operands.len() {
+            for left in 0..right {
+                if let Some(combined) = combine(&operands[left], &operands[right]) {
+                    operands.remove(right);
+                    operands[left] = Arc::new(combined);
+                    continue 'combine;
+                }
+            }
+        }
+        return rebuild(formula, operands);
+    }
+}
+
+/// Simplifies φ ∧ ψ, if possible.
+/// Rules that are not symmetric are only checked in one direction, as the operands are tried in both orders.
+fn rewrite_and(left_child: &Arc<SyntaxTree>, right_child: &Arc<SyntaxTree>) -> Option<SyntaxTree> {
+    let (left_disjuncts, right_disjuncts) = (disjuncts(left_child), disjuncts(right_child));
+    match (left_child.as_ref(), right_child.as_ref()) {
+        // Excluded middle
+        (left, right) if is_negation_of(right, left) => Some(SyntaxTree::False),
+        // Domination law
+        (_, SyntaxTree::False) => Some(SyntaxTree::False),
+        // Identity law
+        (_, SyntaxTree::True) => Some(left_child.as_ref().clone()),
+        // Idempotence and absorption laws
+        // (φ ∨ ψ) ∧ φ ≡ φ
+        _ if is_subset(&right_disjuncts, &left_disjuncts) => Some(right_child.as_ref().clone()),
+        _ if is_subset(&left_disjuncts, &right_disjuncts) => Some(left_child.as_ref().clone()),
+        // Distributive laws
+        // (φ ∨ ψ_1) ∧ (φ ∨ ψ_2) ≡ φ ∨ (ψ_1 ∧ ψ_2)
+        (SyntaxTree::Or(..), SyntaxTree::Or(..))
+            if left_disjuncts.iter().any(|c| right_disjuncts.contains(c)) =>
+        {
+            let (common, left_rest): (Vec<_>, Vec<_>) = left_disjuncts
+                .into_iter()
+                .partition(|c| right_disjuncts.contains(c));
+            let right_rest = right_disjuncts
+                .into_iter()
+                .filter(|c| !common.contains(c))
+                .collect();
+            let rest = and(
+                vec![or(left_rest, Vec::new())],
+                vec![or(right_rest, Vec::new())],
+            );
+            Some(or(common, vec![rest]).as_ref().clone())
+        }
+        // X (φ ∧ ψ) ≡ (X φ) ∧ (X ψ)
+        (SyntaxTree::Next(c_0), SyntaxTree::Next(c_1)) => {
+            Some(SyntaxTree::Next(and(vec![c_0.clone()], vec![c_1.clone()])))
+        }
+        // N (φ ∧ ψ) ≡ (N φ) ∧ (N ψ)
+        (SyntaxTree::WeakNext(c_0), SyntaxTree::WeakNext(c_1)) => Some(SyntaxTree::WeakNext(and(
+            vec![c_0.clone()],
+            vec![c_1.clone()],
+        ))),
+        // G (φ ∧ ψ) ≡ (G φ) ∧ (G ψ)
+        (SyntaxTree::Globally(c_0), SyntaxTree::Globally(c_1)) => Some(SyntaxTree::Globally(and(
+            vec![c_0.clone()],
+            vec![c_1.clone()],
+        ))),
+        // Y (φ ∧ ψ) ≡ (Y φ) ∧ (Y ψ)
+        (SyntaxTree::Yesterday(c_0), SyntaxTree::Yesterday(c_1)) => Some(SyntaxTree::Yesterday(
+            and(vec![c_0.clone()], vec![c_1.clone()]),
+        )),
+        // H (φ ∧ ψ) ≡ (H φ) ∧ (H ψ)
+        (SyntaxTree::Historically(c_0), SyntaxTree::Historically(c_1)) => Some(
+            SyntaxTree::Historically(and(vec![c_0.clone()], vec![c_1.clone()])),
+        ),
+        // G[a,b] (φ ∧ ψ) ≡ (G[a,b] φ) ∧ (G[a,b] ψ)
+        (SyntaxTree::BoundedGlobally(i_0, c_0), SyntaxTree::BoundedGlobally(i_1, c_1))
+            if i_0 == i_1 =>
+        {
+            Some(SyntaxTree::BoundedGlobally(
+                *i_0,
+                and(vec![c_0.clone()], vec![c_1.clone()]),
+            ))
+        }
+        // De Morgan's laws
+        (SyntaxTree::Not(c_0), SyntaxTree::Not(c_1)) => {
+            Some(SyntaxTree::Not(or(vec![c_0.clone()], vec![c_1.clone()])))
+        }
+        // (φ -> ψ_1) ∧ (φ -> ψ_2) ≡ φ -> (ψ_1 ∧ ψ_2)
+        (SyntaxTree::Implies(c_1_0, c_1_1), SyntaxTree::Implies(c_2_0, c_2_1))
+            if c_1_0 == c_2_0 =>
+        {
+            Some(SyntaxTree::Implies(
+                c_1_0.clone(),
+                and(vec![c_1_1.clone()], vec![c_2_1.clone()]),
+            ))
+        }
+        // (φ_1 -> ψ) ∧ (φ_2 -> ψ) ≡ (φ_1 ∨ φ_2) -> ψ
+        (SyntaxTree::Implies(c_1_0, c_1_1), SyntaxTree::Implies(c_2_0, c_2_1))
+            if c_1_1 == c_2_1 =>
+        {
+            Some(SyntaxTree::Implies(
+                or(vec![c_1_0.clone()], vec![c_2_0.clone()]),
+                c_1_1.clone(),
+            ))
+        }
+        // (φ_1 U ψ) ∧ (φ_2 U ψ) ≡ (φ_1 ∧ φ_2) U ψ
+        (SyntaxTree::Until(c_1_0, c_1_1), SyntaxTree::Until(c_2_0, c_2_1)) if c_1_1 == c_2_1 => {
+            Some(SyntaxTree::Until(
+                and(vec![c_1_0.clone()], vec![c_2_0.clone()]),
+                c_1_1.clone(),
+            ))
+        }
+        // (φ_1 W ψ) ∧ (φ_2 W ψ) ≡ (φ_1 ∧ φ_2) W ψ
+        (SyntaxTree::WeakUntil(c_1_0, c_1_1), SyntaxTree::WeakUntil(c_2_0, c_2_1))
+            if c_1_1 == c_2_1 =>
+        {
+            Some(SyntaxTree::WeakUntil(
+                and(vec![c_1_0.clone()], vec![c_2_0.clone()]),
+                c_1_1.clone(),
+            ))
+        }
+        // (φ R ψ_1) ∧ (φ R ψ_2) ≡ φ R (ψ_1 ∧ ψ_2)
+        (SyntaxTree::Release(c_1_0, c_1_1), SyntaxTree::Release(c_2_0, c_2_1))
+            if c_1_0 == c_2_0 =>
+        {
+            Some(SyntaxTree::Release(
+                c_1_0.clone(),
+                and(vec![c_1_1.clone()], vec![c_2_1.clone()]),
+            ))
+        }
+        // (φ_1 S ψ) ∧ (φ_2 S ψ) ≡ (φ_1 ∧ φ_2) S ψ
+        (SyntaxTree::Since(c_1_0, c_1_1), SyntaxTree::Since(c_2_0, c_2_1)) if c_1_1 == c_2_1 => {
+            Some(SyntaxTree::Since(
+                and(vec![c_1_0.clone()], vec![c_2_0.clone()]),
+                c_1_1.clone(),
+            ))
+        }
+        // G φ ≡ φ ∧ N(G φ)
+        (left, SyntaxTree::WeakNext(child)) if matches!(child.as_ref(), SyntaxTree::Globally(c) if c.as_ref() == left) => {
+            Some(child.as_ref().clone())
+        }
+        _ => None,
+    }
+}
+
+/// Simplifies φ ∨ ψ, if possible.
+/// Rules that are not symmetric are only checked in one direction, as the operands are tried in both orders.
+fn rewrite_or(left_child: &Arc<SyntaxTree>, right_child: &Arc<SyntaxTree>) -> Option<SyntaxTree> {
+    let (left_conjuncts, right_conjuncts) = (conjuncts(left_child), conjuncts(right_child));
+    match (left_child.as_ref(), right_child.as_ref()) {
+        // Excluded middle
+        (left, right) if is_negation_of(right, left) => Some(SyntaxTree::True),
+        // Domination law
+        (_, SyntaxTree::True) => Some(SyntaxTree::True),
+        // Identity law
+        (_, SyntaxTree::False) => Some(left_child.as_ref().clone()),
+        // Idempotence and absorption laws
+        // (φ ∧ ψ) ∨ φ ≡ φ
+        _ if is_subset(&right_conjuncts, &left_conjuncts) => Some(right_child.as_ref().clone()),
+        _ if is_subset(&left_conjuncts, &right_conjuncts) => Some(left_child.as_ref().clone()),
+        // Distributive laws
+        // (φ ∧ ψ_1) ∨ (φ ∧ ψ_2) ≡ φ ∧ (ψ_1 ∨ ψ_2)
+        (SyntaxTree::And(..), SyntaxTree::And(..))
+            if left_conjuncts.iter().any(|c| right_conjuncts.contains(c)) =>
+        {
+            let (common, left_rest): (Vec<_>, Vec<_>) = left_conjuncts
+                .into_iter()
+                .partition(|c| right_conjuncts.contains(c));
+            let right_rest = right_conjuncts
+                .into_iter()
+                .filter(|c| !common.contains(c))
+                .collect();
+            let rest = or(
+                vec![and(left_rest, Vec::new())],
+                vec![and(right_rest, Vec::new())],
+            );
+            Some(and(common, vec![rest]).as_ref().clone())
+        }
+        // X (φ ∨ ψ) ≡ (X φ) ∨ (X ψ)
+        (SyntaxTree::Next(c_0), SyntaxTree::Next(c_1)) => {
+            Some(SyntaxTree::Next(or(vec![c_0.clone()], vec![c_1.clone()])))
+        }
+        // N (φ ∨ ψ) ≡ (N φ) ∨ (N ψ)
+        (SyntaxTree::WeakNext(c_0), SyntaxTree::WeakNext(c_1)) => Some(SyntaxTree::WeakNext(or(
+            vec![c_0.clone()],
+            vec![c_1.clone()],
+        ))),
+        // F (φ ∨ ψ) ≡ (F φ) ∨ (F ψ)
+        (SyntaxTree::Finally(c_0), SyntaxTree::Finally(c_1)) => Some(SyntaxTree::Finally(or(
+            vec![c_0.clone()],
+            vec![c_1.clone()],
+        ))),
+        // Y (φ ∨ ψ) ≡ (Y φ) ∨ (Y ψ)
+        (SyntaxTree::Yesterday(c_0), SyntaxTree::Yesterday(c_1)) => Some(SyntaxTree::Yesterday(
+            or(vec![c_0.clone()], vec![c_1.clone()]),
+        )),
+        // O (φ ∨ ψ) ≡ (O φ) ∨ (O ψ)
+        (SyntaxTree::Once(c_0), SyntaxTree::Once(c_1)) => {
+            Some(SyntaxTree::Once(or(vec![c_0.clone()], vec![c_1.clone()])))
+        }
+        // F[a,b] (φ ∨ ψ) ≡ (F[a,b] φ) ∨ (F[a,b] ψ)
+        (SyntaxTree::BoundedFinally(i_0, c_0), SyntaxTree::BoundedFinally(i_1, c_1))
+            if i_0 == i_1 =>
+        {
+            Some(SyntaxTree::BoundedFinally(
+                *i_0,
+                or(vec![c_0.clone()], vec![c_1.clone()]),
+            ))
+        }
+        // De Morgan's laws
+        (SyntaxTree::Not(c_0), SyntaxTree::Not(c_1)) => {
+            Some(SyntaxTree::Not(and(vec![c_0.clone()], vec![c_1.clone()])))
+        }
+        // ¬φ ∨ ψ ≡ φ -> ψ
+        (SyntaxTree::Not(child), _) => {
+            Some(SyntaxTree::Implies(child.clone(), right_child.clone()))
+        }
+        // (φ -> ψ_1) ∨ (φ -> ψ_2) ≡ φ -> (ψ_1 ∨ ψ_2)
+        (SyntaxTree::Implies(c_1_0, c_1_1), SyntaxTree::Implies(c_2_0, c_2_1))
+            if c_1_0 == c_2_0 =>
+        {
+            Some(SyntaxTree::Implies(
+                c_1_0.clone(),
+                or(vec![c_1_1.clone()], vec![c_2_1.clone()]),
+            ))
+        }
+        // (φ_1 -> ψ) ∨ (φ_2 -> ψ) ≡ (φ_1 ∧ φ_2) -> ψ
+        (SyntaxTree::Implies(c_1_0, c_1_1), SyntaxTree::Implies(c_2_0, c_2_1))
+            if c_1_1 == c_2_1 =>
+        {
+            Some(SyntaxTree::Implies(
+                and(vec![c_1_0.clone()], vec![c_2_0.clone()]),
+                c_1_1.clone(),
+            ))
+        }
+        // (φ U ψ_1) ∨ (φ U ψ_2) ≡ φ U (ψ_1 ∨ ψ_2)
+        (SyntaxTree::Until(c_1_0, c_1_1), SyntaxTree::Until(c_2_0, c_2_1)) if c_1_0 == c_2_0 => {
+            Some(SyntaxTree::Until(
+                c_1_0.clone(),
+                or(vec![c_1_1.clone()], vec![c_2_1.clone()]),
+            ))
+        }
+        // (φ W ψ_1) ∨ (φ W ψ_2) ≡ φ W (ψ_1 ∨ ψ_2)
+        (SyntaxTree::WeakUntil(c_1_0, c_1_1), SyntaxTree::WeakUntil(c_2_0, c_2_1))
+            if c_1_0 == c_2_0 =>
+        {
+            Some(SyntaxTree::WeakUntil(
+                c_1_0.clone(),
+                or(vec![c_1_1.clone()], vec![c_2_1.clone()]),
+            ))
+        }
+        // (φ_1 R ψ) ∨ (φ_2 R ψ) ≡ (φ_1 ∨ φ_2) R ψ
+        (SyntaxTree::Release(c_1_0, c_1_1), SyntaxTree::Release(c_2_0, c_2_1))
+            if c_1_1 == c_2_1 =>
+        {
+            Some(SyntaxTree::Release(
+                or(vec![c_1_0.clone()], vec![c_2_0.clone()]),
+                c_1_1.clone(),
+            ))
+        }
+        // (φ S ψ_1) ∨ (φ S ψ_2) ≡ φ S (ψ_1 ∨ ψ_2)
+        (SyntaxTree::Since(c_1_0, c_1_1), SyntaxTree::Since(c_2_0, c_2_1)) if c_1_0 == c_2_0 => {
+            Some(SyntaxTree::Since(
+                c_1_0.clone(),
+                or(vec![c_1_1.clone()], vec![c_2_1.clone()]),
+            ))
+        }
+        // F φ ≡ φ ∨ X(F φ)
+        (left, SyntaxTree::Next(child)) if matches!(child.as_ref(), SyntaxTree::Finally(c) if c.as_ref() == left) => {
+            Some(child.as_ref().clone())
+        }
+        // φ U ψ ≡ ψ ∨ (φ ∧ X(φ U ψ))
+        (left, SyntaxTree::And(c_0, c_1)) => {
+            [(c_0, c_1), (c_1, c_0)]
+                .into_iter()
+                .find_map(|(now, next)| match next.as_ref() {
+                    SyntaxTree::Next(until) => match until.as_ref() {
+                        SyntaxTree::Until(c_2_0, c_2_1)
+                            if c_2_0 == now && c_2_1.as_ref() == left =>
+                        {
+                            Some(until.as_ref().clone())
+                        }
+                        _ => None,
+                    },
+                    _ => None,
+                })
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod rewrite {
+    use super::*;
+    use rand::prelude::*;
+
+    #[test]
+    fn rules() {
+        for (formula, simplified) in [
+            ("!!x0", "x0"),
+            ("!true", "false"),
+            ("!F !x0", "G x0"),
+            ("!(x0 -> x1)", "x0 & !x1"),
+            ("!(!x0 | x1)", "x0 & !x1"),
+            ("!x0 | !x1", "!(x0 & x1)"),
+            ("!x0 | x1", "x0 -> x1"),
+            ("x0 & !x0", "false"),
+            ("x1 | !x1", "true"),
+            ("x0 & true", "x0"),
+            ("x0 | (x1 & x0)", "x0"),
+            ("x1 & (x2 & x1)", "x1 & x2"),
+            ("(x0 | x1) & (x2 | x0)", "x0 | (x1 & x2)"),
+            ("X x0 & (x1 & X x2)", "x1 & X(x0 & x2)"),
+            ("F x0 | F x1", "F(x0 | x1)"),
+            ("(x0 -> x1) & (x0 -> x2)", "x0 -> (x1 & x2)"),
+            ("x0 -> (x1 -> x2)", "(x0 & x1) -> x2"),
+            ("!x0 -> x1", "x1 | x0"),
+            ("x0 -> false", "!x0"),
+            ("G G x0", "G x0"),
+            ("G X x0", "false"),
+            ("F N x0", "true"),
+            ("H Y x0", "false"),
+            ("O O x0", "O x0"),
+            ("x0 | X F x0", "F x0"),
+            ("x0 & N G x0", "G x0"),
+            ("x1 | (x0 & X(x0 U x1))", "x0 U x1"),
+            ("true U x0", "F x0"),
+            ("false R x0", "G x0"),
+            ("x0 W false", "G x0"),
+            ("true S x0", "O x0"),
+            ("x0 U (x0 U x1)", "x0 U x1"),
+            ("(x0 U x1) & (x2 U x1)", "(x0 & x2) U x1"),
+            ("X x0 U X x1", "X(x0 U x1)"),
+            ("G[0,0] x0", "x0"),
+            ("F[1,2] F[0,3] x0", "F[1,5] x0"),
+            ("false U[1,2] x0", "false"),
+            ("true U[1,2] x0", "F[1,2] x0"),
+            ("G(x0 -> (x1 -> false))", "G(x0 -> !x1)"),
+        ] {
+            assert_eq!(
+                parse_formula(formula).simplify(),
+                parse_formula(simplified).to_canonical(),
+                "{formula}"
+            );
+        }
+    }
+
+    // Nested intervals are not merged if their bounds would overflow.
+    #[test]
+    fn huge_bounds() {
+        let max = Time::MAX;
+        for formula in [
+            format!("F[1,{max}] F[0,1] x0"),
+            format!("G[0,{max}] G[{max},{max}] x0"),
+        ] {
+            let formula = parse_formula(&formula);
+            assert_eq!(formula.simplify(), formula.to_canonical(), "{formula}");
+        }
+        assert_eq!(
+            parse_formula(&format!("F[1,{}] F[0,1] x0", max - 1)).simplify(),
+            parse_formula(&format!("F[1,{max}] x0"))
+        );
+    }
+
+    #[test]
+    fn canonical() {
+        let formula = parse_formula("x2 & (x0 & x1)").to_canonical();
+        assert_eq!(formula, parse_formula("(x1 & x2) & x0").to_canonical());
+        assert_eq!(formula, parse_formula("x0 & (x1 & x2)"));
+        assert_eq!(
+            parse_formula("G((x1 | x0) U x2)").to_canonical(),
+            parse_formula("G((x0 | x1) U x2)")
+        );
+    }
+
+    #[test]
+    fn nnf() {
+        for (formula, nnf) in [
+            ("!(x0 & G x1)", "!x0 | F !x1"),
+            ("!(x0 -> X x1)", "x0 & N !x1"),
+            ("!(x0 U x1)", "!x0 R !x1"),
+            ("!(x0 W x1)", "!x1 U (!x0 & !x1)"),
+            ("!H(x0 -> !x1)", "O(x0 & x1)"),
+            ("!F[1,2] Y x0", "G[1,2] !Y x0"),
+            ("!true | x0", "false | x0"),
+        ] {
+            assert_eq!(
+                parse_formula(formula).to_nnf(),
+                parse_formula(nnf),
+                "{formula}"
+            );
+        }
+    }
+
+    fn random_interval(rng: &mut StdRng) -> Interval {
+        let start = rng.gen_range(0..=2);
+        Interval::new(start, start + rng.gen_range(0..=2))
+    }
+
+    fn random_formula(rng: &mut StdRng, size: usize) -> SyntaxTree {
+        if size == 1 {
+            return match rng.gen_range(0..5) {
+                0 => SyntaxTree::True,
+                1 => SyntaxTree::False,
+                _ => SyntaxTree::Atom(rng.gen_range(0..3)),
+            };
+        }
+        if size == 2 || rng.gen_bool(0.4) {
+            let child = Arc::new(random_formula(rng, size - 1));
+            return match rng.gen_range(0..10) {
+                0 => SyntaxTree::Not(child),
+                1 => SyntaxTree::Next(child),
+                2 => SyntaxTree::WeakNext(child),
+                3 => SyntaxTree::Globally(child),
+                4 => SyntaxTree::Finally(child),
+                5 => SyntaxTree::Yesterday(child),
+                6 => SyntaxTree::Once(child),
+                7 => SyntaxTree::Historically(child),
+                8 => SyntaxTree::BoundedGlobally(random_interval(rng), child),
+                _ => SyntaxTree::BoundedFinally(random_interval(rng), child),
+            };
+        }
+        let left_size = rng.gen_range(1..size - 1);
+        let left = Arc::new(random_formula(rng, left_size));
+        let right = Arc::new(random_formula(rng, size - 1 - left_size));
+        match rng.gen_range(0..8) {
+            0 => SyntaxTree::And(left, right),
+            1 => SyntaxTree::Or(left, right),
+            2 => SyntaxTree::Implies(left, right),
+            3 => SyntaxTree::Until(left, right),
+            4 => SyntaxTree::Release(left, right),
+            5 => SyntaxTree::WeakUntil(left, right),
+            6 => SyntaxTree::Since(left, right),
+            _ => SyntaxTree::BoundedUntil(random_interval(rng), left, right),
+        }
+    }
+
+    fn is_nnf(formula: &SyntaxTree) -> bool {
+        match formula {
+            SyntaxTree::Atom(_) | SyntaxTree::True | SyntaxTree::False => true,
+            SyntaxTree::Not(child) => match child.as_ref() {
+                SyntaxTree::Atom(_) => true,
+                SyntaxTree::Yesterday(child) => is_nnf(child),
+                SyntaxTree::BoundedUntil(_, left_child, right_child) => {
+                    is_nnf(left_child) && is_nnf(right_child)
+                }
+                _ => false,
+            },
+            SyntaxTree::Implies(..) => false,
+            SyntaxTree::Next(child)
+            | SyntaxTree::WeakNext(child)
+            | SyntaxTree::Globally(child)
+            | SyntaxTree::Finally(child)
+            | SyntaxTree::Yesterday(child)
+            | SyntaxTree::Once(child)
+            | SyntaxTree::Historically(child)
+            | SyntaxTree::BoundedGlobally(_, child)
+            | SyntaxTree::BoundedFinally(_, child) => is_nnf(child),
+            SyntaxTree::And(left_child, right_child)
+            | SyntaxTree::Or(left_child, right_child)
+            | SyntaxTree::Until(left_child, right_child)
+            | SyntaxTree::Release(left_child, right_child)
+            | SyntaxTree::WeakUntil(left_child, right_child)
+            | SyntaxTree::Since(left_child, right_child)
+            | SyntaxTree::BoundedUntil(_, left_child, right_child) => {
+                is_nnf(left_child) && is_nnf(right_child)
+            }
+        }
+    }
+
+    // Rewritten formulae have to agree with the original ones at every time instant of random traces.
+    #[test]
+    fn equivalence() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let traces = (0..50)
+            .map(|_| {
+                let len = rng.gen_range(1..=8);
+                (0..len)
+                    .map(|_| [rng.gen(), rng.gen(), rng.gen()])
+                    .collect::<Vec<[bool; 3]>>()
+            })
+            .collect::<Vec<_>>();
+
+        for _ in 0..2000 {
+            let size = rng.gen_range(1..=10);
+            let formula = random_formula(&mut rng, size);
+            let simplified = formula.simplify();
+            let nnf = formula.to_nnf();
+            let canonical = formula.to_canonical();
+            assert!(
+                simplified.size() <= formula.size(),
+                "{formula}: {simplified}"
+            );
+            assert_eq!(simplified.simplify(), simplified, "{formula}");
+            assert_eq!(canonical.to_canonical(), canonical, "{formula}");
+            assert!(is_nnf(&nnf), "{formula}: {nnf}");
+            for trace in &traces {
+                let values = formula.eval_all_times(trace);
+                assert_eq!(
+                    simplified.eval_all_times(trace),
+                    values,
+                    "{formula}: {simplified} on {trace:?}"
+                );
+                assert_eq!(
+                    nnf.eval_all_times(trace),
+                    values,
+                    "{formula}: {nnf} on {trace:?}"
+                );
+                assert_eq!(
+                    canonical.eval_all_times(trace),
+                    values,
+                    "{formula}: {canonical} on {trace:?}"
+                );
+            }
+        }
+    }
+}
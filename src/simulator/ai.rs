@@ -9,6 +9,8 @@ pub trait Ai: Debug {
     fn decide(&mut self, world: &World) -> Action;
 }
 
+// Swapped in by hand for the default AI (see `World::proc_gen_scenario`).
+#[allow(dead_code)]
 #[derive(Clone, Debug)]
 pub struct RandomAi {
     rng: StdRng,
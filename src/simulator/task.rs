@@ -8,6 +8,8 @@ pub trait Task: Debug {
     }
 }
 
+// Swapped in by hand for the default task.
+#[allow(dead_code)]
 #[derive(Debug)]
 pub struct ReachRoom {
     room: Room,
@@ -15,6 +17,7 @@ pub struct ReachRoom {
 }
 
 impl ReachRoom {
+    #[allow(dead_code)]
     pub fn new(room: Room) -> ReachRoom {
         ReachRoom {
             room,
@@ -39,11 +39,17 @@ pub struct World {
     pub icub_location: NodeIndex,
     pub icub_charge: Time,
     pub outcome: (Action, Result<(), Failure>),
+    /// Simulations are stopped after this many steps, if the task has not been completed already,
+    /// or only when it is completed if `None`.
+    pub time_limit: Option<Time>,
 }
 
 impl World {
     pub const MAX_CHARGE: Time = 9;
 
+    /// The time limit of the predefined scenarios.
+    pub const DEFAULT_TIME_LIMIT: Time = 4096;
+
     pub fn new(rooms: UnGraph<Room, Path>, icub_location: NodeIndex) -> World {
         World {
             rooms,
@@ -51,11 +57,12 @@ impl World {
             icub_location,
             icub_charge: World::MAX_CHARGE,
             outcome: (Action::Wait, Ok(())),
+            time_limit: Some(World::DEFAULT_TIME_LIMIT),
         }
     }
 
     pub fn running(&self) -> bool {
-        self.time < 255
+        self.time_limit.is_none_or(|limit| self.time < limit)
     }
 
     pub fn run<const N: usize>(
@@ -114,6 +121,8 @@ impl World {
         Ok(())
     }
 
+    // Alternative scenario, swapped in by hand in `main`.
+    #[allow(dead_code)]
     pub fn lab_scenario() -> Self {
         let mut rooms = Graph::new_undirected();
         let lab_1 = rooms.add_node(Room::Lab);
@@ -149,9 +158,12 @@ impl World {
             icub_location: lab_1,
             icub_charge: 4,
             outcome: (Action::Wait, Ok(())),
+            time_limit: Some(World::DEFAULT_TIME_LIMIT),
         }
     }
 
+    // Alternative scenario, swapped in by hand in `main`.
+    #[allow(dead_code)]
     pub fn recharging_scenario() -> Self {
         let mut rooms = Graph::new_undirected();
         let lab = rooms.add_node(Room::Lab);
@@ -187,9 +199,12 @@ impl World {
             icub_location: lab,
             icub_charge: 3,
             outcome: (Action::Wait, Ok(())),
+            time_limit: Some(World::DEFAULT_TIME_LIMIT),
         }
     }
 
+    // Alternative scenario, swapped in by hand in `main`.
+    #[allow(dead_code)]
     pub fn proc_gen_recharging_scenario() -> Self {
         let mut rng = StdRng::seed_from_u64(rand::thread_rng().gen());
         let mut rooms = Graph::new_undirected();
@@ -239,9 +254,12 @@ impl World {
             icub_location: start,
             icub_charge: World::MAX_CHARGE,
             outcome: (Action::Wait, Ok(())),
+            time_limit: Some(World::DEFAULT_TIME_LIMIT),
         }
     }
 
+    // Alternative scenario, swapped in by hand in `main`.
+    #[allow(dead_code)]
     pub fn door_scenario() -> (Self, Box<dyn Task>) {
         const ROOM_TYPES: [Room; 3] = [Room::Office, Room::ChargingStation, Room::Lab];
         let mut rng = StdRng::seed_from_u64(rand::thread_rng().gen());
@@ -263,11 +281,14 @@ impl World {
             icub_location: room_1,
             icub_charge: 5,
             outcome: (Action::Wait, Ok(())),
+            time_limit: Some(World::DEFAULT_TIME_LIMIT),
         };
         let task = ReachNode::new(room_2);
         (world, Box::new(task))
     }
 
+    // Alternative scenario, swapped in by hand in `main`.
+    #[allow(dead_code)]
     pub fn proc_gen_scenario() -> (Self, Box<dyn Task>, Box<dyn Ai>) {
         const ROOM_TYPES: [Room; 3] = [Room::Office, Room::ChargingStation, Room::Lab];
         let mut rng = StdRng::from_entropy();
@@ -373,3 +394,53 @@ impl World {
         (world, Box::new(task), Box::new(ai))
     }
 }
+
+#[cfg(test)]
+mod simulation {
+    use super::*;
+
+    #[derive(Debug)]
+    struct Idle;
+
+    impl Ai for Idle {
+        fn decide(&mut self, _world: &World) -> Action {
+            Action::Wait
+        }
+    }
+
+    /// A task completed at the given time.
+    #[derive(Debug)]
+    struct Deadline(Time);
+
+    impl Task for Deadline {
+        fn success(&mut self, world: &World) -> bool {
+            world.time >= self.0
+        }
+    }
+
+    fn one_room() -> World {
+        let mut rooms = Graph::new_undirected();
+        let lab = rooms.add_node(Room::Lab);
+        World::new(rooms, lab)
+    }
+
+    // Simulations can run past 255 steps, up to the time limit if any.
+    #[test]
+    fn long_runs() {
+        let monitors: [Box<dyn Monitor>; 1] = [Box::new(BatteryLevel(0))];
+
+        let mut world = one_room();
+        world.time_limit = Some(1000);
+        let (trace, success) = world.run(&mut Idle, &mut Deadline(Time::MAX), &monitors);
+        assert!(!success);
+        assert_eq!(trace.len(), 1000);
+        // The battery runs out, and stays so.
+        assert!(!trace[0][0] && trace[999][0]);
+
+        let mut world = one_room();
+        world.time_limit = None;
+        let (trace, success) = world.run(&mut Idle, &mut Deadline(5000), &monitors);
+        assert!(success);
+        assert_eq!(trace.len(), 5001);
+    }
+}
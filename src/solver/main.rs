@@ -4,19 +4,75 @@ use std::fs::File;
 use std::io::prelude::*;
 use std::io::BufReader;
 use std::path::Path;
+use std::time::Duration;
 
-use clap::Parser;
+use clap::{ArgGroup, Parser, ValueEnum};
 
 /// Search for a formula consistent with the given sample.
 /// Supported file types: ron, json.
 #[derive(Parser, Debug)]
 #[clap(name = "solver")]
+#[command(group(ArgGroup::new("misclassified").args(["check", "max_errors"])))]
 struct Solver {
     /// The sample for which to learn a solving formula
     sample: String,
-    /// Use parallel search via multithreading
+    /// The search algorithm to use
+    #[arg(short, long, value_enum, default_value_t = Algorithm::Enumerative)]
+    algorithm: Algorithm,
+    /// Use parallel search via multithreading (enumerative search only)
     #[arg(short, long, default_value_t = false)]
     multithread: bool,
+    /// Allow the solution to misclassify some traces, to learn from noisy samples
+    /// (enumerative search only). Either a number of traces, such as 3,
+    /// or a fraction of all traces, such as 0.05 or 5%
+    #[arg(short = 'e', long)]
+    max_errors: Option<ErrorBudget>,
+    /// Find all solutions of minimal size (enumerative search only)
+    #[arg(long, default_value_t = false)]
+    all: bool,
+    /// With --all, find also the solutions up to the given number of sizes more than the minimal one
+    #[arg(long, default_value_t = 0, requires = "all")]
+    extra_size: usize,
+    /// The operators allowed in the solution, separated by commas, such as G,F,&,|,->.
+    /// Each operator counts as 1 towards the size of the solution,
+    /// unless followed by a different cost, such as U:2
+    #[arg(long, default_value_t = Operators::default())]
+    operators: Operators,
+    /// The largest end of the intervals of bounded operators, e.g. 5 allows intervals up to F[0,5]
+    #[arg(long, default_value_t = Operators::DEFAULT_HORIZON)]
+    horizon: usize,
+    /// Give up if no solution is found up to the given size
+    #[arg(long)]
+    max_size: Option<usize>,
+    /// Give up if no solution is found within the given number of seconds
+    #[arg(short, long, value_parser = parse_timeout)]
+    timeout: Option<Duration>,
+    /// Check the given formula (written with the variable names of the sample) against the sample,
+    /// reporting the traces it misclassifies, instead of learning one
+    #[arg(long, conflicts_with_all = ["all", "max_errors", "lasso"])]
+    check: Option<String>,
+    /// Explain why each misclassified trace is misclassified (with --check or --max-errors),
+    /// by the values of the subformulae at the time instants that justify them
+    #[arg(long, default_value_t = false, requires = "misclassified")]
+    explain: bool,
+    /// Read the sample as made of infinite traces, given as lassos (prefix and cycle),
+    /// and learn under infinite-trace semantics (enumerative search only)
+    #[arg(long, default_value_t = false)]
+    lasso: bool,
+    /// Save the solution to the given file, together with the names of its variables.
+    /// Supported file types: ron, json.
+    #[arg(short, long)]
+    output: Option<String>,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum Algorithm {
+    /// Enumerate formulae by size, pruning them by syntactic rules
+    Enumerative,
+    /// Build formulae by size out of smaller ones, discarding those equivalent on the sample
+    BottomUp,
+    /// Solve SAT problems encoding formulae of increasing size
+    Sat,
 }
 
 fn main() -> std::io::Result<()> {
@@ -28,435 +84,246 @@ fn main() -> std::io::Result<()> {
     let mut contents = Vec::new();
     buf_reader.read_to_end(&mut contents)?;
 
-    let load_and_solve = match path.extension() {
-        Some(ext) if ext == "ron" => ron_load_and_solve,
-        Some(ext) if ext == "json" => json_load_and_solve,
-        Some(ext) => {
-            println!("File format unknown or not supported: {:#?}", ext);
+    let options = SolveOptions {
+        multithread: solver.multithread,
+        log: true,
+        max_size: solver.max_size,
+        operators: solver.operators.with_horizon(solver.horizon),
+        timeout: solver.timeout,
+        cancellation: None,
+    };
+
+    if solver.lasso {
+        if !matches!(solver.algorithm, Algorithm::Enumerative)
+            || solver.all
+            || solver.max_errors.is_some()
+        {
+            println!("Lasso samples are only supported by the enumerative search");
             return Ok(());
         }
-        None => {
-            println!("File format missing");
+        let Some(sample) = load::<LassoSample>(path, &contents)? else {
             return Ok(());
-        }
+        };
+        println!(
+            "Loaded lasso sample with {} variables, {} positive and {} negative traces",
+            sample.num_vars(),
+            sample.positive_traces(),
+            sample.negative_traces(),
+        );
+        return match solve_lasso(&sample, &options) {
+            Outcome::Solved(formula) => report(formula, sample.var_names, solver.output),
+            outcome => {
+                report_failure(outcome);
+                Ok(())
+            }
+        };
+    }
+
+    let Some(sample) = load::<DynSample>(path, &contents)? else {
+        return Ok(());
     };
 
-    if let Some(solution) = load_and_solve(contents, solver.multithread) {
-        println!("Solution: {}", solution);
+    println!(
+        "Loaded sample with {} variables, {} positive and {} negative traces",
+        sample.num_vars(),
+        sample.positive_traces(),
+        sample.negative_traces(),
+    );
+
+    if let Some(formula) = &solver.check {
+        let formula = match SyntaxTree::parse_w_named_vars(formula, &sample.var_names) {
+            Ok(formula) => formula,
+            Err(err) => {
+                println!("Invalid formula: {}", err);
+                return Ok(());
+            }
+        };
+        let misclassified = sample.misclassified(&formula);
+        if misclassified.is_empty() {
+            println!("The formula is consistent with the sample");
+        } else {
+            report_misclassified(&sample, &formula, &misclassified, solver.explain);
+        }
+        return Ok(());
+    }
+
+    if solver.all {
+        if !matches!(solver.algorithm, Algorithm::Enumerative) || solver.max_errors.is_some() {
+            println!("Finding all solutions is only supported by the enumerative search");
+            return Ok(());
+        }
+        return match solve_all(&sample, solver.extra_size, &options) {
+            Outcome::Solved(solutions) => {
+                if let Some(size) = solutions.truncated_at {
+                    println!(
+                        "Search stopped while searching formulae of size {}: some solutions may be missing",
+                        size
+                    );
+                }
+                let solutions = solutions
+                    .formulae
+                    .into_iter()
+                    .map(|formula| NamedFormula::new(formula, sample.var_names.clone()))
+                    .collect::<Vec<_>>();
+                println!("Solutions:");
+                for solution in &solutions {
+                    println!("{}", solution);
+                }
+                match solver.output {
+                    Some(output) => save_solution(Path::new(&output), &solutions),
+                    None => Ok(()),
+                }
+            }
+            outcome => {
+                report_failure(outcome);
+                Ok(())
+            }
+        };
+    }
+
+    let outcome = if let Some(budget) = solver.max_errors {
+        if !matches!(solver.algorithm, Algorithm::Enumerative) {
+            println!(
+                "Learning with misclassified traces is only supported by the enumerative search"
+            );
+            return Ok(());
+        }
+        match solve_approx(&sample, budget, &options) {
+            Outcome::Unsolvable => {
+                println!(
+                    "No solution found: every formula misclassifies at least {} traces",
+                    sample.min_errors()
+                );
+                return Ok(());
+            }
+            outcome => outcome.map(|approx| {
+                report_misclassified(
+                    &sample,
+                    &approx.formula,
+                    &approx.misclassified,
+                    solver.explain,
+                );
+                approx.formula
+            }),
+        }
     } else {
-        println!("No solution found");
+        match solver.algorithm {
+            Algorithm::Enumerative => solve(&sample, &options),
+            Algorithm::BottomUp => solve_bottom_up(&sample, &options),
+            Algorithm::Sat => solve_sat(&sample, &options),
+        }
+    };
+
+    match outcome {
+        Outcome::Solved(formula) => report(formula, sample.var_names, solver.output),
+        outcome => {
+            report_failure(outcome);
+            Ok(())
+        }
     }
+}
 
-    Ok(())
+fn report_failure<T>(outcome: Outcome<T>) {
+    match outcome {
+        Outcome::Solved(_) => {}
+        Outcome::Unsolvable => println!("No solution found"),
+        Outcome::Exhausted { max_size } => {
+            println!("No solution found up to size {}", max_size)
+        }
+        Outcome::TimedOut { size } => {
+            println!("Timed out while searching formulae of size {}", size)
+        }
+        Outcome::Cancelled { size } => {
+            println!("Cancelled while searching formulae of size {}", size)
+        }
+    }
 }
 
-fn ron_load_and_solve(contents: Vec<u8>, multithread: bool) -> Option<String> {
-    // Ugly hack to get around limitations of deserialization for types with const generics.
-    // See https://github.com/serde-rs/serde/issues/1937
-    (1..).into_iter().find_map(|n| {
-        match n {
-            0 => ron::de::from_bytes::<Sample<0>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            1 => ron::de::from_bytes::<Sample<1>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            2 => ron::de::from_bytes::<Sample<2>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            3 => ron::de::from_bytes::<Sample<3>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            4 => ron::de::from_bytes::<Sample<4>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            5 => ron::de::from_bytes::<Sample<5>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            6 => ron::de::from_bytes::<Sample<6>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            7 => ron::de::from_bytes::<Sample<7>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            8 => ron::de::from_bytes::<Sample<8>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            9 => ron::de::from_bytes::<Sample<9>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            10 => ron::de::from_bytes::<Sample<10>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            11 => ron::de::from_bytes::<Sample<11>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            12 => ron::de::from_bytes::<Sample<12>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            13 => ron::de::from_bytes::<Sample<13>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            14 => ron::de::from_bytes::<Sample<14>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            15 => ron::de::from_bytes::<Sample<15>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            16 => ron::de::from_bytes::<Sample<16>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            17 => ron::de::from_bytes::<Sample<17>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            18 => ron::de::from_bytes::<Sample<18>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            19 => ron::de::from_bytes::<Sample<19>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            20 => ron::de::from_bytes::<Sample<20>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            21 => ron::de::from_bytes::<Sample<21>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            22 => ron::de::from_bytes::<Sample<22>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            23 => ron::de::from_bytes::<Sample<23>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            24 => ron::de::from_bytes::<Sample<24>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            25 => ron::de::from_bytes::<Sample<25>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            26 => ron::de::from_bytes::<Sample<26>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            27 => ron::de::from_bytes::<Sample<27>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            28 => ron::de::from_bytes::<Sample<28>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            29 => ron::de::from_bytes::<Sample<29>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            30 => ron::de::from_bytes::<Sample<30>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            31 => ron::de::from_bytes::<Sample<31>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            32 => ron::de::from_bytes::<Sample<32>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            33 => ron::de::from_bytes::<Sample<33>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            34 => ron::de::from_bytes::<Sample<34>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            35 => ron::de::from_bytes::<Sample<35>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            36 => ron::de::from_bytes::<Sample<36>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            37 => ron::de::from_bytes::<Sample<37>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            38 => ron::de::from_bytes::<Sample<38>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            _ => panic!("out-of-bound parameter"),
+fn report_misclassified(
+    sample: &DynSample,
+    formula: &SyntaxTree,
+    misclassified: &Misclassified,
+    explain: bool,
+) {
+    println!(
+        "Misclassified {} positive traces {:?} and {} negative traces {:?}",
+        misclassified.positives.len(),
+        misclassified.positives,
+        misclassified.negatives.len(),
+        misclassified.negatives,
+    );
+    if explain {
+        for (label, traces, indexes) in [
+            ("positive", sample.positives(), &misclassified.positives),
+            ("negative", sample.negatives(), &misclassified.negatives),
+        ] {
+            for &index in indexes {
+                let explanation = formula.explain(&traces[index]);
+                println!(
+                    "Explanation for {} trace {}:\n{}",
+                    label,
+                    index,
+                    explanation.print_w_named_vars(&sample.var_names)
+                );
+            }
         }
-        .ok()
-    })
+    }
 }
 
-fn json_load_and_solve(contents: Vec<u8>, multithread: bool) -> Option<String> {
-    // Ugly hack to get around limitations of deserialization for types with const generics.
-    (1..).into_iter().find_map(|n| {
-        match n {
-            0 => serde_json::from_slice::<Sample<0>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            1 => serde_json::from_slice::<Sample<1>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            2 => serde_json::from_slice::<Sample<2>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            3 => serde_json::from_slice::<Sample<3>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            4 => serde_json::from_slice::<Sample<4>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            5 => serde_json::from_slice::<Sample<5>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            6 => serde_json::from_slice::<Sample<6>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            7 => serde_json::from_slice::<Sample<7>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            8 => serde_json::from_slice::<Sample<8>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            9 => serde_json::from_slice::<Sample<9>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            10 => serde_json::from_slice::<Sample<10>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            11 => serde_json::from_slice::<Sample<11>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            12 => serde_json::from_slice::<Sample<12>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            13 => serde_json::from_slice::<Sample<13>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            14 => serde_json::from_slice::<Sample<14>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            15 => serde_json::from_slice::<Sample<15>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            16 => serde_json::from_slice::<Sample<16>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            17 => serde_json::from_slice::<Sample<17>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            18 => serde_json::from_slice::<Sample<18>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            19 => serde_json::from_slice::<Sample<19>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            20 => serde_json::from_slice::<Sample<20>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            21 => serde_json::from_slice::<Sample<21>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            22 => serde_json::from_slice::<Sample<22>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            23 => serde_json::from_slice::<Sample<23>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            24 => serde_json::from_slice::<Sample<24>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            25 => serde_json::from_slice::<Sample<25>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            26 => serde_json::from_slice::<Sample<26>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            27 => serde_json::from_slice::<Sample<27>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            28 => serde_json::from_slice::<Sample<28>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            29 => serde_json::from_slice::<Sample<29>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            30 => serde_json::from_slice::<Sample<30>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            31 => serde_json::from_slice::<Sample<31>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            32 => serde_json::from_slice::<Sample<32>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            33 => serde_json::from_slice::<Sample<33>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            34 => serde_json::from_slice::<Sample<34>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            35 => serde_json::from_slice::<Sample<35>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            36 => serde_json::from_slice::<Sample<36>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            37 => serde_json::from_slice::<Sample<37>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            38 => serde_json::from_slice::<Sample<38>>(&contents).map(|sample| {
-                solve(&sample, multithread, true)
-                    .map(|formula| formula.print_w_named_vars(&sample.var_names))
-                    .unwrap_or("No solution".to_string())
-            }),
-            _ => panic!("out-of-bound parameter"),
+fn parse_timeout(s: &str) -> Result<Duration, String> {
+    let secs: f64 = s.parse().map_err(|err| format!("{err}"))?;
+    Duration::try_from_secs_f64(secs).map_err(|err| format!("{err}"))
+}
+
+/// Deserializes a sample according to the extension of its file, or returns `None` if not supported.
+fn load<T: serde::de::DeserializeOwned>(
+    path: &Path,
+    contents: &[u8],
+) -> std::io::Result<Option<T>> {
+    match path.extension() {
+        Some(ext) if ext == "ron" => ron::de::from_bytes::<T>(contents)
+            .map(Some)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err)),
+        Some(ext) if ext == "json" => Ok(Some(serde_json::from_slice::<T>(contents)?)),
+        Some(ext) => {
+            println!("File format unknown or not supported: {:#?}", ext);
+            Ok(None)
+        }
+        None => {
+            println!("File format missing");
+            Ok(None)
         }
-        .ok()
-    })
+    }
+}
+
+fn report(
+    formula: SyntaxTree,
+    var_names: Vec<String>,
+    output: Option<String>,
+) -> std::io::Result<()> {
+    let solution = NamedFormula::new(formula, var_names);
+    println!("Solution: {}", solution);
+    if let Some(output) = output {
+        save_solution(Path::new(&output), &solution)?;
+    }
+    Ok(())
+}
+
+fn save_solution<T: serde::Serialize>(path: &Path, solution: &T) -> std::io::Result<()> {
+    let contents = match path.extension() {
+        Some(ext) if ext == "ron" => {
+            ron::ser::to_string_pretty(solution, ron::ser::PrettyConfig::default())
+                .map_err(std::io::Error::other)?
+        }
+        Some(ext) if ext == "json" => serde_json::to_string_pretty(solution)?,
+        Some(ext) => {
+            println!("File format unknown or not supported: {:#?}", ext);
+            return Ok(());
+        }
+        None => {
+            println!("File format missing");
+            return Ok(());
+        }
+    };
+    File::create(path)?.write_all(contents.as_bytes())
 }
@@ -1,36 +1,119 @@
-use serde::Deserialize;
-use std::{fmt, sync::Arc};
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr, sync::Arc};
 
-/// The type representing time instants.
-pub type Time = u8;
+/// The type representing time instants (and trace lengths).
+/// It is as wide as `usize`, so that traces of any length can be indexed.
+pub type Time = usize;
 
 /// The type of indexes of propositional variables.
-pub type Idx = u8;
+pub type Idx = u16;
+
+/// A closed interval `[start, end]` of time instants, relative to the current one,
+/// within which bounded temporal operators look for their arguments.
+#[derive(Debug, Clone, Copy, PartialOrd, Ord, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "IntervalRepr")]
+pub struct Interval {
+    pub start: Time,
+    pub end: Time,
+}
+
+/// An interval as deserialized, before checking that it is not empty.
+#[derive(Deserialize)]
+#[serde(rename = "Interval")]
+struct IntervalRepr {
+    start: Time,
+    end: Time,
+}
+
+impl TryFrom<IntervalRepr> for Interval {
+    type Error = String;
+
+    fn try_from(IntervalRepr { start, end }: IntervalRepr) -> Result<Self, Self::Error> {
+        if start <= end {
+            Ok(Interval { start, end })
+        } else {
+            Err(format!("interval [{start},{end}] is empty"))
+        }
+    }
+}
+
+impl Interval {
+    pub fn new(start: Time, end: Time) -> Self {
+        assert!(start <= end, "interval [{start},{end}] is empty");
+        Interval { start, end }
+    }
+
+    /// The time instants of a trace of the given length falling in the interval, relative to `time`.
+    /// The range is empty (but still usable to slice the trace) if the interval starts past the end of the trace.
+    pub(crate) fn window(self, time: Time, len: Time) -> std::ops::RangeInclusive<Time> {
+        // Bounds can be as large as `Time::MAX`, so the additions saturate rather than overflow.
+        time.saturating_add(self.start).min(len)
+            ..=time.saturating_add(self.end).min(len.saturating_sub(1))
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{},{}]", self.start, self.end)
+    }
+}
 
 /// A formula represented via its syntax tree.
 /// This is a recursive data structure, so it requires the use of smart pointers.
 /// We use `Arc` to make it compatible with parallel computations.
-#[derive(Debug, Clone, PartialOrd, Ord, PartialEq, Eq, Deserialize)]
+#[derive(Debug, Clone, PartialOrd, Ord, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum SyntaxTree {
     Atom(Idx),
+    /// The constant ⊤, holding at every time instant.
+    True,
+    /// The constant ⊥, holding at no time instant.
+    False,
     Not(Arc<SyntaxTree>),
     Next(Arc<SyntaxTree>),
+    /// Weak next: unlike (strong) `Next`, it holds at the end of the trace.
+    WeakNext(Arc<SyntaxTree>),
     Globally(Arc<SyntaxTree>),
     Finally(Arc<SyntaxTree>),
+    /// Yesterday: φ holds at the previous time instant (so it does not hold at the start of the trace).
+    Yesterday(Arc<SyntaxTree>),
+    /// Once: φ holds at some time instant up to the current one.
+    Once(Arc<SyntaxTree>),
+    /// Historically: φ holds at every time instant up to the current one.
+    Historically(Arc<SyntaxTree>),
+    /// Bounded globally: φ holds at every time instant within the interval (that is still part of the trace).
+    BoundedGlobally(Interval, Arc<SyntaxTree>),
+    /// Bounded finally: φ holds at some time instant within the interval.
+    BoundedFinally(Interval, Arc<SyntaxTree>),
     And(Arc<SyntaxTree>, Arc<SyntaxTree>),
     Or(Arc<SyntaxTree>, Arc<SyntaxTree>),
     Implies(Arc<SyntaxTree>, Arc<SyntaxTree>),
     Until(Arc<SyntaxTree>, Arc<SyntaxTree>),
+    /// φ R ψ: ψ holds up to and including the first time φ holds, or until the end of the trace.
+    Release(Arc<SyntaxTree>, Arc<SyntaxTree>),
+    /// Weak until: unlike (strong) `Until`, it holds if φ holds until the end of the trace.
+    WeakUntil(Arc<SyntaxTree>, Arc<SyntaxTree>),
+    /// φ S ψ: ψ held at some time instant up to the current one, and φ has held ever since.
+    Since(Arc<SyntaxTree>, Arc<SyntaxTree>),
+    /// Bounded until: ψ holds at some time instant within the interval, and φ holds up to then.
+    BoundedUntil(Interval, Arc<SyntaxTree>, Arc<SyntaxTree>),
 }
 
 impl fmt::Display for SyntaxTree {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             SyntaxTree::Atom(var) => write!(f, "x{}", var),
+            SyntaxTree::True => write!(f, "⊤"),
+            SyntaxTree::False => write!(f, "⊥"),
             SyntaxTree::Not(branch) => write!(f, "¬({})", branch),
             SyntaxTree::Next(branch) => write!(f, "X({})", branch),
+            SyntaxTree::WeakNext(branch) => write!(f, "N({})", branch),
             SyntaxTree::Globally(branch) => write!(f, "G({})", branch),
             SyntaxTree::Finally(branch) => write!(f, "F({})", branch),
+            SyntaxTree::Yesterday(branch) => write!(f, "Y({})", branch),
+            SyntaxTree::Once(branch) => write!(f, "O({})", branch),
+            SyntaxTree::Historically(branch) => write!(f, "H({})", branch),
+            SyntaxTree::BoundedGlobally(interval, branch) => write!(f, "G{}({})", interval, branch),
+            SyntaxTree::BoundedFinally(interval, branch) => write!(f, "F{}({})", interval, branch),
             SyntaxTree::And(left_branch, right_branch) => {
                 write!(f, "({})∧({})", left_branch, right_branch)
             }
@@ -43,6 +126,18 @@ impl fmt::Display for SyntaxTree {
             SyntaxTree::Until(left_branch, right_branch) => {
                 write!(f, "({})U({})", left_branch, right_branch)
             }
+            SyntaxTree::Release(left_branch, right_branch) => {
+                write!(f, "({})R({})", left_branch, right_branch)
+            }
+            SyntaxTree::WeakUntil(left_branch, right_branch) => {
+                write!(f, "({})W({})", left_branch, right_branch)
+            }
+            SyntaxTree::Since(left_branch, right_branch) => {
+                write!(f, "({})S({})", left_branch, right_branch)
+            }
+            SyntaxTree::BoundedUntil(interval, left_branch, right_branch) => {
+                write!(f, "({})U{}({})", left_branch, interval, right_branch)
+            }
         }
     }
 }
@@ -50,11 +145,25 @@ impl fmt::Display for SyntaxTree {
 impl SyntaxTree {
     pub fn print_w_named_vars(&self, vars: &[String]) -> String {
         match self {
-            SyntaxTree::Atom(var) => vars[*var as usize].clone(),
+            SyntaxTree::Atom(var) => print_var(&vars[*var as usize]),
+            SyntaxTree::True => String::from("⊤"),
+            SyntaxTree::False => String::from("⊥"),
             SyntaxTree::Not(branch) => format!("¬({})", branch.print_w_named_vars(vars)),
             SyntaxTree::Next(branch) => format!("X({})", branch.print_w_named_vars(vars)),
+            SyntaxTree::WeakNext(branch) => format!("N({})", branch.print_w_named_vars(vars)),
             SyntaxTree::Globally(branch) => format!("G({})", branch.print_w_named_vars(vars)),
             SyntaxTree::Finally(branch) => format!("F({})", branch.print_w_named_vars(vars)),
+            SyntaxTree::Yesterday(branch) => format!("Y({})", branch.print_w_named_vars(vars)),
+            SyntaxTree::Once(branch) => format!("O({})", branch.print_w_named_vars(vars)),
+            SyntaxTree::Historically(branch) => {
+                format!("H({})", branch.print_w_named_vars(vars))
+            }
+            SyntaxTree::BoundedGlobally(interval, branch) => {
+                format!("G{}({})", interval, branch.print_w_named_vars(vars))
+            }
+            SyntaxTree::BoundedFinally(interval, branch) => {
+                format!("F{}({})", interval, branch.print_w_named_vars(vars))
+            }
             SyntaxTree::And(left_branch, right_branch) => {
                 format!(
                     "({})∧({})",
@@ -83,6 +192,62 @@ impl SyntaxTree {
                     right_branch.print_w_named_vars(vars)
                 )
             }
+            SyntaxTree::Release(left_branch, right_branch) => {
+                format!(
+                    "({})R({})",
+                    left_branch.print_w_named_vars(vars),
+                    right_branch.print_w_named_vars(vars)
+                )
+            }
+            SyntaxTree::WeakUntil(left_branch, right_branch) => {
+                format!(
+                    "({})W({})",
+                    left_branch.print_w_named_vars(vars),
+                    right_branch.print_w_named_vars(vars)
+                )
+            }
+            SyntaxTree::Since(left_branch, right_branch) => {
+                format!(
+                    "({})S({})",
+                    left_branch.print_w_named_vars(vars),
+                    right_branch.print_w_named_vars(vars)
+                )
+            }
+            SyntaxTree::BoundedUntil(interval, left_branch, right_branch) => {
+                format!(
+                    "({})U{}({})",
+                    left_branch.print_w_named_vars(vars),
+                    interval,
+                    right_branch.print_w_named_vars(vars)
+                )
+            }
+        }
+    }
+
+    /// Returns the size of the formula, i.e., the number of nodes of its syntax tree.
+    pub fn size(&self) -> usize {
+        match self {
+            SyntaxTree::Atom(_) | SyntaxTree::True | SyntaxTree::False => 1,
+            SyntaxTree::Not(branch)
+            | SyntaxTree::Next(branch)
+            | SyntaxTree::WeakNext(branch)
+            | SyntaxTree::Globally(branch)
+            | SyntaxTree::Finally(branch)
+            | SyntaxTree::Yesterday(branch)
+            | SyntaxTree::Once(branch)
+            | SyntaxTree::Historically(branch)
+            | SyntaxTree::BoundedGlobally(_, branch)
+            | SyntaxTree::BoundedFinally(_, branch) => 1 + branch.size(),
+            SyntaxTree::And(left_branch, right_branch)
+            | SyntaxTree::Or(left_branch, right_branch)
+            | SyntaxTree::Implies(left_branch, right_branch)
+            | SyntaxTree::Until(left_branch, right_branch)
+            | SyntaxTree::Release(left_branch, right_branch)
+            | SyntaxTree::WeakUntil(left_branch, right_branch)
+            | SyntaxTree::Since(left_branch, right_branch)
+            | SyntaxTree::BoundedUntil(_, left_branch, right_branch) => {
+                1 + left_branch.size() + right_branch.size()
+            }
         }
     }
 
@@ -91,34 +256,88 @@ impl SyntaxTree {
     pub fn vars(&self) -> Idx {
         match self {
             SyntaxTree::Atom(n) => *n + 1,
+            SyntaxTree::True | SyntaxTree::False => 0,
             SyntaxTree::Not(branch)
             | SyntaxTree::Next(branch)
+            | SyntaxTree::WeakNext(branch)
             | SyntaxTree::Globally(branch)
-            | SyntaxTree::Finally(branch) => branch.as_ref().vars(),
+            | SyntaxTree::Finally(branch)
+            | SyntaxTree::Yesterday(branch)
+            | SyntaxTree::Once(branch)
+            | SyntaxTree::Historically(branch)
+            | SyntaxTree::BoundedGlobally(_, branch)
+            | SyntaxTree::BoundedFinally(_, branch) => branch.as_ref().vars(),
             SyntaxTree::And(left_branch, right_branch)
             | SyntaxTree::Or(left_branch, right_branch)
             | SyntaxTree::Implies(left_branch, right_branch)
-            | SyntaxTree::Until(left_branch, right_branch) => {
+            | SyntaxTree::Until(left_branch, right_branch)
+            | SyntaxTree::Release(left_branch, right_branch)
+            | SyntaxTree::WeakUntil(left_branch, right_branch)
+            | SyntaxTree::Since(left_branch, right_branch)
+            | SyntaxTree::BoundedUntil(_, left_branch, right_branch) => {
                 left_branch.vars().max(right_branch.vars())
             }
         }
     }
 
+    /// Returns the indexes of the propositional variables appearing in the formula, in increasing order.
+    pub fn used_vars(&self) -> Vec<Idx> {
+        let mut vars = Vec::new();
+        self.collect_vars(&mut vars);
+        vars.sort_unstable();
+        vars.dedup();
+        vars
+    }
+
+    fn collect_vars(&self, vars: &mut Vec<Idx>) {
+        match self {
+            SyntaxTree::Atom(n) => vars.push(*n),
+            SyntaxTree::True | SyntaxTree::False => {}
+            SyntaxTree::Not(branch)
+            | SyntaxTree::Next(branch)
+            | SyntaxTree::WeakNext(branch)
+            | SyntaxTree::Globally(branch)
+            | SyntaxTree::Finally(branch)
+            | SyntaxTree::Yesterday(branch)
+            | SyntaxTree::Once(branch)
+            | SyntaxTree::Historically(branch)
+            | SyntaxTree::BoundedGlobally(_, branch)
+            | SyntaxTree::BoundedFinally(_, branch) => branch.collect_vars(vars),
+            SyntaxTree::And(left_branch, right_branch)
+            | SyntaxTree::Or(left_branch, right_branch)
+            | SyntaxTree::Implies(left_branch, right_branch)
+            | SyntaxTree::Until(left_branch, right_branch)
+            | SyntaxTree::Release(left_branch, right_branch)
+            | SyntaxTree::WeakUntil(left_branch, right_branch)
+            | SyntaxTree::Since(left_branch, right_branch)
+            | SyntaxTree::BoundedUntil(_, left_branch, right_branch) => {
+                left_branch.collect_vars(vars);
+                right_branch.collect_vars(vars);
+            }
+        }
+    }
+
     /// Evaluate a formula on a trace.
-    pub fn eval<const N: usize>(&self, trace: &[[bool; N]]) -> bool {
+    /// The states of the trace can be any tuple of truth values, e.g., `[bool; N]` or `Vec<bool>`.
+    pub fn eval<S: AsRef<[bool]>>(&self, trace: &[S]) -> bool {
         self.eval_at_time(trace, 0)
     }
 
     /// Evaluate a formula on a trace.
-    pub fn eval_at_time<const N: usize>(&self, trace: &[[bool; N]], time: usize) -> bool {
+    pub fn eval_at_time<S: AsRef<[bool]>>(&self, trace: &[S], time: Time) -> bool {
         assert!(time < trace.len());
 
         match self {
-            SyntaxTree::Atom(var) => trace[time][*var as usize],
+            SyntaxTree::Atom(var) => trace[time].as_ref()[*var as usize],
+            SyntaxTree::True => true,
+            SyntaxTree::False => false,
             SyntaxTree::Not(branch) => !branch.eval_at_time(trace, time),
             SyntaxTree::Next(branch) => {
                 time + 1 < trace.len() && branch.eval_at_time(trace, time + 1)
             }
+            SyntaxTree::WeakNext(branch) => {
+                time + 1 == trace.len() || branch.eval_at_time(trace, time + 1)
+            }
             // Globally and Finally are interpreted by reverse temporal order because interpreting on shorter traces is generally faster.
             SyntaxTree::Globally(branch) => (time..trace.len())
                 .rev()
@@ -126,6 +345,15 @@ impl SyntaxTree {
             SyntaxTree::Finally(branch) => (time..trace.len())
                 .rev()
                 .any(|t| branch.eval_at_time(trace, t)),
+            SyntaxTree::Yesterday(branch) => time > 0 && branch.eval_at_time(trace, time - 1),
+            SyntaxTree::Once(branch) => (0..=time).any(|t| branch.eval_at_time(trace, t)),
+            SyntaxTree::Historically(branch) => (0..=time).all(|t| branch.eval_at_time(trace, t)),
+            SyntaxTree::BoundedGlobally(interval, branch) => interval
+                .window(time, trace.len())
+                .all(|t| branch.eval_at_time(trace, t)),
+            SyntaxTree::BoundedFinally(interval, branch) => interval
+                .window(time, trace.len())
+                .any(|t| branch.eval_at_time(trace, t)),
             SyntaxTree::And(left_branch, right_branch) => {
                 left_branch.eval_at_time(trace, time) && right_branch.eval_at_time(trace, time)
             }
@@ -146,118 +374,1821 @@ impl SyntaxTree {
                 // (Strong) Until is not satisfied if its right-hand-side argument never becomes true.
                 false
             }
+            SyntaxTree::Release(left_branch, right_branch) => {
+                for t in time..trace.len() {
+                    if !right_branch.eval_at_time(trace, t) {
+                        return false;
+                    } else if left_branch.eval_at_time(trace, t) {
+                        return true;
+                    }
+                }
+                // Release is satisfied if its right-hand-side argument holds until the end of the trace.
+                true
+            }
+            SyntaxTree::WeakUntil(left_branch, right_branch) => {
+                for t in time..trace.len() {
+                    if right_branch.eval_at_time(trace, t) {
+                        return true;
+                    } else if !left_branch.eval_at_time(trace, t) {
+                        return false;
+                    }
+                }
+                // Weak Until is satisfied if its left-hand-side argument holds until the end of the trace.
+                true
+            }
+            SyntaxTree::Since(left_branch, right_branch) => {
+                for t in (0..=time).rev() {
+                    if right_branch.eval_at_time(trace, t) {
+                        return true;
+                    } else if !left_branch.eval_at_time(trace, t) {
+                        return false;
+                    }
+                }
+                // Since is not satisfied if its right-hand-side argument never held.
+                false
+            }
+            SyntaxTree::BoundedUntil(interval, left_branch, right_branch) => {
+                let window = interval.window(time, trace.len());
+                for t in time..=*window.end() {
+                    if window.contains(&t) && right_branch.eval_at_time(trace, t) {
+                        return true;
+                    } else if !left_branch.eval_at_time(trace, t) {
+                        return false;
+                    }
+                }
+                // As (strong) Until, it is not satisfied if its right-hand-side argument does not become true within the interval.
+                false
+            }
         }
     }
-}
 
-#[cfg(test)]
-mod eval {
-    use super::*;
+    /// Evaluate a formula on a trace at every time instant at once,
+    /// returning the vector of its truth values (one per time instant).
+    ///
+    /// Subformulae are evaluated bottom-up, each one with a single backward pass over the trace,
+    /// so that the cost is linear in both the size of the formula and the length of the trace,
+    /// whereas `eval_at_time` can take quadratic time (or worse, with nested temporal operators).
+    /// Bounded operators are the exception: working on a copy of the values of their arguments,
+    /// they take a shifted pass for each time instant of their interval (up to the length of the trace),
+    /// so that their cost is proportional to their interval as well.
+    /// Truth values are packed in bitsets, so that up to 64 time instants are processed at once.
+    pub fn eval_all_times<S: AsRef<[bool]>>(&self, trace: &[S]) -> Vec<bool> {
+        let words = words(trace.len());
+        let mut buffer = vec![0; words * self.eval_buffers()];
+        self.eval_all_times_in(trace, &mut buffer);
+        (0..trace.len())
+            .map(|time| buffer[time / 64] & (1 << (time % 64)) != 0)
+            .collect()
+    }
 
-    const ATOM_0: SyntaxTree = SyntaxTree::Atom(0);
+    /// The number of bitsets needed by `eval_all_times_in` to evaluate the formula.
+    pub(crate) fn eval_buffers(&self) -> usize {
+        match self {
+            SyntaxTree::Atom(_) | SyntaxTree::True | SyntaxTree::False => 1,
+            SyntaxTree::Not(branch)
+            | SyntaxTree::Next(branch)
+            | SyntaxTree::WeakNext(branch)
+            | SyntaxTree::Globally(branch)
+            | SyntaxTree::Finally(branch)
+            | SyntaxTree::Yesterday(branch)
+            | SyntaxTree::Once(branch)
+            | SyntaxTree::Historically(branch)
+            | SyntaxTree::BoundedGlobally(_, branch)
+            | SyntaxTree::BoundedFinally(_, branch) => branch.eval_buffers(),
+            SyntaxTree::And(left_branch, right_branch)
+            | SyntaxTree::Or(left_branch, right_branch)
+            | SyntaxTree::Implies(left_branch, right_branch)
+            | SyntaxTree::Until(left_branch, right_branch)
+            | SyntaxTree::Release(left_branch, right_branch)
+            | SyntaxTree::WeakUntil(left_branch, right_branch)
+            | SyntaxTree::Since(left_branch, right_branch)
+            | SyntaxTree::BoundedUntil(_, left_branch, right_branch) => left_branch
+                .eval_buffers()
+                .max(1 + right_branch.eval_buffers()),
+        }
+    }
 
-    const ATOM_1: SyntaxTree = SyntaxTree::Atom(1);
+    /// Writes the truth values of the formula at every time instant, as a bitset, into the first `words(trace.trace_len())` elements of `buffer`,
+    /// using the rest of it as scratch space.
+    /// `buffer` has to be at least `words(trace.trace_len()) * self.eval_buffers()` long.
+    pub(crate) fn eval_all_times_in<T: AtomValues + ?Sized>(&self, trace: &T, buffer: &mut [u64]) {
+        let len = trace.trace_len();
+        let words = words(len);
+        match self {
+            SyntaxTree::Atom(var) => trace.atom_values(*var, &mut buffer[..words]),
+            SyntaxTree::True => {
+                buffer[..words].fill(u64::MAX);
+                mask_last(&mut buffer[..words], len);
+            }
+            SyntaxTree::False => buffer[..words].fill(0),
+            SyntaxTree::Not(branch) => {
+                branch.eval_all_times_in(trace, buffer);
+                not_in_place(&mut buffer[..words], len);
+            }
+            SyntaxTree::Next(branch) => {
+                branch.eval_all_times_in(trace, buffer);
+                next_in_place(&mut buffer[..words]);
+            }
+            SyntaxTree::WeakNext(branch) => {
+                branch.eval_all_times_in(trace, buffer);
+                weak_next_in_place(&mut buffer[..words], len);
+            }
+            SyntaxTree::Globally(branch) => {
+                branch.eval_all_times_in(trace, buffer);
+                globally_in_place(&mut buffer[..words], len);
+            }
+            SyntaxTree::Finally(branch) => {
+                branch.eval_all_times_in(trace, buffer);
+                finally_in_place(&mut buffer[..words]);
+            }
+            SyntaxTree::Yesterday(branch) => {
+                branch.eval_all_times_in(trace, buffer);
+                yesterday_in_place(&mut buffer[..words], len);
+            }
+            SyntaxTree::Once(branch) => {
+                branch.eval_all_times_in(trace, buffer);
+                once_in_place(&mut buffer[..words], len);
+            }
+            SyntaxTree::Historically(branch) => {
+                branch.eval_all_times_in(trace, buffer);
+                historically_in_place(&mut buffer[..words], len);
+            }
+            SyntaxTree::BoundedGlobally(interval, branch) => {
+                branch.eval_all_times_in(trace, buffer);
+                bounded_globally_in_place(&mut buffer[..words], len, *interval);
+            }
+            SyntaxTree::BoundedFinally(interval, branch) => {
+                branch.eval_all_times_in(trace, buffer);
+                bounded_finally_in_place(&mut buffer[..words], len, *interval);
+            }
+            SyntaxTree::And(left_branch, right_branch) => {
+                left_branch.eval_all_times_in(trace, buffer);
+                let (values, scratch) = buffer.split_at_mut(words);
+                right_branch.eval_all_times_in(trace, scratch);
+                and_in_place(values, scratch);
+            }
+            SyntaxTree::Or(left_branch, right_branch) => {
+                left_branch.eval_all_times_in(trace, buffer);
+                let (values, scratch) = buffer.split_at_mut(words);
+                right_branch.eval_all_times_in(trace, scratch);
+                or_in_place(values, scratch);
+            }
+            SyntaxTree::Implies(left_branch, right_branch) => {
+                left_branch.eval_all_times_in(trace, buffer);
+                let (values, scratch) = buffer.split_at_mut(words);
+                right_branch.eval_all_times_in(trace, scratch);
+                implies_in_place(values, scratch, len);
+            }
+            SyntaxTree::Until(left_branch, right_branch) => {
+                left_branch.eval_all_times_in(trace, buffer);
+                let (values, scratch) = buffer.split_at_mut(words);
+                right_branch.eval_all_times_in(trace, scratch);
+                until_in_place(values, &mut scratch[..words]);
+                values.copy_from_slice(&scratch[..words]);
+            }
+            SyntaxTree::Release(left_branch, right_branch) => {
+                left_branch.eval_all_times_in(trace, buffer);
+                let (values, scratch) = buffer.split_at_mut(words);
+                right_branch.eval_all_times_in(trace, scratch);
+                release_in_place(values, &scratch[..words], len);
+            }
+            SyntaxTree::WeakUntil(left_branch, right_branch) => {
+                left_branch.eval_all_times_in(trace, buffer);
+                let (values, scratch) = buffer.split_at_mut(words);
+                right_branch.eval_all_times_in(trace, scratch);
+                weak_until_in_place(values, &mut scratch[..words], len);
+                values.copy_from_slice(&scratch[..words]);
+            }
+            SyntaxTree::Since(left_branch, right_branch) => {
+                left_branch.eval_all_times_in(trace, buffer);
+                let (values, scratch) = buffer.split_at_mut(words);
+                right_branch.eval_all_times_in(trace, scratch);
+                since_in_place(values, &mut scratch[..words], len);
+                values.copy_from_slice(&scratch[..words]);
+            }
+            SyntaxTree::BoundedUntil(interval, left_branch, right_branch) => {
+                left_branch.eval_all_times_in(trace, buffer);
+                let (values, scratch) = buffer.split_at_mut(words);
+                right_branch.eval_all_times_in(trace, scratch);
+                bounded_until_in_place(values, &mut scratch[..words], len, *interval);
+                values.copy_from_slice(&scratch[..words]);
+            }
+        }
+    }
+}
 
-    #[test]
-    fn atomic_prop() {
-        let trace = [[true]];
-        assert!(ATOM_0.eval(&trace));
+/// A trace providing the truth values of its propositional variables at every time instant, packed in bitsets.
+pub(crate) trait AtomValues {
+    fn trace_len(&self) -> usize;
 
-        let trace = [[false]];
-        assert!(!ATOM_0.eval(&trace));
+    /// Writes the truth values of the given variable into `values`, which is `words(self.trace_len())` long.
+    fn atom_values(&self, var: Idx, values: &mut [u64]);
+}
 
-        // let trace: [[bool; 1]; 0] = [];
-        // assert!(!ATOM_0.eval(&trace));
+impl<S: AsRef<[bool]>> AtomValues for [S] {
+    fn trace_len(&self) -> usize {
+        self.len()
     }
 
-    #[test]
-    fn not() {
-        let formula = SyntaxTree::Not(Arc::new(ATOM_0));
+    fn atom_values(&self, var: Idx, values: &mut [u64]) {
+        values.fill(0);
+        for (time, state) in self.iter().enumerate() {
+            values[time / 64] |= (state.as_ref()[var as usize] as u64) << (time % 64);
+        }
+    }
+}
 
-        let trace = [[false]];
-        assert!(formula.eval(&trace));
+// The following functions compute the truth values of a formula over a trace at every time instant,
+// given those of its arguments (which are overwritten by the result).
+// Truth values are packed in bitsets: the value at time `t` is bit `t % 64` of word `t / 64`.
+// Bits past the end of the trace are always unset.
 
-        let trace = [[true]];
-        assert!(!formula.eval(&trace));
+/// The number of words of a bitset holding the truth values over a trace of the given length.
+pub(crate) fn words(len: usize) -> usize {
+    len.div_ceil(64)
+}
+
+// Unsets the bits past the end of the trace.
+fn mask_last(values: &mut [u64], len: usize) {
+    if !len.is_multiple_of(64) {
+        if let Some(last) = values.last_mut() {
+            *last &= (1 << (len % 64)) - 1;
+        }
     }
+}
 
-    #[test]
-    fn next() {
-        let formula = SyntaxTree::Next(Arc::new(ATOM_0));
+pub(crate) fn not_in_place(values: &mut [u64], len: usize) {
+    values.iter_mut().for_each(|val| *val = !*val);
+    mask_last(values, len);
+}
 
-        let trace = [[false], [true]];
-        assert!(formula.eval(&trace));
+pub(crate) fn next_in_place(values: &mut [u64]) {
+    for word in 0..values.len() {
+        // There is no next time instant at the end of the trace, where a zero is shifted in.
+        let carry = values.get(word + 1).map_or(0, |next| next << 63);
+        values[word] = (values[word] >> 1) | carry;
+    }
+}
 
-        let trace = [[true], [false]];
-        assert!(!formula.eval(&trace));
+// Weak next is the same as (strong) next, but it holds at the end of the trace.
+pub(crate) fn weak_next_in_place(values: &mut [u64], len: usize) {
+    next_in_place(values);
+    if len > 0 {
+        values[(len - 1) / 64] |= 1 << ((len - 1) % 64);
     }
+}
 
-    #[test]
-    fn globally() {
-        let formula = SyntaxTree::Globally(Arc::new(ATOM_0));
+pub(crate) fn finally_in_place(values: &mut [u64]) {
+    let mut carry = false;
+    for val in values.iter_mut().rev() {
+        if carry {
+            *val = !0;
+        } else {
+            // Propagate each set bit to all the lower ones.
+            for shift in [1, 2, 4, 8, 16, 32] {
+                *val |= *val >> shift;
+            }
+            carry = *val != 0;
+        }
+    }
+}
 
-        let trace = [[true], [true], [true]];
-        assert!(formula.eval(&trace));
+// G φ ≡ ¬ F ¬ φ
+pub(crate) fn globally_in_place(values: &mut [u64], len: usize) {
+    not_in_place(values, len);
+    finally_in_place(values);
+    not_in_place(values, len);
+}
 
-        let trace = [[true], [false], [true]];
-        assert!(!formula.eval(&trace));
+pub(crate) fn yesterday_in_place(values: &mut [u64], len: usize) {
+    for word in (0..values.len()).rev() {
+        // There is no previous time instant at the start of the trace, where a zero is shifted in.
+        let carry = word.checked_sub(1).map_or(0, |prev| values[prev] >> 63);
+        values[word] = (values[word] << 1) | carry;
+    }
+    mask_last(values, len);
+}
 
-        // // Not even Globally can be true at moment 0 on an empty trace
-        // let trace: [[bool; 1]; 0] = [];
-        // assert!(!formula.eval(&trace));
+pub(crate) fn once_in_place(values: &mut [u64], len: usize) {
+    let mut carry = false;
+    for val in values.iter_mut() {
+        if carry {
+            *val = !0;
+        } else {
+            // Propagate each set bit to all the higher ones.
+            for shift in [1, 2, 4, 8, 16, 32] {
+                *val |= *val << shift;
+            }
+            carry = *val != 0;
+        }
     }
+    mask_last(values, len);
+}
 
-    #[test]
-    fn finally() {
-        let formula = SyntaxTree::Finally(Arc::new(ATOM_0));
+// H φ ≡ ¬ O ¬ φ
+pub(crate) fn historically_in_place(values: &mut [u64], len: usize) {
+    not_in_place(values, len);
+    once_in_place(values, len);
+    not_in_place(values, len);
+}
 
-        let trace = [[false], [false], [true]];
-        assert!(formula.eval(&trace));
+pub(crate) fn and_in_place(left_values: &mut [u64], right_values: &[u64]) {
+    left_values
+        .iter_mut()
+        .zip(right_values)
+        .for_each(|(left, right)| *left &= right);
+}
 
-        let trace = [[false], [true], [false]];
-        assert!(formula.eval(&trace));
+pub(crate) fn or_in_place(left_values: &mut [u64], right_values: &[u64]) {
+    left_values
+        .iter_mut()
+        .zip(right_values)
+        .for_each(|(left, right)| *left |= right);
+}
 
-        let trace = [[false], [false], [false]];
-        assert!(!formula.eval(&trace));
+pub(crate) fn implies_in_place(left_values: &mut [u64], right_values: &[u64], len: usize) {
+    left_values
+        .iter_mut()
+        .zip(right_values)
+        .for_each(|(left, right)| *left = !*left | right);
+    mask_last(left_values, len);
+}
+
+// φ U ψ holds at time t iff ψ holds at t, or φ holds at t and φ U ψ holds at t + 1.
+// At the end of the trace, (strong) Until holds iff ψ does.
+// Within a word, the recurrence is solved by parallel prefix, as carries in a carry-lookahead adder.
+pub(crate) fn until_in_place(left_values: &[u64], right_values: &mut [u64]) {
+    let mut carry = 0;
+    for (left, right) in left_values.iter().zip(right_values.iter_mut()).rev() {
+        let mut propagate = *left;
+        let mut generate = *right | (propagate & (carry << 63));
+        for shift in [1, 2, 4, 8, 16, 32] {
+            generate |= propagate & (generate >> shift);
+            propagate &= propagate >> shift;
+        }
+        *right = generate;
+        carry = generate & 1;
     }
+}
 
-    #[test]
-    fn and() {
-        let formula = SyntaxTree::And(Arc::new(ATOM_0), Arc::new(ATOM_1));
+// φ W ψ holds at time t iff ψ holds at t, or φ holds at t and φ W ψ holds at t + 1,
+// as for (strong) Until, but at the end of the trace it holds iff either φ or ψ does.
+// Thus, it is computed as φ U ψ', where ψ' is ψ ∨ φ at the end of the trace, and ψ elsewhere.
+pub(crate) fn weak_until_in_place(left_values: &[u64], right_values: &mut [u64], len: usize) {
+    if len > 0 {
+        let last = (len - 1) / 64;
+        right_values[last] |= left_values[last] & (1 << ((len - 1) % 64));
+    }
+    until_in_place(left_values, right_values);
+}
 
-        let trace = [[true, true]];
-        assert!(formula.eval(&trace));
+// φ R ψ ≡ ψ W (φ ∧ ψ), with the result taking the place of the left operand.
+pub(crate) fn release_in_place(left_values: &mut [u64], right_values: &[u64], len: usize) {
+    and_in_place(left_values, right_values);
+    weak_until_in_place(right_values, left_values, len);
+}
 
-        let trace = [[true, false]];
-        assert!(!formula.eval(&trace));
+// φ S ψ holds at time t iff ψ holds at t, or φ holds at t and φ S ψ holds at t - 1.
+// At the start of the trace, Since holds iff ψ does.
+// This is the same recurrence as for (strong) Until, with time reversed.
+pub(crate) fn since_in_place(left_values: &[u64], right_values: &mut [u64], len: usize) {
+    let mut carry = 0;
+    for (left, right) in left_values.iter().zip(right_values.iter_mut()) {
+        let mut propagate = *left;
+        let mut generate = *right | (propagate & carry);
+        for shift in [1, 2, 4, 8, 16, 32] {
+            generate |= propagate & (generate << shift);
+            propagate &= propagate << shift;
+        }
+        *right = generate;
+        carry = generate >> 63;
     }
+    mask_last(right_values, len);
+}
 
-    #[test]
-    fn or() {
-        let formula = SyntaxTree::Or(Arc::new(ATOM_0), Arc::new(ATOM_1));
-
-        let trace = [[true, false]];
-        assert!(formula.eval(&trace));
+// Writes into `shifted` the truth values `offset` time instants later,
+// so that bit t of the result is bit t + offset of `values` (unset past the end of the trace).
+fn shift_earlier(values: &[u64], offset: usize, shifted: &mut [u64]) {
+    let (words, bits) = (offset / 64, offset % 64);
+    for (word, val) in shifted.iter_mut().enumerate() {
+        let low = values.get(word + words).map_or(0, |next| next >> bits);
+        let high = match bits {
+            0 => 0,
+            _ => values
+                .get(word + words + 1)
+                .map_or(0, |next| next << (64 - bits)),
+        };
+        *val = low | high;
+    }
+}
 
-        let trace = [[false, false]];
-        assert!(!formula.eval(&trace));
+// F[a,b] φ is the disjunction of the values of φ shifted by every offset in the interval.
+// Offsets reaching past the end of the trace contribute nothing, so they are skipped.
+pub(crate) fn bounded_finally_in_place(values: &mut [u64], len: usize, interval: Interval) {
+    let child = values.to_vec();
+    let mut shifted = vec![0; values.len()];
+    values.fill(0);
+    for offset in interval.start..=interval.end.min(len) {
+        shift_earlier(&child, offset, &mut shifted);
+        or_in_place(values, &shifted);
     }
+}
 
-    #[test]
-    fn until() {
-        let formula = SyntaxTree::Until(Arc::new(ATOM_0), Arc::new(ATOM_1));
+// G[a,b] φ ≡ ¬ F[a,b] ¬ φ
+pub(crate) fn bounded_globally_in_place(values: &mut [u64], len: usize, interval: Interval) {
+    not_in_place(values, len);
+    bounded_finally_in_place(values, len, interval);
+    not_in_place(values, len);
+}
 
-        let trace = [[true, false], [false, true], [false, false]];
-        assert!(formula.eval(&trace));
+// φ U[a,b] ψ is the disjunction, over every offset k in the interval,
+// of ψ shifted by k and φ holding at all offsets before k.
+pub(crate) fn bounded_until_in_place(
+    left_values: &[u64],
+    right_values: &mut [u64],
+    len: usize,
+    interval: Interval,
+) {
+    let child = right_values.to_vec();
+    // Whether φ holds at every time instant from the current one up to (excluded) the current offset.
+    let mut prefix = vec![!0; right_values.len()];
+    mask_last(&mut prefix, len);
+    let mut shifted = vec![0; right_values.len()];
+    right_values.fill(0);
+    for offset in 0..=interval.end.min(len) {
+        if offset >= interval.start {
+            shift_earlier(&child, offset, &mut shifted);
+            and_in_place(&mut shifted, &prefix);
+            or_in_place(right_values, &shifted);
+        }
+        shift_earlier(left_values, offset, &mut shifted);
+        and_in_place(&mut prefix, &shifted);
+    }
+}
 
-        let trace = [[true, false], [true, false], [false, false]];
-        assert!(!formula.eval(&trace));
+/// A formula together with the names of the variables it has been learned against.
+/// It is serialized in a compact form, with the formula written in the notation of `print_w_named_vars`,
+/// so that it can be stored, read and diffed by humans and parsed back by `parse_w_named_vars`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NamedFormula {
+    pub var_names: Vec<String>,
+    pub formula: SyntaxTree,
+}
 
-        // Until is not satisfied if its right-hand-side argument never becomes true.
-        let trace = [[true, false], [true, false], [true, false]];
-        assert!(!formula.eval(&trace));
+impl NamedFormula {
+    pub fn new(formula: SyntaxTree, var_names: Vec<String>) -> Self {
+        assert!(
+            (formula.vars() as usize) <= var_names.len(),
+            "not enough variable names for the formula"
+        );
+        NamedFormula { var_names, formula }
+    }
+}
 
-        // let trace: [[bool; 2]; 0] = [];
+impl fmt::Display for NamedFormula {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.formula.print_w_named_vars(&self.var_names))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "NamedFormula")]
+struct NamedFormulaRepr {
+    var_names: Vec<String>,
+    formula: String,
+}
+
+impl Serialize for NamedFormula {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        NamedFormulaRepr {
+            var_names: self.var_names.clone(),
+            formula: self.to_string(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for NamedFormula {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = NamedFormulaRepr::deserialize(deserializer)?;
+        let formula = SyntaxTree::parse_w_named_vars(&repr.formula, &repr.var_names)
+            .map_err(serde::de::Error::custom)?;
+        Ok(NamedFormula {
+            var_names: repr.var_names,
+            formula,
+        })
+    }
+}
+
+/// The kind of error encountered while parsing a formula.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// A character that is not part of the formula syntax.
+    UnexpectedChar(char),
+    /// A token that is not allowed at its position.
+    UnexpectedToken(String),
+    /// The input ended while a formula was still expected.
+    UnexpectedEnd,
+    /// A parenthesis was opened but never closed.
+    UnclosedParen,
+    /// A quoted variable name was opened but never closed.
+    UnclosedQuote,
+    /// A name that does not correspond to any known propositional variable.
+    UnknownVariable(String),
+    /// An interval whose bounds are not numbers, or whose start comes after its end.
+    InvalidInterval(String),
+}
+
+/// An error encountered while parsing a formula,
+/// with the position (in characters, starting from 0) at which it occurred.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub position: usize,
+    pub kind: ParseErrorKind,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            ParseErrorKind::UnexpectedChar(c) => write!(f, "unexpected character '{c}'"),
+            ParseErrorKind::UnexpectedToken(token) => write!(f, "unexpected '{token}'"),
+            ParseErrorKind::UnexpectedEnd => write!(f, "unexpected end of formula"),
+            ParseErrorKind::UnclosedParen => write!(f, "unclosed parenthesis"),
+            ParseErrorKind::UnclosedQuote => write!(f, "unclosed quote"),
+            ParseErrorKind::UnknownVariable(name) => write!(f, "unknown variable '{name}'"),
+            ParseErrorKind::InvalidInterval(interval) => write!(f, "invalid interval '{interval}'"),
+        }?;
+        write!(f, " at position {}", self.position)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl FromStr for SyntaxTree {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SyntaxTree::parse(s)
+    }
+}
+
+impl SyntaxTree {
+    /// Parses a formula written in the notation produced by `Display`,
+    /// where variables are written as `x0`, `x1`, ...
+    ///
+    /// Both the Unicode operators (`¬`, `∧`, `∨`, `→`) and their ASCII alternatives (`!`, `&`, `|`, `->`) are accepted,
+    /// together with the temporal operators `X`, `N` (weak next), `F`, `G`, `U`, `R` (release) and `W` (weak until),
+    /// and the past ones `Y` (yesterday), `O` (once), `H` (historically) and `S` (since).
+    /// The constants are written as `⊤` and `⊥`, or `true` and `false`.
+    /// Bounded operators are written with their interval right after them, such as `F[0,5]` or `U[1,3]`.
+    /// Parentheses are optional where precedence resolves the ambiguity:
+    /// unary operators bind tighter than `U`, `R`, `W` and `S`, which bind tighter than `∧`, then `∨` and finally `→`.
+    pub fn parse(s: &str) -> Result<SyntaxTree, ParseError> {
+        Parser::new(s, None)?.parse()
+    }
+
+    /// Parses a formula written in the notation produced by `print_w_named_vars`,
+    /// resolving variables against the given names (e.g., the `var_names` of a `Sample`).
+    /// Names that are not made of alphanumeric characters, `_`, `~` and `.` only are written between double quotes,
+    /// such as `G("door-open")`, with `\"` and `\\` standing for a quote and a backslash.
+    pub fn parse_w_named_vars(s: &str, vars: &[String]) -> Result<SyntaxTree, ParseError> {
+        Parser::new(s, Some(vars))?.parse()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+    Comma,
+    Not,
+    And,
+    Or,
+    Implies,
+    True,
+    False,
+    Ident(String),
+    /// A variable name between quotes, which is never taken for an operator or a constant.
+    Quoted(String),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::LParen => write!(f, "("),
+            Token::RParen => write!(f, ")"),
+            Token::LBracket => write!(f, "["),
+            Token::RBracket => write!(f, "]"),
+            Token::Comma => write!(f, ","),
+            Token::Not => write!(f, "¬"),
+            Token::And => write!(f, "∧"),
+            Token::Or => write!(f, "∨"),
+            Token::Implies => write!(f, "→"),
+            Token::True => write!(f, "⊤"),
+            Token::False => write!(f, "⊥"),
+            Token::Ident(name) => write!(f, "{name}"),
+            Token::Quoted(name) => write!(f, "{}", quote(name)),
+        }
+    }
+}
+
+/// Splits the input into tokens, each paired with its position.
+fn tokenize(s: &str) -> Result<Vec<(usize, Token)>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = s.chars().enumerate().peekable();
+
+    while let Some((position, c)) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '(' => Token::LParen,
+            ')' => Token::RParen,
+            '[' => Token::LBracket,
+            ']' => Token::RBracket,
+            ',' => Token::Comma,
+            '¬' | '!' => Token::Not,
+            '∧' => Token::And,
+            '∨' => Token::Or,
+            '→' => Token::Implies,
+            '⊤' => Token::True,
+            '⊥' => Token::False,
+            // Accept both `&` and `&&`, and both `|` and `||`.
+            '&' => {
+                chars.next_if(|(_, c)| *c == '&');
+                Token::And
+            }
+            '|' => {
+                chars.next_if(|(_, c)| *c == '|');
+                Token::Or
+            }
+            '-' => {
+                if chars.next_if(|(_, c)| *c == '>').is_some() {
+                    Token::Implies
+                } else {
+                    return Err(ParseError {
+                        position,
+                        kind: ParseErrorKind::UnexpectedChar(c),
+                    });
+                }
+            }
+            '"' => {
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, '"')) => break,
+                        Some((_, '\\')) => match chars.next() {
+                            Some((_, c)) => name.push(c),
+                            None => break,
+                        },
+                        Some((_, c)) => name.push(c),
+                        None => {
+                            return Err(ParseError {
+                                position,
+                                kind: ParseErrorKind::UnclosedQuote,
+                            })
+                        }
+                    }
+                }
+                Token::Quoted(name)
+            }
+            c if is_ident_char(c) => {
+                let mut name = String::from(c);
+                while let Some((_, c)) = chars.next_if(|(_, c)| is_ident_char(*c)) {
+                    name.push(c);
+                }
+                Token::Ident(name)
+            }
+            c => {
+                return Err(ParseError {
+                    position,
+                    kind: ParseErrorKind::UnexpectedChar(c),
+                })
+            }
+        };
+        tokens.push((position, token));
+    }
+
+    Ok(tokens)
+}
+
+// `~` is allowed since it marks discarded variables in a `Sample`.
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '~' || c == '.'
+}
+
+/// Prints a variable name so that it can be parsed back, quoting it if it is not a valid identifier.
+fn print_var(name: &str) -> String {
+    if !name.is_empty() && name.chars().all(is_ident_char) {
+        name.to_string()
+    } else {
+        quote(name)
+    }
+}
+
+fn quote(name: &str) -> String {
+    let mut quoted = String::from('"');
+    for c in name.chars() {
+        if c == '"' || c == '\\' {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Recursive-descent parser with one function per precedence level.
+struct Parser<'a> {
+    tokens: Vec<(usize, Token)>,
+    cursor: usize,
+    end: usize,
+    vars: Option<&'a [String]>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(s: &str, vars: Option<&'a [String]>) -> Result<Self, ParseError> {
+        Ok(Parser {
+            tokens: tokenize(s)?,
+            cursor: 0,
+            end: s.chars().count(),
+            vars,
+        })
+    }
+
+    fn parse(mut self) -> Result<SyntaxTree, ParseError> {
+        let formula = self.parse_implies()?;
+        match self.tokens.get(self.cursor) {
+            None => Ok(formula),
+            Some((position, token)) => Err(ParseError {
+                position: *position,
+                kind: ParseErrorKind::UnexpectedToken(token.to_string()),
+            }),
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.cursor).map(|(_, token)| token)
+    }
+
+    fn peek_ident(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Ident(name)) if name == keyword)
+    }
+
+    fn position(&self) -> usize {
+        self.tokens
+            .get(self.cursor)
+            .map_or(self.end, |(position, _)| *position)
+    }
+
+    // Implication is right-associative: φ → ψ → χ ≡ φ → (ψ → χ)
+    fn parse_implies(&mut self) -> Result<SyntaxTree, ParseError> {
+        let left_branch = self.parse_or()?;
+        if self.peek() == Some(&Token::Implies) {
+            self.cursor += 1;
+            let right_branch = self.parse_implies()?;
+            Ok(SyntaxTree::Implies(
+                Arc::new(left_branch),
+                Arc::new(right_branch),
+            ))
+        } else {
+            Ok(left_branch)
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<SyntaxTree, ParseError> {
+        let mut left_branch = self.parse_and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.cursor += 1;
+            let right_branch = self.parse_and()?;
+            left_branch = SyntaxTree::Or(Arc::new(left_branch), Arc::new(right_branch));
+        }
+        Ok(left_branch)
+    }
+
+    fn parse_and(&mut self) -> Result<SyntaxTree, ParseError> {
+        let mut left_branch = self.parse_until()?;
+        while self.peek() == Some(&Token::And) {
+            self.cursor += 1;
+            let right_branch = self.parse_until()?;
+            left_branch = SyntaxTree::And(Arc::new(left_branch), Arc::new(right_branch));
+        }
+        Ok(left_branch)
+    }
+
+    // Until, Release, Weak Until and Since share the same precedence, and are right-associative: φ U ψ R χ ≡ φ U (ψ R χ)
+    fn parse_until(&mut self) -> Result<SyntaxTree, ParseError> {
+        let left_branch = self.parse_unary()?;
+        if self.peek_ident("U") && self.bracket_follows() {
+            self.cursor += 1;
+            let interval = self.parse_interval()?;
+            let right_branch = self.parse_until()?;
+            return Ok(SyntaxTree::BoundedUntil(
+                interval,
+                Arc::new(left_branch),
+                Arc::new(right_branch),
+            ));
+        }
+        let op = if self.peek_ident("U") {
+            SyntaxTree::Until
+        } else if self.peek_ident("R") {
+            SyntaxTree::Release
+        } else if self.peek_ident("W") {
+            SyntaxTree::WeakUntil
+        } else if self.peek_ident("S") {
+            SyntaxTree::Since
+        } else {
+            return Ok(left_branch);
+        };
+        self.cursor += 1;
+        let right_branch = self.parse_until()?;
+        Ok(op(Arc::new(left_branch), Arc::new(right_branch)))
+    }
+
+    fn parse_unary(&mut self) -> Result<SyntaxTree, ParseError> {
+        let position = self.position();
+        match self.peek().cloned() {
+            None => Err(ParseError {
+                position,
+                kind: ParseErrorKind::UnexpectedEnd,
+            }),
+            Some(Token::Not) => {
+                self.cursor += 1;
+                Ok(SyntaxTree::Not(Arc::new(self.parse_unary()?)))
+            }
+            Some(Token::LParen) => {
+                self.cursor += 1;
+                let formula = self.parse_implies()?;
+                match self.peek() {
+                    Some(Token::RParen) => {
+                        self.cursor += 1;
+                        Ok(formula)
+                    }
+                    Some(token) => Err(ParseError {
+                        position: self.position(),
+                        kind: ParseErrorKind::UnexpectedToken(token.to_string()),
+                    }),
+                    None => Err(ParseError {
+                        position,
+                        kind: ParseErrorKind::UnclosedParen,
+                    }),
+                }
+            }
+            Some(Token::Ident(name))
+                if matches!(name.as_str(), "F" | "G") && self.bracket_follows() =>
+            {
+                self.cursor += 1;
+                let interval = self.parse_interval()?;
+                let branch = Arc::new(self.parse_unary()?);
+                match name.as_str() {
+                    "F" => Ok(SyntaxTree::BoundedFinally(interval, branch)),
+                    "G" => Ok(SyntaxTree::BoundedGlobally(interval, branch)),
+                    _ => unreachable!(),
+                }
+            }
+            // Temporal operators are only recognized as such when followed by an operand,
+            // so that variables can still be named `X`, `N`, `F`, `G`, `Y`, `O` or `H`.
+            Some(Token::Ident(name))
+                if matches!(name.as_str(), "X" | "N" | "F" | "G" | "Y" | "O" | "H")
+                    && self.operand_follows() =>
+            {
+                self.cursor += 1;
+                let branch = Arc::new(self.parse_unary()?);
+                match name.as_str() {
+                    "X" => Ok(SyntaxTree::Next(branch)),
+                    "N" => Ok(SyntaxTree::WeakNext(branch)),
+                    "F" => Ok(SyntaxTree::Finally(branch)),
+                    "G" => Ok(SyntaxTree::Globally(branch)),
+                    "Y" => Ok(SyntaxTree::Yesterday(branch)),
+                    "O" => Ok(SyntaxTree::Once(branch)),
+                    "H" => Ok(SyntaxTree::Historically(branch)),
+                    _ => unreachable!(),
+                }
+            }
+            Some(Token::True) => {
+                self.cursor += 1;
+                Ok(SyntaxTree::True)
+            }
+            Some(Token::False) => {
+                self.cursor += 1;
+                Ok(SyntaxTree::False)
+            }
+            Some(Token::Ident(name)) => {
+                self.cursor += 1;
+                // Variables named `true` or `false` take precedence over the constants.
+                match (self.resolve(&name), name.as_str()) {
+                    (Some(var), _) => Ok(SyntaxTree::Atom(var)),
+                    (None, "true") => Ok(SyntaxTree::True),
+                    (None, "false") => Ok(SyntaxTree::False),
+                    (None, _) => Err(ParseError {
+                        position,
+                        kind: ParseErrorKind::UnknownVariable(name),
+                    }),
+                }
+            }
+            Some(Token::Quoted(name)) => {
+                self.cursor += 1;
+                match self.resolve(&name) {
+                    Some(var) => Ok(SyntaxTree::Atom(var)),
+                    None => Err(ParseError {
+                        position,
+                        kind: ParseErrorKind::UnknownVariable(name),
+                    }),
+                }
+            }
+            Some(token) => Err(ParseError {
+                position,
+                kind: ParseErrorKind::UnexpectedToken(token.to_string()),
+            }),
+        }
+    }
+
+    fn bracket_follows(&self) -> bool {
+        matches!(self.tokens.get(self.cursor + 1), Some((_, Token::LBracket)))
+    }
+
+    // Parses an interval `[start,end]`, starting from its opening bracket.
+    fn parse_interval(&mut self) -> Result<Interval, ParseError> {
+        let position = self.position();
+        self.expect(Token::LBracket)?;
+        let start = self.parse_bound()?;
+        self.expect(Token::Comma)?;
+        let end = self.parse_bound()?;
+        self.expect(Token::RBracket)?;
+        match (start.parse(), end.parse()) {
+            (Ok(start), Ok(end)) if start <= end => Ok(Interval::new(start, end)),
+            _ => Err(ParseError {
+                position,
+                kind: ParseErrorKind::InvalidInterval(format!("[{start},{end}]")),
+            }),
+        }
+    }
+
+    fn parse_bound(&mut self) -> Result<String, ParseError> {
+        match self.peek().cloned() {
+            Some(Token::Ident(bound)) => {
+                self.cursor += 1;
+                Ok(bound)
+            }
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), ParseError> {
+        if self.peek() == Some(&expected) {
+            self.cursor += 1;
+            Ok(())
+        } else {
+            Err(self.unexpected())
+        }
+    }
+
+    // The error for the token at the cursor, which is not the expected one.
+    fn unexpected(&self) -> ParseError {
+        match self.tokens.get(self.cursor) {
+            Some((position, token)) => ParseError {
+                position: *position,
+                kind: ParseErrorKind::UnexpectedToken(token.to_string()),
+            },
+            None => ParseError {
+                position: self.end,
+                kind: ParseErrorKind::UnexpectedEnd,
+            },
+        }
+    }
+
+    fn operand_follows(&self) -> bool {
+        match self.tokens.get(self.cursor + 1) {
+            Some((
+                _,
+                Token::LParen | Token::Not | Token::True | Token::False | Token::Quoted(_),
+            )) => true,
+            Some((_, Token::Ident(name))) => !matches!(name.as_str(), "U" | "R" | "W" | "S"),
+            _ => false,
+        }
+    }
+
+    fn resolve(&self, name: &str) -> Option<Idx> {
+        if let Some(vars) = self.vars {
+            vars.iter()
+                .position(|var| var == name)
+                .and_then(|idx| Idx::try_from(idx).ok())
+        } else {
+            name.strip_prefix('x')
+                .filter(|idx| idx.chars().all(|c| c.is_ascii_digit()))
+                .and_then(|idx| idx.parse().ok())
+        }
+    }
+}
+
+/// Parses a formula known to be valid, for testing.
+#[cfg(test)]
+pub(crate) fn parse_formula(formula: &str) -> SyntaxTree {
+    formula.parse().expect("valid formula")
+}
+
+#[cfg(test)]
+mod eval {
+    use super::*;
+    use itertools::Itertools;
+
+    const ATOM_0: SyntaxTree = SyntaxTree::Atom(0);
+
+    const ATOM_1: SyntaxTree = SyntaxTree::Atom(1);
+
+    #[test]
+    fn atomic_prop() {
+        let trace = [[true]];
+        assert!(ATOM_0.eval(&trace));
+
+        let trace = [[false]];
+        assert!(!ATOM_0.eval(&trace));
+
+        // let trace: [[bool; 1]; 0] = [];
+        // assert!(!ATOM_0.eval(&trace));
+    }
+
+    #[test]
+    fn constants() {
+        let trace = vec![[false]; 70];
+        assert!(SyntaxTree::True.eval(&trace));
+        assert!(!SyntaxTree::False.eval(&trace));
+        assert_eq!(SyntaxTree::True.eval_all_times(&trace), vec![true; 70]);
+        assert_eq!(SyntaxTree::False.eval_all_times(&trace), vec![false; 70]);
+
+        // X ⊤ only fails at the end of the trace.
+        let formula = SyntaxTree::Next(Arc::new(SyntaxTree::True));
+        assert!(formula.eval_at_time(&trace, 68));
+        assert!(!formula.eval_at_time(&trace, 69));
+    }
+
+    #[test]
+    fn not() {
+        let formula = SyntaxTree::Not(Arc::new(ATOM_0));
+
+        let trace = [[false]];
+        assert!(formula.eval(&trace));
+
+        let trace = [[true]];
+        assert!(!formula.eval(&trace));
+    }
+
+    #[test]
+    fn next() {
+        let formula = SyntaxTree::Next(Arc::new(ATOM_0));
+
+        let trace = [[false], [true]];
+        assert!(formula.eval(&trace));
+
+        let trace = [[true], [false]];
+        assert!(!formula.eval(&trace));
+
+        // Strong next does not hold at the end of the trace.
+        let trace = [[true]];
+        assert!(!formula.eval(&trace));
+    }
+
+    #[test]
+    fn weak_next() {
+        let formula = SyntaxTree::WeakNext(Arc::new(ATOM_0));
+
+        let trace = [[false], [true]];
+        assert!(formula.eval(&trace));
+
+        let trace = [[true], [false]];
+        assert!(!formula.eval(&trace));
+
+        // Weak next holds at the end of the trace.
+        let trace = [[false]];
+        assert!(formula.eval(&trace));
+    }
+
+    #[test]
+    fn globally() {
+        let formula = SyntaxTree::Globally(Arc::new(ATOM_0));
+
+        let trace = [[true], [true], [true]];
+        assert!(formula.eval(&trace));
+
+        let trace = [[true], [false], [true]];
+        assert!(!formula.eval(&trace));
+
+        // // Not even Globally can be true at moment 0 on an empty trace
+        // let trace: [[bool; 1]; 0] = [];
+        // assert!(!formula.eval(&trace));
+    }
+
+    #[test]
+    fn finally() {
+        let formula = SyntaxTree::Finally(Arc::new(ATOM_0));
+
+        let trace = [[false], [false], [true]];
+        assert!(formula.eval(&trace));
+
+        let trace = [[false], [true], [false]];
+        assert!(formula.eval(&trace));
+
+        let trace = [[false], [false], [false]];
+        assert!(!formula.eval(&trace));
+    }
+
+    #[test]
+    fn and() {
+        let formula = SyntaxTree::And(Arc::new(ATOM_0), Arc::new(ATOM_1));
+
+        let trace = [[true, true]];
+        assert!(formula.eval(&trace));
+
+        let trace = [[true, false]];
+        assert!(!formula.eval(&trace));
+    }
+
+    #[test]
+    fn or() {
+        let formula = SyntaxTree::Or(Arc::new(ATOM_0), Arc::new(ATOM_1));
+
+        let trace = [[true, false]];
+        assert!(formula.eval(&trace));
+
+        let trace = [[false, false]];
+        assert!(!formula.eval(&trace));
+    }
+
+    #[test]
+    fn until() {
+        let formula = SyntaxTree::Until(Arc::new(ATOM_0), Arc::new(ATOM_1));
+
+        let trace = [[true, false], [false, true], [false, false]];
+        assert!(formula.eval(&trace));
+
+        let trace = [[true, false], [true, false], [false, false]];
+        assert!(!formula.eval(&trace));
+
+        // Until is not satisfied if its right-hand-side argument never becomes true.
+        let trace = [[true, false], [true, false], [true, false]];
+        assert!(!formula.eval(&trace));
+
+        // let trace: [[bool; 2]; 0] = [];
         // assert!(!formula.eval(&trace));
     }
+
+    #[test]
+    fn weak_until() {
+        let formula = SyntaxTree::WeakUntil(Arc::new(ATOM_0), Arc::new(ATOM_1));
+
+        let trace = [[true, false], [false, true], [false, false]];
+        assert!(formula.eval(&trace));
+
+        let trace = [[true, false], [true, false], [false, false]];
+        assert!(!formula.eval(&trace));
+
+        // Weak until is satisfied if its left-hand-side argument holds until the end of the trace.
+        let trace = [[true, false], [true, false], [true, false]];
+        assert!(formula.eval(&trace));
+    }
+
+    #[test]
+    fn release() {
+        let formula = SyntaxTree::Release(Arc::new(ATOM_0), Arc::new(ATOM_1));
+
+        // The right-hand-side argument has to hold also when the left-hand-side one does.
+        let trace = [[false, true], [true, true], [false, false]];
+        assert!(formula.eval(&trace));
+
+        let trace = [[false, true], [true, false], [false, false]];
+        assert!(!formula.eval(&trace));
+
+        // Release is satisfied if its right-hand-side argument holds until the end of the trace.
+        let trace = [[false, true], [false, true], [false, true]];
+        assert!(formula.eval(&trace));
+
+        // φ R ψ ≡ ¬(¬φ U ¬ψ)
+        let dual = SyntaxTree::Not(Arc::new(SyntaxTree::Until(
+            Arc::new(SyntaxTree::Not(Arc::new(ATOM_0))),
+            Arc::new(SyntaxTree::Not(Arc::new(ATOM_1))),
+        )));
+        for trace in (0..4)
+            .map(|_| [[false, false], [false, true], [true, false], [true, true]])
+            .multi_cartesian_product()
+        {
+            assert_eq!(formula.eval(&trace), dual.eval(&trace), "{trace:?}");
+        }
+    }
+
+    #[test]
+    fn yesterday() {
+        let formula = SyntaxTree::Yesterday(Arc::new(ATOM_0));
+
+        let trace = [[true, false], [false, false]];
+        assert!(formula.eval_at_time(&trace, 1));
+
+        let trace = [[false, false], [true, false]];
+        assert!(!formula.eval_at_time(&trace, 1));
+
+        // Yesterday does not hold at the first instant, where there is no previous one.
+        let trace = [[true, false]];
+        assert!(!formula.eval(&trace));
+    }
+
+    #[test]
+    fn once() {
+        let formula = SyntaxTree::Once(Arc::new(ATOM_0));
+
+        let trace = [[true, false], [false, false], [false, false]];
+        assert!(formula.eval_at_time(&trace, 2));
+
+        let trace = [[false, false], [false, false], [true, false]];
+        assert!(!formula.eval_at_time(&trace, 1));
+        assert!(formula.eval_at_time(&trace, 2));
+    }
+
+    #[test]
+    fn historically() {
+        let formula = SyntaxTree::Historically(Arc::new(ATOM_0));
+
+        let trace = [[true, false], [true, false], [false, false]];
+        assert!(formula.eval_at_time(&trace, 1));
+        assert!(!formula.eval_at_time(&trace, 2));
+
+        let trace = [[false, false], [true, false], [true, false]];
+        assert!(!formula.eval_at_time(&trace, 2));
+    }
+
+    #[test]
+    fn since() {
+        let formula = SyntaxTree::Since(Arc::new(ATOM_0), Arc::new(ATOM_1));
+
+        let trace = [[false, true], [true, false], [true, false]];
+        assert!(formula.eval_at_time(&trace, 2));
+
+        let trace = [[false, true], [false, false], [true, false]];
+        assert!(!formula.eval_at_time(&trace, 2));
+
+        // Since is not satisfied if its right-hand-side argument never held.
+        let trace = [[true, false], [true, false], [true, false]];
+        assert!(!formula.eval_at_time(&trace, 2));
+
+        // φ S ψ ≡ ψ ∨ (φ ∧ Y(φ S ψ))
+        let unfolded = SyntaxTree::Or(
+            Arc::new(ATOM_1),
+            Arc::new(SyntaxTree::And(
+                Arc::new(ATOM_0),
+                Arc::new(SyntaxTree::Yesterday(Arc::new(formula.clone()))),
+            )),
+        );
+        for trace in (0..4)
+            .map(|_| [[false, false], [false, true], [true, false], [true, true]])
+            .multi_cartesian_product()
+        {
+            for time in 0..trace.len() {
+                assert_eq!(
+                    formula.eval_at_time(&trace, time),
+                    unfolded.eval_at_time(&trace, time),
+                    "{trace:?} at {time}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn bounded_finally() {
+        let formula = SyntaxTree::BoundedFinally(Interval::new(1, 2), Arc::new(ATOM_0));
+
+        let trace = [[true], [false], [true], [false]];
+        assert!(formula.eval(&trace));
+        assert!(formula.eval_at_time(&trace, 1));
+        assert!(!formula.eval_at_time(&trace, 2));
+
+        // x0 holds too late.
+        let trace = [[true], [false], [false], [true]];
+        assert!(!formula.eval(&trace));
+
+        // The interval reaches past the end of the trace.
+        let trace = [[true]];
+        assert!(!formula.eval(&trace));
+    }
+
+    #[test]
+    fn bounded_globally() {
+        let formula = SyntaxTree::BoundedGlobally(Interval::new(1, 2), Arc::new(ATOM_0));
+
+        let trace = [[false], [true], [true], [false]];
+        assert!(formula.eval(&trace));
+        assert!(!formula.eval_at_time(&trace, 1));
+
+        // Time instants past the end of the trace are not required to satisfy x0.
+        let trace = [[false], [true]];
+        assert!(formula.eval(&trace));
+        assert!(formula.eval_at_time(&trace, 1));
+    }
+
+    #[test]
+    fn bounded_until() {
+        let formula =
+            SyntaxTree::BoundedUntil(Interval::new(1, 2), Arc::new(ATOM_0), Arc::new(ATOM_1));
+
+        let trace = [[true, false], [true, false], [false, true]];
+        assert!(formula.eval(&trace));
+
+        // x1 holds too early, as well as too late.
+        let trace = [[false, true], [false, false], [false, false]];
+        assert!(!formula.eval(&trace));
+        let trace = [[true, false], [true, false], [true, false], [false, true]];
+        assert!(!formula.eval(&trace));
+
+        // x0 does not hold up to x1.
+        let trace = [[true, false], [false, false], [false, true]];
+        assert!(!formula.eval(&trace));
+
+        // F[a,b] φ ≡ ⊤ U[a,b] φ and φ U ψ ≡ φ U[0,n] ψ on traces of length up to n + 1
+        let finally = SyntaxTree::BoundedFinally(Interval::new(1, 2), Arc::new(ATOM_1));
+        let true_until = SyntaxTree::BoundedUntil(
+            Interval::new(1, 2),
+            Arc::new(SyntaxTree::True),
+            Arc::new(ATOM_1),
+        );
+        let until = SyntaxTree::Until(Arc::new(ATOM_0), Arc::new(ATOM_1));
+        let bounded_until =
+            SyntaxTree::BoundedUntil(Interval::new(0, 3), Arc::new(ATOM_0), Arc::new(ATOM_1));
+        for trace in (0..4)
+            .map(|_| [[false, false], [false, true], [true, false], [true, true]])
+            .multi_cartesian_product()
+        {
+            assert_eq!(finally.eval(&trace), true_until.eval(&trace), "{trace:?}");
+            assert_eq!(until.eval(&trace), bounded_until.eval(&trace), "{trace:?}");
+        }
+    }
+
+    // Intervals with bounds up to `Time::MAX` reach past the end of the trace at any time instant.
+    #[test]
+    fn huge_bounds() {
+        let max = Time::MAX;
+        for formula in [
+            format!("G F[0,{max}] x0"),
+            format!("G F[{max},{max}] x0"),
+            format!("F G[1,{max}] x0"),
+            format!("G (x0 U[1,{max}] x1)"),
+        ] {
+            let formula = parse_formula(&formula);
+            for trace in (1..=3).flat_map(|len| {
+                (0..len)
+                    .map(|_| [[false, false], [false, true], [true, false], [true, true]])
+                    .multi_cartesian_product()
+            }) {
+                let values = formula.eval_all_times(&trace);
+                for (time, value) in values.into_iter().enumerate().skip(1) {
+                    assert_eq!(
+                        formula.eval_at_time(&trace, time),
+                        value,
+                        "{formula} on {trace:?} at {time}"
+                    );
+                    assert_eq!(
+                        formula.explain_at_time(&trace, time).value,
+                        value,
+                        "{formula} on {trace:?} at {time}"
+                    );
+                }
+            }
+        }
+    }
+
+    // The dynamic-programming evaluation has to agree with the recursive one at every time instant.
+    #[test]
+    fn all_times() {
+        let traces = (1..=4)
+            .flat_map(|len| {
+                (0..len)
+                    .map(|_| [[false, false], [false, true], [true, false], [true, true]])
+                    .multi_cartesian_product()
+            })
+            .collect::<Vec<_>>();
+
+        for size in 1..=4 {
+            for formula in crate::Operators::all()
+                .with_horizon(2)
+                .gen_formulae(size, &[0, 1])
+            {
+                for trace in &traces {
+                    let values = formula.eval_all_times(trace);
+                    assert_eq!(values.len(), trace.len());
+                    for (time, val) in values.into_iter().enumerate() {
+                        assert_eq!(
+                            val,
+                            formula.eval_at_time(trace, time),
+                            "{formula} on {trace:?}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    // Same as above, on longer traces whose truth values span multiple words.
+    #[test]
+    fn all_times_long_traces() {
+        use rand::prelude::*;
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let traces = [1, 63, 64, 65, 127, 128, 200]
+            .into_iter()
+            .map(|len| {
+                (0..len)
+                    .map(|_| [rng.gen_bool(0.7), rng.gen_bool(0.3)])
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        for size in 1..=4 {
+            for formula in crate::Operators::all()
+                .with_horizon(2)
+                .gen_formulae(size, &[0, 1])
+            {
+                for trace in &traces {
+                    let values = formula.eval_all_times(trace);
+                    for (time, val) in values.into_iter().enumerate() {
+                        assert_eq!(
+                            val,
+                            formula.eval_at_time(trace, time),
+                            "{formula} at {time}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    // Regression test: time instants used to be `u8`, so traces longer than 255 steps could not be evaluated.
+    #[test]
+    fn long_trace() {
+        let mut trace = vec![[false, true]; 1000];
+        trace[700][0] = true;
+
+        let formula = SyntaxTree::Finally(Arc::new(ATOM_0));
+        assert!(formula.eval(&trace));
+        assert!(formula.eval_at_time(&trace, 700));
+        assert!(!formula.eval_at_time(&trace, 701));
+
+        let formula = SyntaxTree::Until(Arc::new(ATOM_1), Arc::new(ATOM_0));
+        assert!(formula.eval(&trace));
+
+        trace[300][1] = false;
+        assert!(!formula.eval(&trace));
+        assert!(formula.eval_at_time(&trace, 301));
+
+        let formula = SyntaxTree::Globally(Arc::new(ATOM_1));
+        assert!(!formula.eval(&trace));
+        assert!(formula.eval_at_time(&trace, 301));
+
+        let values = formula.eval_all_times(&trace);
+        assert!(!values[300]);
+        assert!(values[301..].iter().all(|val| *val));
+    }
+}
+
+#[cfg(test)]
+mod parse {
+    use super::*;
+
+    const ATOM_0: SyntaxTree = SyntaxTree::Atom(0);
+
+    const ATOM_1: SyntaxTree = SyntaxTree::Atom(1);
+
+    #[test]
+    fn atomic_prop() {
+        assert_eq!("x0".parse::<SyntaxTree>(), Ok(ATOM_0));
+        assert_eq!("x1".parse::<SyntaxTree>(), Ok(ATOM_1));
+        assert_eq!(" ( (x1) ) ".parse::<SyntaxTree>(), Ok(ATOM_1));
+    }
+
+    #[test]
+    fn constants() {
+        assert_eq!("⊤".parse::<SyntaxTree>(), Ok(SyntaxTree::True));
+        assert_eq!("true".parse::<SyntaxTree>(), Ok(SyntaxTree::True));
+        assert_eq!("(false)".parse::<SyntaxTree>(), Ok(SyntaxTree::False));
+
+        let formula = SyntaxTree::Implies(
+            Arc::new(SyntaxTree::Globally(Arc::new(SyntaxTree::False))),
+            Arc::new(ATOM_0),
+        );
+        assert_eq!("G⊥ -> x0".parse::<SyntaxTree>(), Ok(formula.clone()));
+        assert_eq!(formula.to_string().parse::<SyntaxTree>(), Ok(formula));
+
+        // Variables named `true` or `false` take precedence over the constants.
+        let vars = ["true".to_string()];
+        assert_eq!(
+            SyntaxTree::parse_w_named_vars("true ∨ false", &vars),
+            Ok(SyntaxTree::Or(
+                Arc::new(ATOM_0),
+                Arc::new(SyntaxTree::False)
+            ))
+        );
+    }
+
+    #[test]
+    fn operators() {
+        let not = SyntaxTree::Not(Arc::new(ATOM_0));
+        assert_eq!("¬(x0)".parse::<SyntaxTree>(), Ok(not.clone()));
+        assert_eq!("!x0".parse::<SyntaxTree>(), Ok(not));
+
+        let globally = SyntaxTree::Globally(Arc::new(ATOM_0));
+        assert_eq!("G(x0)".parse::<SyntaxTree>(), Ok(globally.clone()));
+        assert_eq!("G x0".parse::<SyntaxTree>(), Ok(globally));
+
+        let and = SyntaxTree::And(Arc::new(ATOM_0), Arc::new(ATOM_1));
+        assert_eq!("(x0)∧(x1)".parse::<SyntaxTree>(), Ok(and.clone()));
+        assert_eq!("x0 & x1".parse::<SyntaxTree>(), Ok(and.clone()));
+        assert_eq!("x0 && x1".parse::<SyntaxTree>(), Ok(and));
+
+        let or = SyntaxTree::Or(Arc::new(ATOM_0), Arc::new(ATOM_1));
+        assert_eq!("(x0)∨(x1)".parse::<SyntaxTree>(), Ok(or.clone()));
+        assert_eq!("x0 | x1".parse::<SyntaxTree>(), Ok(or));
+
+        let implies = SyntaxTree::Implies(Arc::new(ATOM_0), Arc::new(ATOM_1));
+        assert_eq!("(x0)→(x1)".parse::<SyntaxTree>(), Ok(implies.clone()));
+        assert_eq!("x0 -> x1".parse::<SyntaxTree>(), Ok(implies));
+
+        let until = SyntaxTree::Until(Arc::new(ATOM_0), Arc::new(ATOM_1));
+        assert_eq!("(x0)U(x1)".parse::<SyntaxTree>(), Ok(until.clone()));
+        assert_eq!("x0 U x1".parse::<SyntaxTree>(), Ok(until));
+
+        let weak_next = SyntaxTree::WeakNext(Arc::new(ATOM_0));
+        assert_eq!("N(x0)".parse::<SyntaxTree>(), Ok(weak_next.clone()));
+        assert_eq!("N x0".parse::<SyntaxTree>(), Ok(weak_next));
+
+        let release = SyntaxTree::Release(Arc::new(ATOM_0), Arc::new(ATOM_1));
+        assert_eq!("(x0)R(x1)".parse::<SyntaxTree>(), Ok(release.clone()));
+        assert_eq!("x0 R x1".parse::<SyntaxTree>(), Ok(release));
+
+        let weak_until = SyntaxTree::WeakUntil(Arc::new(ATOM_0), Arc::new(ATOM_1));
+        assert_eq!("(x0)W(x1)".parse::<SyntaxTree>(), Ok(weak_until.clone()));
+        assert_eq!("x0 W x1".parse::<SyntaxTree>(), Ok(weak_until));
+
+        let yesterday = SyntaxTree::Yesterday(Arc::new(ATOM_0));
+        assert_eq!("Y(x0)".parse::<SyntaxTree>(), Ok(yesterday.clone()));
+        assert_eq!("Y x0".parse::<SyntaxTree>(), Ok(yesterday));
+
+        let once = SyntaxTree::Once(Arc::new(ATOM_0));
+        assert_eq!("O(x0)".parse::<SyntaxTree>(), Ok(once));
+
+        let historically = SyntaxTree::Historically(Arc::new(ATOM_0));
+        assert_eq!("H x0".parse::<SyntaxTree>(), Ok(historically));
+
+        let since = SyntaxTree::Since(Arc::new(ATOM_0), Arc::new(ATOM_1));
+        assert_eq!("(x0)S(x1)".parse::<SyntaxTree>(), Ok(since.clone()));
+        assert_eq!("x0 S x1".parse::<SyntaxTree>(), Ok(since));
+    }
+
+    #[test]
+    fn bounded_operators() {
+        let finally = SyntaxTree::BoundedFinally(Interval::new(0, 5), Arc::new(ATOM_1));
+        assert_eq!("F[0,5](x1)".parse::<SyntaxTree>(), Ok(finally.clone()));
+        assert_eq!("F [0, 5] x1".parse::<SyntaxTree>(), Ok(finally.clone()));
+        assert_eq!(finally.to_string(), "F[0,5](x1)");
+
+        let globally = SyntaxTree::BoundedGlobally(Interval::new(2, 3), Arc::new(ATOM_0));
+        assert_eq!("G[2,3] x0".parse::<SyntaxTree>(), Ok(globally));
+
+        let until =
+            SyntaxTree::BoundedUntil(Interval::new(1, 3), Arc::new(ATOM_0), Arc::new(ATOM_1));
+        assert_eq!("(x0)U[1,3](x1)".parse::<SyntaxTree>(), Ok(until.clone()));
+        assert_eq!("x0 U[1,3] x1".parse::<SyntaxTree>(), Ok(until.clone()));
+        assert_eq!(until.to_string(), "(x0)U[1,3](x1)");
+
+        assert_eq!(
+            "F[3,1] x0".parse::<SyntaxTree>(),
+            Err(ParseError {
+                position: 1,
+                kind: ParseErrorKind::InvalidInterval("[3,1]".to_string()),
+            })
+        );
+        assert_eq!(
+            "F[0] x0".parse::<SyntaxTree>(),
+            Err(ParseError {
+                position: 3,
+                kind: ParseErrorKind::UnexpectedToken("]".to_string()),
+            })
+        );
+    }
+
+    #[test]
+    fn precedence() {
+        // ¬ x0 ∧ x1 ∨ x0 U x1 → x0 ≡ (((¬ x0) ∧ x1) ∨ (x0 U x1)) → x0
+        let formula = SyntaxTree::Implies(
+            Arc::new(SyntaxTree::Or(
+                Arc::new(SyntaxTree::And(
+                    Arc::new(SyntaxTree::Not(Arc::new(ATOM_0))),
+                    Arc::new(ATOM_1),
+                )),
+                Arc::new(SyntaxTree::Until(Arc::new(ATOM_0), Arc::new(ATOM_1))),
+            )),
+            Arc::new(ATOM_0),
+        );
+        assert_eq!(
+            "!x0 & x1 | x0 U x1 -> x0".parse::<SyntaxTree>(),
+            Ok(formula)
+        );
+
+        // Implication is right-associative
+        let formula = SyntaxTree::Implies(
+            Arc::new(ATOM_0),
+            Arc::new(SyntaxTree::Implies(Arc::new(ATOM_1), Arc::new(ATOM_0))),
+        );
+        assert_eq!("x0 -> x1 -> x0".parse::<SyntaxTree>(), Ok(formula));
+    }
+
+    #[test]
+    fn named_vars() {
+        let vars = ["F".to_string(), "door_closed".to_string()];
+
+        let formula = SyntaxTree::Finally(Arc::new(SyntaxTree::And(
+            Arc::new(ATOM_0),
+            Arc::new(SyntaxTree::Not(Arc::new(ATOM_1))),
+        )));
+        assert_eq!(
+            SyntaxTree::parse_w_named_vars("F(F ∧ ¬door_closed)", &vars),
+            Ok(formula)
+        );
+        assert_eq!(
+            SyntaxTree::parse_w_named_vars("x0", &vars),
+            Err(ParseError {
+                position: 0,
+                kind: ParseErrorKind::UnknownVariable("x0".to_string()),
+            })
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            "(x0)∧(x2".parse::<SyntaxTree>(),
+            Err(ParseError {
+                position: 5,
+                kind: ParseErrorKind::UnclosedParen,
+            })
+        );
+        assert_eq!(
+            "G(x0) $ x1".parse::<SyntaxTree>(),
+            Err(ParseError {
+                position: 6,
+                kind: ParseErrorKind::UnexpectedChar('$'),
+            })
+        );
+        assert_eq!(
+            "(x0)∧".parse::<SyntaxTree>(),
+            Err(ParseError {
+                position: 5,
+                kind: ParseErrorKind::UnexpectedEnd,
+            })
+        );
+        assert_eq!(
+            "x0 x1".parse::<SyntaxTree>(),
+            Err(ParseError {
+                position: 3,
+                kind: ParseErrorKind::UnexpectedToken("x1".to_string()),
+            })
+        );
+        assert_eq!(
+            "y0".parse::<SyntaxTree>(),
+            Err(ParseError {
+                position: 0,
+                kind: ParseErrorKind::UnknownVariable("y0".to_string()),
+            })
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let vars = (0..2).map(|n| format!("var_{n}")).collect::<Vec<_>>();
+        for size in 1..=4 {
+            for formula in crate::Operators::all()
+                .with_horizon(2)
+                .gen_formulae(size, &[0, 1])
+            {
+                assert_eq!(
+                    formula.to_string().parse::<SyntaxTree>(),
+                    Ok(formula.clone())
+                );
+                assert_eq!(
+                    SyntaxTree::parse_w_named_vars(&formula.print_w_named_vars(&vars), &vars),
+                    Ok(formula)
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod serialize {
+    use super::*;
+
+    fn formula() -> SyntaxTree {
+        SyntaxTree::Globally(Arc::new(SyntaxTree::Implies(
+            Arc::new(SyntaxTree::Atom(0)),
+            Arc::new(SyntaxTree::Until(
+                Arc::new(SyntaxTree::Not(Arc::new(SyntaxTree::Atom(1)))),
+                Arc::new(SyntaxTree::Atom(0)),
+            )),
+        )))
+    }
+
+    #[test]
+    fn ron() {
+        let formula = formula();
+        let ron = ron::ser::to_string(&formula).expect("serialize formula");
+        assert_eq!(
+            ron,
+            "Globally(Implies(Atom(0),Until(Not(Atom(1)),Atom(0))))"
+        );
+        assert_eq!(ron::de::from_str::<SyntaxTree>(&ron), Ok(formula));
+    }
+
+    #[test]
+    fn json() {
+        let formula = formula();
+        let json = serde_json::to_string(&formula).expect("serialize formula");
+        assert_eq!(
+            serde_json::from_str::<SyntaxTree>(&json).expect("deserialize formula"),
+            formula
+        );
+    }
+
+    #[test]
+    fn named_formula() {
+        let named = NamedFormula::new(formula(), vec!["charged".to_string(), "moving".to_string()]);
+
+        let ron = ron::ser::to_string(&named).expect("serialize formula");
+        assert_eq!(
+            ron,
+            r#"(var_names:["charged","moving"],formula:"G((charged)→((¬(moving))U(charged)))")"#
+        );
+        assert_eq!(ron::de::from_str::<NamedFormula>(&ron), Ok(named.clone()));
+
+        let json = serde_json::to_string(&named).expect("serialize formula");
+        assert_eq!(
+            serde_json::from_str::<NamedFormula>(&json).expect("deserialize formula"),
+            named
+        );
+
+        let unknown_var = r#"(var_names:["charged"],formula:"G(moving)")"#;
+        assert!(ron::de::from_str::<NamedFormula>(unknown_var).is_err());
+    }
+
+    // Empty intervals are rejected, as by the parser.
+    #[test]
+    fn interval() {
+        let formula = "BoundedGlobally((start:1,end:3),Atom(0))";
+        assert_eq!(
+            ron::de::from_str::<SyntaxTree>(formula),
+            Ok(SyntaxTree::BoundedGlobally(
+                Interval::new(1, 3),
+                Arc::new(SyntaxTree::Atom(0))
+            ))
+        );
+        let empty = "BoundedGlobally((start:3,end:1),Atom(0))";
+        assert!(ron::de::from_str::<SyntaxTree>(empty).is_err());
+        assert!(serde_json::from_str::<Interval>(r#"{"start":3,"end":1}"#).is_err());
+    }
+
+    // Names that are not valid identifiers are quoted, so that they can be read back.
+    #[test]
+    fn quoted_names() {
+        let named = NamedFormula::new(
+            formula(),
+            vec!["door-open".to_string(), r#"say "hi" \o/"#.to_string()],
+        );
+        assert_eq!(
+            named.to_string(),
+            r#"G(("door-open")→((¬("say \"hi\" \\o/"))U("door-open")))"#
+        );
+        let json = serde_json::to_string(&named).expect("serialize formula");
+        assert_eq!(
+            serde_json::from_str::<NamedFormula>(&json).expect("deserialize formula"),
+            named
+        );
+        let ron = ron::ser::to_string(&named).expect("serialize formula");
+        assert_eq!(ron::de::from_str::<NamedFormula>(&ron), Ok(named));
+
+        // Quoted names are variables, even when they are also operators or constants.
+        let vars = ["G".to_string(), "true".to_string(), String::new()];
+        let until = SyntaxTree::Until(Arc::new(SyntaxTree::Atom(1)), Arc::new(SyntaxTree::Atom(2)));
+        for (formula, expected) in [
+            (
+                "G(\"G\")",
+                SyntaxTree::Globally(Arc::new(SyntaxTree::Atom(0))),
+            ),
+            ("(\"true\")U(\"\")", until),
+        ] {
+            let parsed = SyntaxTree::parse_w_named_vars(formula, &vars).expect("valid formula");
+            assert_eq!(parsed, expected);
+            let printed = parsed.print_w_named_vars(&vars);
+            assert_eq!(SyntaxTree::parse_w_named_vars(&printed, &vars), Ok(parsed));
+        }
+        assert_eq!(
+            SyntaxTree::parse_w_named_vars("G \"G", &vars),
+            Err(ParseError {
+                position: 2,
+                kind: ParseErrorKind::UnclosedQuote
+            })
+        );
+    }
 }
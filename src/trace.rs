@@ -5,39 +5,25 @@ use serde_with::*;
 
 pub type Trace<const N: usize> = Vec<[bool; N]>;
 
-#[serde_as]
-#[derive(Debug, Serialize, Deserialize)]
-pub struct Sample<const N: usize> {
-    #[serde_as(as = "[_; N]")]
-    #[serde(default = "Sample::var_names")]
-    pub var_names: [String; N],
-    #[serde_as(as = "Vec<Vec<[_; N]>>")]
-    pub positive_traces: Vec<Trace<N>>,
-    #[serde_as(as = "Vec<Vec<[_; N]>>")]
-    pub negative_traces: Vec<Trace<N>>,
-}
+/// A trace whose number of propositional variables is only known at runtime.
+pub type DynTrace = Vec<Vec<bool>>;
 
-impl<const N: usize> Default for Sample<N> {
-    fn default() -> Self {
-        Sample {
-            var_names: Sample::var_names(),
-            positive_traces: Vec::default(),
-            negative_traces: Vec::default(),
-        }
-    }
-}
+/// The interface shared by all representations of a sample,
+/// i.e., a set of positive and negative traces over named propositional variables.
+pub trait LabeledSample: Sync {
+    /// The type of the tuples of truth values making up the traces.
+    type State: AsRef<[bool]> + Sync;
 
-impl<const N: usize> Sample<N> {
-    fn var_names() -> [String; N] {
-        (0..N)
-            .map(|n| format!("x{n}"))
-            .collect::<Vec<_>>()
-            .try_into()
-            .expect("wrong size iterator")
-    }
+    fn var_names(&self) -> &[String];
 
-    pub fn vars(&self) -> Vec<Idx> {
-        self.var_names
+    fn positives(&self) -> &[Vec<Self::State>];
+
+    fn negatives(&self) -> &[Vec<Self::State>];
+
+    /// Returns the indexes of the variables that can appear in a solution,
+    /// excluding those whose name has been marked as discarded by a leading `~`.
+    fn vars(&self) -> Vec<Idx> {
+        self.var_names()
             .iter()
             .enumerate()
             .filter_map(|(idx, name)| {
@@ -50,14 +36,15 @@ impl<const N: usize> Sample<N> {
             .collect_vec()
     }
 
-    pub fn is_solvable(&self) -> bool {
+    fn is_solvable(&self) -> bool {
         let vars = self.vars();
 
-        self.positive_traces.iter().all(|pos_trace| {
-            self.negative_traces.iter().all(|neg_trace| {
+        self.positives().iter().all(|pos_trace| {
+            self.negatives().iter().all(|neg_trace| {
                 pos_trace.len() != neg_trace.len()
-                    || pos_trace.iter().enumerate().any(|(time, &pos_tuple)| {
-                        let neg_tuple = neg_trace[time];
+                    || pos_trace.iter().enumerate().any(|(time, pos_tuple)| {
+                        let neg_tuple = neg_trace[time].as_ref();
+                        let pos_tuple = pos_tuple.as_ref();
                         vars.iter()
                             .any(|n| pos_tuple[*n as usize] != neg_tuple[*n as usize])
                     })
@@ -65,37 +52,309 @@ impl<const N: usize> Sample<N> {
         })
     }
 
-    pub fn is_consistent(&self, formula: &SyntaxTree) -> bool {
+    fn is_consistent(&self, formula: &SyntaxTree) -> bool {
         use itertools::*;
 
-        self.positive_traces
+        // Evaluation at all times at once scales better than `eval` on long traces,
+        // and the same buffer can be reused for every trace.
+        let buffers = formula.eval_buffers();
+        let mut buffer = Vec::new();
+
+        self.positives()
             .iter()
-            .map(|trace| formula.eval(trace.as_slice()))
-            .interleave(
-                self.negative_traces
-                    .iter()
-                    .map(|trace| !formula.eval(trace.as_slice())),
-            )
-            .all(|val| val)
+            .map(|trace| (trace, true))
+            .interleave(self.negatives().iter().map(|trace| (trace, false)))
+            .all(|(trace, positive)| {
+                buffer.resize(words(trace.len()) * buffers, 0);
+                formula.eval_all_times_in(trace.as_slice(), &mut buffer);
+                (buffer[0] & 1 == 1) == positive
+            })
     }
 
-    pub fn time_lenght(&self) -> Time {
+    /// Returns the indexes of the positive and of the negative traces misclassified by the formula.
+    fn misclassified(&self, formula: &SyntaxTree) -> Misclassified {
+        BitSample::new(self)
+            .misclassified(formula, usize::MAX)
+            .expect("no bound on errors")
+    }
+
+    /// Returns the minimum number of traces misclassified by any formula,
+    /// i.e., the number of traces that cannot be told apart from a trace with the opposite label
+    /// in the sample (counting only the smaller group of each set of indistinguishable traces).
+    /// A sample is solvable iff this is zero.
+    fn min_errors(&self) -> usize {
+        use std::collections::HashMap;
+
+        let vars = self.vars();
+        let mut groups: HashMap<Vec<Vec<bool>>, (usize, usize)> = HashMap::new();
+        let project = |trace: &Vec<Self::State>| {
+            trace
+                .iter()
+                .map(|state| {
+                    vars.iter()
+                        .map(|&var| state.as_ref()[var as usize])
+                        .collect()
+                })
+                .collect()
+        };
+        for trace in self.positives() {
+            groups.entry(project(trace)).or_default().0 += 1;
+        }
+        for trace in self.negatives() {
+            groups.entry(project(trace)).or_default().1 += 1;
+        }
+        groups
+            .values()
+            .map(|(positives, negatives)| *positives.min(negatives))
+            .sum()
+    }
+
+    fn time_lenght(&self) -> Time {
         let positive_lenght = self
-            .positive_traces
+            .positives()
             .iter()
             .map(|trace| trace.len())
             .max()
             .unwrap_or(0);
         let negative_lenght = self
-            .negative_traces
+            .negatives()
             .iter()
             .map(|trace| trace.len())
             .max()
             .unwrap_or(0);
-        positive_lenght.max(negative_lenght) as Time
+        positive_lenght.max(negative_lenght)
+    }
+}
+
+/// The traces of a sample misclassified by a formula, given by their indexes.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Misclassified {
+    /// Positive traces not satisfying the formula.
+    pub positives: Vec<usize>,
+    /// Negative traces satisfying the formula.
+    pub negatives: Vec<usize>,
+}
+
+impl Misclassified {
+    /// Total number of misclassified traces.
+    pub fn len(&self) -> usize {
+        self.positives.len() + self.negatives.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positives.is_empty() && self.negatives.is_empty()
+    }
+}
+
+/// A trace stored variable-wise, with the truth values of each variable packed in a bitset,
+/// so that formulae can be evaluated over it without unpacking its states every time.
+#[derive(Debug, Clone)]
+pub(crate) struct BitTrace {
+    len: usize,
+    atoms: Vec<u64>,
+}
+
+impl BitTrace {
+    pub(crate) fn new<S: AsRef<[bool]>>(trace: &[S]) -> Self {
+        let vars = trace.first().map_or(0, |state| state.as_ref().len());
+        let words = words(trace.len());
+        let mut atoms = vec![0; vars * words];
+        for var in 0..vars {
+            trace.atom_values(var as Idx, &mut atoms[var * words..(var + 1) * words]);
+        }
+        BitTrace {
+            len: trace.len(),
+            atoms,
+        }
+    }
+}
+
+impl AtomValues for BitTrace {
+    fn trace_len(&self) -> usize {
+        self.len
+    }
+
+    fn atom_values(&self, var: Idx, values: &mut [u64]) {
+        let words = values.len();
+        let var = var as usize;
+        values.copy_from_slice(&self.atoms[var * words..(var + 1) * words]);
+    }
+}
+
+/// A sample whose traces have been converted into [`BitTrace`]s,
+/// to speed up checking the consistency of many formulae.
+#[derive(Debug, Clone)]
+pub(crate) struct BitSample {
+    positive_traces: Vec<BitTrace>,
+    negative_traces: Vec<BitTrace>,
+}
+
+impl BitSample {
+    pub(crate) fn new<S: LabeledSample + ?Sized>(sample: &S) -> Self {
+        BitSample {
+            positive_traces: sample
+                .positives()
+                .iter()
+                .map(|trace| BitTrace::new(trace))
+                .collect(),
+            negative_traces: sample
+                .negatives()
+                .iter()
+                .map(|trace| BitTrace::new(trace))
+                .collect(),
+        }
+    }
+
+    pub(crate) fn is_consistent(&self, formula: &SyntaxTree) -> bool {
+        let buffers = formula.eval_buffers();
+        let mut buffer = Vec::new();
+
+        self.positive_traces
+            .iter()
+            .map(|trace| (trace, true))
+            .interleave(self.negative_traces.iter().map(|trace| (trace, false)))
+            .all(|(trace, positive)| {
+                buffer.resize(words(trace.len) * buffers, 0);
+                formula.eval_all_times_in(trace, &mut buffer);
+                (buffer[0] & 1 == 1) == positive
+            })
+    }
+
+    /// Returns the traces misclassified by the formula,
+    /// or `None` as soon as more than `max_errors` are found.
+    pub(crate) fn misclassified(
+        &self,
+        formula: &SyntaxTree,
+        max_errors: usize,
+    ) -> Option<Misclassified> {
+        let buffers = formula.eval_buffers();
+        let mut buffer = Vec::new();
+        let mut misclassified = Misclassified::default();
+
+        for (traces, positive) in [
+            (&self.positive_traces, true),
+            (&self.negative_traces, false),
+        ] {
+            for (idx, trace) in traces.iter().enumerate() {
+                buffer.resize(words(trace.len) * buffers, 0);
+                formula.eval_all_times_in(trace, &mut buffer);
+                if (buffer[0] & 1 == 1) != positive {
+                    if misclassified.len() == max_errors {
+                        return None;
+                    }
+                    if positive {
+                        misclassified.positives.push(idx);
+                    } else {
+                        misclassified.negatives.push(idx);
+                    }
+                }
+            }
+        }
+
+        Some(misclassified)
+    }
+}
+
+/// All the traces over the given variables, from length 1 up to `max_len`, by increasing length.
+/// States have room for every variable up to the largest one in `vars`, and those not in `vars` are always false.
+pub(crate) fn all_traces(vars: &[Idx], max_len: usize) -> impl Iterator<Item = DynTrace> {
+    let width = vars.iter().map(|var| *var as usize + 1).max().unwrap_or(0);
+    let states = (0..1usize << vars.len())
+        .map(|valuation| {
+            let mut state = vec![false; width];
+            for (bit, var) in vars.iter().enumerate() {
+                state[*var as usize] = valuation & (1 << bit) != 0;
+            }
+            state
+        })
+        .collect_vec();
+    (1..=max_len).flat_map(move |len| {
+        (0..len)
+            .map(|_| states.clone().into_iter())
+            .multi_cartesian_product()
+    })
+}
+
+/// Loads one of the samples shipped with the repository, for testing.
+#[cfg(test)]
+pub(crate) fn load_sample(path: &str) -> DynSample {
+    use std::io::Read;
+
+    let mut contents = Vec::new();
+    std::fs::File::open(path)
+        .expect("open sample")
+        .read_to_end(&mut contents)
+        .expect("read sample");
+    ron::de::from_bytes(&contents).expect("deserialize sample")
+}
+
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Sample<const N: usize> {
+    #[serde_as(as = "[_; N]")]
+    #[serde(default = "Sample::default_var_names")]
+    pub var_names: [String; N],
+    #[serde_as(as = "Vec<Vec<[_; N]>>")]
+    pub positive_traces: Vec<Trace<N>>,
+    #[serde_as(as = "Vec<Vec<[_; N]>>")]
+    pub negative_traces: Vec<Trace<N>>,
+}
+
+impl<const N: usize> Default for Sample<N> {
+    fn default() -> Self {
+        Sample {
+            var_names: Sample::default_var_names(),
+            positive_traces: Vec::default(),
+            negative_traces: Vec::default(),
+        }
+    }
+}
+
+impl<const N: usize> LabeledSample for Sample<N> {
+    type State = [bool; N];
+
+    fn var_names(&self) -> &[String] {
+        &self.var_names
+    }
+
+    fn positives(&self) -> &[Trace<N>] {
+        &self.positive_traces
+    }
+
+    fn negatives(&self) -> &[Trace<N>] {
+        &self.negative_traces
+    }
+}
+
+impl<const N: usize> Sample<N> {
+    fn default_var_names() -> [String; N] {
+        (0..N)
+            .map(|n| format!("x{n}"))
+            .collect::<Vec<_>>()
+            .try_into()
+            .expect("wrong size iterator")
+    }
+
+    // The methods below forward to `LabeledSample`, so that existing callers need not import the trait.
+
+    pub fn vars(&self) -> Vec<Idx> {
+        LabeledSample::vars(self)
+    }
+
+    pub fn is_solvable(&self) -> bool {
+        LabeledSample::is_solvable(self)
+    }
+
+    pub fn is_consistent(&self, formula: &SyntaxTree) -> bool {
+        LabeledSample::is_consistent(self, formula)
+    }
+
+    pub fn time_lenght(&self) -> Time {
+        LabeledSample::time_lenght(self)
     }
 
     // https://rust-lang.github.io/rust-clippy/master/index.html#result_unit_err
+    #[allow(clippy::result_unit_err)]
     pub fn add_positive_trace(&mut self, trace: Trace<N>) -> Result<(), ()> {
         if !self.negative_traces.contains(&trace) {
             if !self.positive_traces.contains(&trace) {
@@ -108,6 +367,7 @@ impl<const N: usize> Sample<N> {
     }
 
     // https://rust-lang.github.io/rust-clippy/master/index.html#result_unit_err
+    #[allow(clippy::result_unit_err)]
     pub fn add_negative_trace(&mut self, trace: Trace<N>) -> Result<(), ()> {
         if !self.positive_traces.contains(&trace) {
             if !self.negative_traces.contains(&trace) {
@@ -128,6 +388,206 @@ impl<const N: usize> Sample<N> {
     }
 }
 
+/// A sample whose number of propositional variables is discovered at load time,
+/// rather than fixed at compile time as for [`Sample`].
+///
+/// It is (de)serialized in the same format as [`Sample`],
+/// with the variable names defaulting to `x0`, `x1`, ... when missing.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DynSample {
+    pub var_names: Vec<String>,
+    pub positive_traces: Vec<DynTrace>,
+    pub negative_traces: Vec<DynTrace>,
+}
+
+impl LabeledSample for DynSample {
+    type State = Vec<bool>;
+
+    fn var_names(&self) -> &[String] {
+        &self.var_names
+    }
+
+    fn positives(&self) -> &[DynTrace] {
+        &self.positive_traces
+    }
+
+    fn negatives(&self) -> &[DynTrace] {
+        &self.negative_traces
+    }
+}
+
+impl DynSample {
+    /// Creates an empty sample over the given number of variables, named `x0`, `x1`, ...
+    pub fn new(vars: usize) -> Self {
+        DynSample {
+            var_names: Self::default_var_names(vars),
+            ..Default::default()
+        }
+    }
+
+    fn default_var_names(vars: usize) -> Vec<String> {
+        (0..vars).map(|n| format!("x{n}")).collect()
+    }
+
+    /// The number of propositional variables of the sample.
+    pub fn num_vars(&self) -> usize {
+        self.var_names.len()
+    }
+
+    // https://rust-lang.github.io/rust-clippy/master/index.html#result_unit_err
+    #[allow(clippy::result_unit_err)]
+    pub fn add_positive_trace(&mut self, trace: DynTrace) -> Result<(), ()> {
+        assert!(trace.iter().all(|state| state.len() == self.num_vars()));
+        if !self.negative_traces.contains(&trace) {
+            if !self.positive_traces.contains(&trace) {
+                self.positive_traces.push(trace);
+            }
+            Ok(())
+        } else {
+            Err(())
+        }
+    }
+
+    // https://rust-lang.github.io/rust-clippy/master/index.html#result_unit_err
+    #[allow(clippy::result_unit_err)]
+    pub fn add_negative_trace(&mut self, trace: DynTrace) -> Result<(), ()> {
+        assert!(trace.iter().all(|state| state.len() == self.num_vars()));
+        if !self.positive_traces.contains(&trace) {
+            if !self.negative_traces.contains(&trace) {
+                self.negative_traces.push(trace);
+            }
+            Ok(())
+        } else {
+            Err(())
+        }
+    }
+
+    pub fn positive_traces(&self) -> usize {
+        self.positive_traces.len()
+    }
+
+    pub fn negative_traces(&self) -> usize {
+        self.negative_traces.len()
+    }
+}
+
+impl<const N: usize> From<Sample<N>> for DynSample {
+    fn from(sample: Sample<N>) -> Self {
+        let into_dyn = |trace: Trace<N>| trace.into_iter().map(Vec::from).collect();
+        DynSample {
+            var_names: sample.var_names.into(),
+            positive_traces: sample.positive_traces.into_iter().map(into_dyn).collect(),
+            negative_traces: sample.negative_traces.into_iter().map(into_dyn).collect(),
+        }
+    }
+}
+
+/// A tuple of values of runtime length, (de)serialized as a tuple (as arrays `[T; N]` are)
+/// but also accepted as a sequence.
+pub(crate) struct Tuple<T>(pub(crate) Vec<T>);
+
+impl<T> Default for Tuple<T> {
+    fn default() -> Self {
+        Tuple(Vec::new())
+    }
+}
+
+impl<T: Serialize> Serialize for Tuple<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeTuple;
+
+        let mut tuple = serializer.serialize_tuple(self.0.len())?;
+        for val in &self.0 {
+            tuple.serialize_element(val)?;
+        }
+        tuple.end()
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Tuple<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct TupleVisitor<T>(std::marker::PhantomData<T>);
+
+        impl<'de, T: Deserialize<'de>> serde::de::Visitor<'de> for TupleVisitor<T> {
+            type Value = Tuple<T>;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a tuple")
+            }
+
+            fn visit_unit<E: serde::de::Error>(self) -> Result<Tuple<T>, E> {
+                Ok(Tuple(Vec::new()))
+            }
+
+            fn visit_seq<A: serde::de::SeqAccess<'de>>(
+                self,
+                mut seq: A,
+            ) -> Result<Tuple<T>, A::Error> {
+                let mut tuple = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+                while let Some(val) = seq.next_element()? {
+                    tuple.push(val);
+                }
+                Ok(Tuple(tuple))
+            }
+        }
+
+        // The length is not known in advance, so let the format decide.
+        deserializer.deserialize_any(TupleVisitor(std::marker::PhantomData))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Sample")]
+struct DynSampleRepr {
+    // Missing (or empty) names are replaced by the default ones.
+    #[serde(default)]
+    var_names: Tuple<String>,
+    positive_traces: Vec<Vec<Tuple<bool>>>,
+    negative_traces: Vec<Vec<Tuple<bool>>>,
+}
+
+impl Serialize for DynSample {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let into_repr = |trace: &DynTrace| trace.iter().cloned().map(Tuple).collect();
+        DynSampleRepr {
+            var_names: Tuple(self.var_names.clone()),
+            positive_traces: self.positive_traces.iter().map(into_repr).collect(),
+            negative_traces: self.negative_traces.iter().map(into_repr).collect(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for DynSample {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = DynSampleRepr::deserialize(deserializer)?;
+        let from_repr = |trace: Vec<Tuple<bool>>| trace.into_iter().map(|state| state.0).collect();
+        let positive_traces: Vec<DynTrace> =
+            repr.positive_traces.into_iter().map(from_repr).collect();
+        let negative_traces: Vec<DynTrace> =
+            repr.negative_traces.into_iter().map(from_repr).collect();
+
+        let mut states = positive_traces.iter().chain(&negative_traces).flatten();
+        let var_names = if repr.var_names.0.is_empty() {
+            Self::default_var_names(states.clone().next().map_or(0, Vec::len))
+        } else {
+            repr.var_names.0
+        };
+        if let Some(state) = states.find(|state| state.len() != var_names.len()) {
+            return Err(serde::de::Error::invalid_length(
+                state.len(),
+                &format!("{} variables", var_names.len()).as_str(),
+            ));
+        }
+
+        Ok(DynSample {
+            var_names,
+            positive_traces,
+            negative_traces,
+        })
+    }
+}
+
 #[cfg(test)]
 mod consistency {
     use std::sync::Arc;
@@ -141,7 +601,7 @@ mod consistency {
     #[test]
     fn and() {
         let sample = Sample {
-            var_names: Sample::var_names(),
+            var_names: Sample::default_var_names(),
             positive_traces: vec![vec![[true, true]]],
             negative_traces: vec![
                 vec![[false, true]],
@@ -154,4 +614,100 @@ mod consistency {
 
         assert!(sample.is_consistent(&formula));
     }
+
+    // Regression test: trace lengths used to be truncated to `u8`.
+    #[test]
+    fn long_traces() {
+        let mut positive_trace = vec![[false, false]; 300];
+        positive_trace[299] = [true, false];
+        let negative_trace = vec![[false, false]; 1000];
+        let sample = Sample {
+            positive_traces: vec![positive_trace],
+            negative_traces: vec![negative_trace],
+            ..Default::default()
+        };
+
+        assert_eq!(sample.time_lenght(), 1000);
+        assert!(sample.is_solvable());
+
+        let formula = SyntaxTree::Finally(Arc::new(ATOM_0));
+        assert!(sample.is_consistent(&formula));
+    }
+}
+
+#[cfg(test)]
+mod dyn_sample {
+    use std::sync::Arc;
+
+    use super::*;
+
+    #[test]
+    fn deserialize() {
+        let sample = ron::de::from_str::<DynSample>(
+            "(positive_traces: [[(true, true), (false, true)]], negative_traces: [[(false, false)]])",
+        )
+        .expect("deserialize sample");
+        assert_eq!(sample.var_names, vec!["x0", "x1"]);
+        assert_eq!(
+            sample.positive_traces,
+            vec![vec![vec![true, true], vec![false, true]]]
+        );
+        assert_eq!(sample.negative_traces, vec![vec![vec![false, false]]]);
+
+        let sample = serde_json::from_str::<DynSample>(
+            r#"{"var_names": ["a", "b", "c"], "positive_traces": [[[true, true, false]]], "negative_traces": []}"#,
+        )
+        .expect("deserialize sample");
+        assert_eq!(sample.num_vars(), 3);
+        assert_eq!(sample.var_names, vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn wrong_number_of_vars() {
+        assert!(ron::de::from_str::<DynSample>(
+            "(positive_traces: [[(true, true), (false)]], negative_traces: [])",
+        )
+        .is_err());
+        assert!(ron::de::from_str::<DynSample>(
+            r#"(var_names: ["a"], positive_traces: [[(true, true)]], negative_traces: [])"#,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn same_format_as_sample() {
+        let sample = Sample {
+            positive_traces: vec![vec![[true, false, true]]],
+            negative_traces: vec![vec![[false, false, true], [true, true, true]]],
+            ..Default::default()
+        };
+        let ron = ron::ser::to_string(&sample).expect("serialize sample");
+        let dyn_sample = DynSample::from(sample);
+        assert_eq!(
+            ron::ser::to_string(&dyn_sample).expect("serialize sample"),
+            ron
+        );
+        assert_eq!(ron::de::from_str::<DynSample>(&ron), Ok(dyn_sample));
+        assert!(ron::de::from_str::<Sample<3>>(&ron).is_ok());
+    }
+
+    #[test]
+    fn many_vars() {
+        const VARS: usize = 300;
+
+        let mut sample = DynSample::new(VARS);
+        let mut positive_state = vec![false; VARS];
+        positive_state[VARS - 1] = true;
+        sample
+            .add_positive_trace(vec![positive_state])
+            .expect("add positive trace");
+        sample
+            .add_negative_trace(vec![vec![false; VARS]])
+            .expect("add negative trace");
+
+        assert!(sample.is_solvable());
+        let formula = SyntaxTree::Atom(VARS as Idx - 1);
+        assert!(sample.is_consistent(&formula));
+        assert!(!sample.is_consistent(&SyntaxTree::Not(Arc::new(formula))));
+    }
 }